@@ -0,0 +1,252 @@
+//! Benchmarks of the timestamp search against simulated chains
+//!
+//! Every search runs against an in-memory chain, so results only depend on the
+//! algorithm. Besides the time per search, the mean number of block time
+//! probes and binary search iterations per search is printed for each chain
+//! shape, which is what a real search pays for in RPC calls:
+//!
+//! ```text
+//! cargo bench --bench search
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+use criterion::{criterion_group, criterion_main, Criterion};
+use serde_json::Value;
+use tokio::runtime::Runtime;
+
+use solana_block_finder::backend::BackendFuture;
+use solana_block_finder::epoch::EpochSchedule;
+use solana_block_finder::rpc::{AccountInfo, BlockInfo, EpochInfo, PerformanceSample, RpcError, SignatureInfo, VersionInfo, VoteAccounts};
+use solana_block_finder::{BlockFinder, Commitment, NoCache, RpcBackend, Slot, UnixTs};
+
+/// Slots of every simulated chain
+const CHAIN_SLOTS: u64 = 2_000_000;
+
+/// Block time of slot 0
+const FIRST_BLOCK_TIME: i64 = 1_600_000_000;
+
+/// Targets searched per chain, spread evenly over its history
+const TARGETS: usize = 64;
+
+/// A chain held in memory: the block time of every slot, `None` for skipped slots
+struct SimulatedChain {
+    block_times: Vec<Option<UnixTs>>,
+    endpoints: Vec<String>,
+}
+
+impl SimulatedChain {
+    fn new(name: &str, block_time: impl Fn(u64) -> Option<i64>) -> Self {
+        SimulatedChain {
+            block_times: (0..CHAIN_SLOTS).map(|slot| block_time(slot).map(UnixTs)).collect(),
+            endpoints: vec![format!("simulated:{}", name)],
+        }
+    }
+    
+    /// A block in every slot, 400 ms apart
+    fn dense() -> Self {
+        Self::new("dense", |slot| Some(nominal_time(slot)))
+    }
+    
+    /// A third of the slots skipped, in runs of up to a few slots
+    fn gappy() -> Self {
+        Self::new("gappy", |slot| (!mix(slot / 4).is_multiple_of(2) || mix(slot).is_multiple_of(3)).then(|| nominal_time(slot)))
+    }
+    
+    /// Normal production with two cluster halts: no blocks for 20 000 slots, and the first
+    /// block after each halt hours later than the slot numbers suggest
+    fn outages() -> Self {
+        let halts = [(600_000, 6 * 3_600), (1_400_000, 19 * 3_600)];
+        Self::new("outages", move |slot| {
+            let mut delay = 0;
+            for (start, duration) in halts {
+                if (start..start + 20_000).contains(&slot) {
+                    return None;
+                }
+                if slot >= start {
+                    delay += duration;
+                }
+            }
+            Some(nominal_time(slot) + delay)
+        })
+    }
+    
+    fn tip(&self) -> Slot {
+        Slot(CHAIN_SLOTS - 1)
+    }
+    
+    fn block_time(&self, slot: Slot) -> Option<UnixTs> {
+        self.block_times.get(slot.0 as usize).copied().flatten()
+    }
+    
+    fn produced(&self, start_slot: Slot) -> impl Iterator<Item = Slot> + '_ {
+        (start_slot.0..CHAIN_SLOTS).map(Slot).filter(|slot| self.block_time(*slot).is_some())
+    }
+    
+    /// Timestamps evenly spread from the first to the last block
+    fn targets(&self) -> Vec<UnixTs> {
+        let first = self.block_time(Slot(0)).unwrap_or(UnixTs(FIRST_BLOCK_TIME));
+        let last = self.block_times.iter().rev().flatten().next().copied().unwrap_or(first);
+        (0..TARGETS).map(|i| first + (last - first) * i as i64 / TARGETS as i64).collect()
+    }
+}
+
+fn nominal_time(slot: u64) -> i64 {
+    FIRST_BLOCK_TIME + (slot * 2 / 5) as i64
+}
+
+/// SplitMix64, a fixed pseudo-random pattern of skipped slots
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn not_simulated<T>(method: &'static str) -> BackendFuture<'static, T> {
+    Box::pin(async move { Err(format!("{} is not simulated", method).into()) })
+}
+
+impl RpcBackend for SimulatedChain {
+    fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+    
+    fn get_slot(&self, _commitment: Commitment) -> BackendFuture<'_, Slot> {
+        Box::pin(async move { Ok(self.tip()) })
+    }
+    
+    fn get_block_time(&self, slot: Slot) -> BackendFuture<'_, Option<UnixTs>> {
+        Box::pin(async move { Ok(self.block_time(slot)) })
+    }
+    
+    fn get_block_info(&self, slot: Slot, _include_rewards: bool, _commitment: Commitment) -> BackendFuture<'_, BlockInfo> {
+        Box::pin(async move {
+            let Some(block_time) = self.block_time(slot) else {
+                return Err(RpcError { code: -32007, message: format!("Slot {} was skipped", slot) }.into());
+            };
+            let parent_slot = (0..slot.0).rev().map(Slot).find(|parent| self.block_time(*parent).is_some()).unwrap_or(slot);
+            Ok(BlockInfo {
+                blockhash: format!("simulated-{}", slot),
+                parent_slot,
+                block_time: Some(block_time),
+                block_height: None,
+                rewards: Vec::new(),
+            })
+        })
+    }
+    
+    fn get_full_block(&self, _slot: Slot, _commitment: Commitment) -> BackendFuture<'_, Value> {
+        not_simulated("getBlock")
+    }
+    
+    fn get_epoch_schedule(&self) -> BackendFuture<'_, EpochSchedule> {
+        not_simulated("getEpochSchedule")
+    }
+    
+    fn get_epoch_info(&self, _commitment: Commitment) -> BackendFuture<'_, EpochInfo> {
+        not_simulated("getEpochInfo")
+    }
+    
+    fn get_version(&self) -> BackendFuture<'_, VersionInfo> {
+        not_simulated("getVersion")
+    }
+    
+    fn get_genesis_hash(&self) -> BackendFuture<'_, String> {
+        not_simulated("getGenesisHash")
+    }
+    
+    fn get_first_available_block(&self) -> BackendFuture<'_, Slot> {
+        Box::pin(async move { Ok(Slot(0)) })
+    }
+    
+    fn get_blocks_with_limit(&self, start_slot: Slot, limit: u64, _commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        Box::pin(async move { Ok(self.produced(start_slot).take(limit as usize).collect()) })
+    }
+    
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot, _commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        Box::pin(async move { Ok(self.produced(start_slot).take_while(|slot| *slot <= end_slot).collect()) })
+    }
+    
+    fn get_slot_leaders(&self, _start_slot: Slot, _limit: u64) -> BackendFuture<'_, Vec<String>> {
+        not_simulated("getSlotLeaders")
+    }
+    
+    fn get_transaction_slot<'a>(&'a self, _signature: &'a str, _commitment: Commitment) -> BackendFuture<'a, (Slot, Option<UnixTs>)> {
+        not_simulated("getTransaction")
+    }
+    
+    fn get_signatures_for_address<'a>(
+        &'a self,
+        _address: &'a str,
+        _before: Option<&'a str>,
+        _min_context_slot: Option<Slot>,
+        _limit: usize,
+        _commitment: Commitment,
+    ) -> BackendFuture<'a, Vec<SignatureInfo>> {
+        not_simulated("getSignaturesForAddress")
+    }
+    
+    fn get_account_info<'a>(&'a self, _address: &'a str, _commitment: Commitment) -> BackendFuture<'a, Option<AccountInfo>> {
+        not_simulated("getAccountInfo")
+    }
+    
+    fn get_recent_performance_samples(&self, _limit: usize) -> BackendFuture<'_, Vec<PerformanceSample>> {
+        not_simulated("getRecentPerformanceSamples")
+    }
+    
+    fn get_vote_accounts(&self, _commitment: Commitment) -> BackendFuture<'_, VoteAccounts> {
+        not_simulated("getVoteAccounts")
+    }
+}
+
+/// A finder that starts every search from scratch: no cache, no anchors from earlier
+/// searches and no pauses between probes
+fn fresh_finder(chain: &Arc<SimulatedChain>) -> BlockFinder {
+    BlockFinder::builder()
+        .backend(chain.clone())
+        .cache(Arc::new(NoCache))
+        .probe_delay(Duration::ZERO)
+        .scan_delay(Duration::ZERO)
+        .build()
+        .expect("simulated finder")
+}
+
+/// Prints the mean probes and iterations of searching every target once
+fn report_cost(runtime: &Runtime, name: &str, chain: &Arc<SimulatedChain>, targets: &[UnixTs]) {
+    let (mut probes, mut iterations) = (0, 0);
+    for target in targets {
+        let found = runtime.block_on(fresh_finder(chain).find_block_by_timestamp(*target)).expect("search");
+        let diagnostics = found.diagnostics.unwrap_or_default();
+        probes += diagnostics.probes;
+        iterations += u64::from(diagnostics.iterations);
+    }
+    let searches = targets.len() as f64;
+    println!("{}: {:.1} probes and {:.1} iterations per search", name, probes as f64 / searches, iterations as f64 / searches);
+}
+
+fn search_benchmarks(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("tokio runtime");
+    let chains = [
+        ("dense", Arc::new(SimulatedChain::dense())),
+        ("gappy", Arc::new(SimulatedChain::gappy())),
+        ("outages", Arc::new(SimulatedChain::outages())),
+    ];
+    
+    let mut group = c.benchmark_group("find_block_by_timestamp");
+    for (name, chain) in &chains {
+        let targets = chain.targets();
+        report_cost(&runtime, name, chain, &targets);
+        let mut next = targets.iter().cycle();
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                let target = *next.next().expect("targets");
+                runtime.block_on(fresh_finder(chain).find_block_by_timestamp(target)).expect("search")
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, search_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+//! Pluggable RPC backends
+//!
+//! [`BlockFinder`](crate::BlockFinder) talks to the cluster through an
+//! [`RpcBackend`]. The default is [`HttpBackend`](crate::rpc::HttpBackend),
+//! a small hand-rolled JSON-RPC client. With the `solana-client` feature,
+//! [`SolanaClientBackend`](crate::solana_client::SolanaClientBackend) uses
+//! the official `solana-rpc-client` crate instead.
+//!
+//! Caching and the concurrency limit are applied by the finder, so a backend
+//! only has to answer requests.
+
+use std::error::Error;
+use serde_json::Value;
+
+use crate::epoch::EpochSchedule;
+use crate::finder::Commitment;
+use crate::rpc::{AccountInfo, BlockInfo, EpochInfo, PerformanceSample, SignatureInfo, VersionInfo, VoteAccounts};
+use crate::types::{EndpointStats, Slot, UnixTs};
+
+/// Future returned by [`RpcBackend`] methods
+#[cfg(not(target_arch = "wasm32"))]
+pub type BackendFuture<'a, T> = futures::future::BoxFuture<'a, Result<T, Box<dyn Error>>>;
+
+/// Future returned by [`RpcBackend`] methods (browser futures are not `Send`)
+#[cfg(target_arch = "wasm32")]
+pub type BackendFuture<'a, T> = futures::future::LocalBoxFuture<'a, Result<T, Box<dyn Error>>>;
+
+/// The Solana RPC methods the finder needs
+pub trait RpcBackend: Send + Sync {
+    /// Endpoints this backend talks to, for display
+    fn endpoints(&self) -> &[String];
+    
+    /// Requests sent to each endpoint so far. Backends that do not count them return nothing.
+    fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        Vec::new()
+    }
+    
+    fn get_slot(&self, commitment: Commitment) -> BackendFuture<'_, Slot>;
+    
+    /// Returns `None` when the slot has no block time (skipped or not available).
+    fn get_block_time(&self, slot: Slot) -> BackendFuture<'_, Option<UnixTs>>;
+    
+    /// Fetches a block without transactions, with its rewards when `include_rewards` is set.
+    fn get_block_info(&self, slot: Slot, include_rewards: bool, commitment: Commitment) -> BackendFuture<'_, BlockInfo>;
+    
+    /// Fetches a block with full transaction details and rewards, as returned by `getBlock`.
+    fn get_full_block(&self, slot: Slot, commitment: Commitment) -> BackendFuture<'_, Value>;
+    
+    fn get_epoch_schedule(&self) -> BackendFuture<'_, EpochSchedule>;
+    
+    fn get_epoch_info(&self, commitment: Commitment) -> BackendFuture<'_, EpochInfo>;
+    
+    fn get_version(&self) -> BackendFuture<'_, VersionInfo>;
+    
+    /// Hash of the cluster's genesis block, which identifies the cluster
+    fn get_genesis_hash(&self) -> BackendFuture<'_, String>;
+    
+    /// Oldest block still available, 0 on archival nodes
+    fn get_first_available_block(&self) -> BackendFuture<'_, Slot>;
+    
+    fn get_blocks_with_limit(&self, start_slot: Slot, limit: u64, commitment: Commitment) -> BackendFuture<'_, Vec<Slot>>;
+    
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot, commitment: Commitment) -> BackendFuture<'_, Vec<Slot>>;
+    
+    /// Fetches the leader identity of each of the `limit` slots starting at `start_slot`.
+    fn get_slot_leaders(&self, start_slot: Slot, limit: u64) -> BackendFuture<'_, Vec<String>>;
+    
+    /// Resolves a transaction signature to its slot and, when known, its block time.
+    fn get_transaction_slot<'a>(&'a self, signature: &'a str, commitment: Commitment) -> BackendFuture<'a, (Slot, Option<UnixTs>)>;
+    
+    /// Fetches one page of signatures for an address, newest first.
+    fn get_signatures_for_address<'a>(
+        &'a self,
+        address: &'a str,
+        before: Option<&'a str>,
+        min_context_slot: Option<Slot>,
+        limit: usize,
+        commitment: Commitment,
+    ) -> BackendFuture<'a, Vec<SignatureInfo>>;
+    
+    /// Fetches an account with `jsonParsed` encoding, `None` if it does not exist.
+    fn get_account_info<'a>(&'a self, address: &'a str, commitment: Commitment) -> BackendFuture<'a, Option<AccountInfo>>;
+    
+    /// Fetches up to `limit` recent performance samples, newest first.
+    fn get_recent_performance_samples(&self, limit: usize) -> BackendFuture<'_, Vec<PerformanceSample>>;
+    
+    /// Fetches the vote accounts of the cluster, current and delinquent.
+    fn get_vote_accounts(&self, commitment: Commitment) -> BackendFuture<'_, VoteAccounts>;
+}
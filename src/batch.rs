@@ -0,0 +1,113 @@
+//! Concurrent resolution of many timestamps
+//!
+//! Every search of a batch runs on the same [`BlockFinder`], so all their
+//! probes go through one concurrency limit, one set of per-endpoint rate
+//! limits and one connection pool. They also share the block time cache,
+//! in-flight request coalescing and the discovered anchors, which makes
+//! nearby timestamps cheap to resolve together.
+//!
+//! Sorted timestamps are cheaper still: each answer is a lower bound for the
+//! next search, so [`BlockFinder::find_blocks_by_sorted_timestamps`] runs
+//! every search after the first of a run in the few slots past the previous
+//! answer, and a day of backfill costs little more than a handful of cold
+//! searches.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use futures::future::join_all;
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::finder::BlockFinder;
+use crate::types::{BlockMatch, Slot, UnixTs};
+
+/// Default number of searches a batch runs at once
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Fewest slots either side of the expected slot that a sorted batch probes
+const MIN_FORWARD_SLOTS: u64 = 64;
+
+impl BlockFinder {
+    /// Resolves every timestamp like [`BlockFinder::find_block_by_timestamp`], running up to
+    /// `concurrency` searches at once. Results are yielded in input order, each paired with
+    /// its timestamp; a failed search does not stop the others.
+    ///
+    /// More searches in flight only help until the finder's own concurrency limit and the
+    /// providers' rate limits are saturated, since every probe waits for those.
+    pub fn find_blocks_by_timestamps<I>(&self, timestamps: I, concurrency: usize) -> impl Stream<Item = (UnixTs, Result<BlockMatch, Box<dyn Error>>)> + '_
+    where
+        I: IntoIterator<Item = UnixTs>,
+        I::IntoIter: 'static,
+    {
+        stream::iter(timestamps)
+            .map(move |target_timestamp| async move {
+                (target_timestamp, self.find_block_by_timestamp(target_timestamp).await)
+            })
+            .buffered(concurrency.max(1))
+    }
+    
+    /// Resolves every timestamp like [`BlockFinder::find_blocks_by_timestamps`], but in ascending
+    /// order, carrying each answer forward as the lower bound of the next search. The sorted
+    /// timestamps are split into `concurrency` runs that are resolved at the same time; within a
+    /// run, only the first search starts cold.
+    ///
+    /// Unsorted timestamps are sorted first. Results are still yielded in input order, so for
+    /// unsorted input most of them only arrive once the searches before them in time are done.
+    pub fn find_blocks_by_sorted_timestamps(&self, timestamps: Vec<UnixTs>, concurrency: usize) -> impl Stream<Item = (UnixTs, Result<BlockMatch, Box<dyn Error>>)> + '_ {
+        let concurrency = concurrency.max(1);
+        let mut order: Vec<usize> = (0..timestamps.len()).collect();
+        order.sort_by_key(|index| timestamps[*index]);
+        let run_len = timestamps.len().div_ceil(concurrency).max(1);
+        let runs: Vec<Vec<(usize, UnixTs)>> = order.chunks(run_len)
+            .map(|run| run.iter().map(|index| (*index, timestamps[*index])).collect())
+            .collect();
+        
+        // Runs finish in time order; hold results back until every earlier input line is out
+        let mut pending = BTreeMap::new();
+        let mut next_index = 0;
+        stream::iter(runs)
+            .map(move |run| self.resolve_ascending(run))
+            .buffered(concurrency)
+            .flat_map(move |resolved| {
+                pending.extend(resolved);
+                let mut ready = Vec::new();
+                while let Some(result) = pending.remove(&next_index) {
+                    ready.push(result);
+                    next_index += 1;
+                }
+                stream::iter(ready)
+            })
+    }
+    
+    /// Resolves a run of ascending timestamps one after another, keyed by their input index
+    async fn resolve_ascending(&self, run: Vec<(usize, UnixTs)>) -> Vec<(usize, (UnixTs, Result<BlockMatch, Box<dyn Error>>))> {
+        let mut resolved = Vec::with_capacity(run.len());
+        let mut previous: Option<(Slot, UnixTs)> = None;
+        for (index, target_timestamp) in run {
+            let result = match previous {
+                Some((slot, block_time)) => self.find_block_after(target_timestamp, slot, block_time).await,
+                None => self.find_block_by_timestamp(target_timestamp).await,
+            };
+            // An approximate answer is no bound for later targets
+            if let Ok(BlockMatch { slot, block_time: Some(block_time), approximate: false, .. }) = &result {
+                previous = Some((*slot, *block_time));
+            }
+            resolved.push((index, (target_timestamp, result)));
+        }
+        resolved
+    }
+    
+    /// Searches for `target_timestamp` from `previous_slot`, the answer for an earlier timestamp.
+    /// The slots [`MIN_FORWARD_SLOTS`] or a sixteenth of the distance either side of where the
+    /// calibration expects the target are probed first; their block times land in the discovery
+    /// map, which narrows the search to the window between them.
+    async fn find_block_after(&self, target_timestamp: UnixTs, previous_slot: Slot, previous_time: UnixTs) -> Result<BlockMatch, Box<dyn Error>> {
+        let current_slot = self.get_current_slot().await?;
+        let distance = self.calibration.slots_in(previous_slot, target_timestamp - previous_time, &self.chain);
+        let expected = previous_slot.saturating_add(distance);
+        let margin = (distance / 16).max(MIN_FORWARD_SLOTS);
+        let edges = [expected.saturating_sub(margin).max(previous_slot), expected.saturating_add(margin).min(current_slot)];
+        // Failed or skipped edges just leave the window wider
+        join_all(edges.map(|slot| self.get_block_time(slot))).await;
+        self.find_block_by_timestamp_in_range(target_timestamp, previous_slot, current_slot).await
+    }
+}
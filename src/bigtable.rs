@@ -0,0 +1,209 @@
+//! [`RpcBackend`] reading history from a warehouse node's Bigtable
+//!
+//! Enabled with the `bigtable` feature. Warehouse nodes upload every
+//! confirmed block to Google Bigtable in the canonical `solana-ledger`
+//! schema. [`BigtableBackend`] reads block times, hashes and the produced
+//! slots of a range straight from there through `solana-storage-bigtable`,
+//! so historical searches make no RPC calls.
+//!
+//! Bigtable has no notion of the current tip and lags behind the cluster, so
+//! the backend wraps an RPC backend for the current slot, for blocks not
+//! uploaded yet and for every other lookup.
+
+use std::error::Error;
+use std::sync::Arc;
+use serde_json::Value;
+use solana_storage_bigtable::{CredentialType, Error as BigtableError, LedgerStorage, LedgerStorageConfig, DEFAULT_INSTANCE_NAME};
+
+use crate::backend::{BackendFuture, RpcBackend};
+use crate::epoch::EpochSchedule;
+use crate::finder::Commitment;
+use crate::rpc::{AccountInfo, BlockInfo, EpochInfo, PerformanceSample, Reward, RpcError, SignatureInfo, VersionInfo, VoteAccounts};
+use crate::types::{EndpointStats, Slot, UnixTs};
+
+/// Backend answering block lookups from Bigtable and the rest from `rpc`
+pub struct BigtableBackend {
+    storage: LedgerStorage,
+    rpc: Arc<dyn RpcBackend>,
+    endpoints: Vec<String>,
+}
+
+impl BigtableBackend {
+    /// Connects read-only to the default `solana-ledger` instance. Without `credential_path`
+    /// the service account key in `GOOGLE_APPLICATION_CREDENTIALS` is used.
+    pub async fn connect(credential_path: Option<String>, rpc: Arc<dyn RpcBackend>) -> Result<Self, Box<dyn Error>> {
+        let config = LedgerStorageConfig {
+            read_only: true,
+            credential_type: CredentialType::Filepath(credential_path),
+            ..LedgerStorageConfig::default()
+        };
+        let storage = LedgerStorage::new_with_config(config).await?;
+        Ok(Self::new(storage, DEFAULT_INSTANCE_NAME, rpc))
+    }
+    
+    /// Wraps a connection that is already set up, e.g. to another instance or an emulator.
+    pub fn new(storage: LedgerStorage, instance_name: &str, rpc: Arc<dyn RpcBackend>) -> Self {
+        let mut endpoints = vec![format!("bigtable:{}", instance_name)];
+        endpoints.extend(rpc.endpoints().iter().cloned());
+        BigtableBackend { storage, rpc, endpoints }
+    }
+    
+    /// The block in `slot` from Bigtable, or the slot back if it was skipped. `Ok(None)` means
+    /// Bigtable cannot tell yet, because nothing after the slot has been uploaded.
+    async fn block(&self, slot: Slot, include_rewards: bool) -> Result<Option<Result<BlockInfo, Slot>>, Box<dyn Error>> {
+        let block = match self.storage.get_confirmed_block(slot.0).await {
+            Ok(block) => block,
+            Err(BigtableError::BlockNotFound(_)) => {
+                // A later block means the slot was skipped
+                let next = self.storage.get_confirmed_blocks(slot.0, 1).await?;
+                return Ok(next.first().map(|_| Err(slot)));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let rewards = if include_rewards { block.rewards } else { Vec::new() };
+        Ok(Some(Ok(BlockInfo {
+            blockhash: block.blockhash,
+            parent_slot: Slot(block.parent_slot),
+            block_time: block.block_time.map(UnixTs),
+            block_height: block.block_height,
+            rewards: rewards
+                .into_iter()
+                .map(|reward| Reward {
+                    pubkey: reward.pubkey,
+                    lamports: reward.lamports,
+                    post_balance: reward.post_balance,
+                    reward_type: reward.reward_type.map(|t| t.to_string()),
+                    commission: reward.commission,
+                })
+                .collect(),
+        })))
+    }
+}
+
+/// Reports a skipped slot with the error code an RPC node uses, so the search probes around it
+fn skipped_slot_error(slot: Slot) -> Box<dyn Error> {
+    Box::new(RpcError {
+        code: -32007,
+        message: format!("Slot {} was skipped", slot),
+    })
+}
+
+impl RpcBackend for BigtableBackend {
+    fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+    
+    fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        self.rpc.endpoint_stats()
+    }
+    
+    fn get_slot(&self, commitment: Commitment) -> BackendFuture<'_, Slot> {
+        self.rpc.get_slot(commitment)
+    }
+    
+    fn get_block_time(&self, slot: Slot) -> BackendFuture<'_, Option<UnixTs>> {
+        Box::pin(async move {
+            let block = self.block(slot, false).await?;
+            match block {
+                Some(Ok(block)) => Ok(block.block_time),
+                Some(Err(_)) => Ok(None),
+                None => self.rpc.get_block_time(slot).await,
+            }
+        })
+    }
+    
+    fn get_block_info(&self, slot: Slot, include_rewards: bool, commitment: Commitment) -> BackendFuture<'_, BlockInfo> {
+        Box::pin(async move {
+            let block = self.block(slot, include_rewards).await?;
+            match block {
+                Some(Ok(block)) => Ok(block),
+                Some(Err(slot)) => Err(skipped_slot_error(slot)),
+                None => self.rpc.get_block_info(slot, include_rewards, commitment).await,
+            }
+        })
+    }
+    
+    fn get_full_block(&self, slot: Slot, commitment: Commitment) -> BackendFuture<'_, Value> {
+        self.rpc.get_full_block(slot, commitment)
+    }
+    
+    fn get_epoch_schedule(&self) -> BackendFuture<'_, EpochSchedule> {
+        self.rpc.get_epoch_schedule()
+    }
+    
+    fn get_epoch_info(&self, commitment: Commitment) -> BackendFuture<'_, EpochInfo> {
+        self.rpc.get_epoch_info(commitment)
+    }
+    
+    fn get_version(&self) -> BackendFuture<'_, VersionInfo> {
+        self.rpc.get_version()
+    }
+    
+    fn get_genesis_hash(&self) -> BackendFuture<'_, String> {
+        self.rpc.get_genesis_hash()
+    }
+    
+    fn get_first_available_block(&self) -> BackendFuture<'_, Slot> {
+        Box::pin(async move {
+            match self.storage.get_first_available_block().await? {
+                Some(slot) => Ok(Slot(slot)),
+                None => self.rpc.get_first_available_block().await,
+            }
+        })
+    }
+    
+    fn get_blocks_with_limit(&self, start_slot: Slot, limit: u64, commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        Box::pin(async move {
+            // One more than asked for shows whether the uploads reach past the last block
+            let count = usize::try_from(limit)?;
+            let mut slots = self.storage.get_confirmed_blocks(start_slot.0, count.saturating_add(1)).await?;
+            if slots.len() <= count {
+                return self.rpc.get_blocks_with_limit(start_slot, limit, commitment).await;
+            }
+            slots.truncate(count);
+            Ok(slots.into_iter().map(Slot).collect())
+        })
+    }
+    
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot, commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        Box::pin(async move {
+            let count = usize::try_from(end_slot.0.saturating_sub(start_slot.0).saturating_add(1))?;
+            let slots = self.storage.get_confirmed_blocks(start_slot.0, count.saturating_add(1)).await?;
+            if slots.last().is_none_or(|last| *last <= end_slot.0) {
+                return self.rpc.get_blocks(start_slot, end_slot, commitment).await;
+            }
+            Ok(slots.into_iter().take_while(|slot| *slot <= end_slot.0).map(Slot).collect())
+        })
+    }
+    
+    fn get_slot_leaders(&self, start_slot: Slot, limit: u64) -> BackendFuture<'_, Vec<String>> {
+        self.rpc.get_slot_leaders(start_slot, limit)
+    }
+    
+    fn get_transaction_slot<'a>(&'a self, signature: &'a str, commitment: Commitment) -> BackendFuture<'a, (Slot, Option<UnixTs>)> {
+        self.rpc.get_transaction_slot(signature, commitment)
+    }
+    
+    fn get_signatures_for_address<'a>(
+        &'a self,
+        address: &'a str,
+        before: Option<&'a str>,
+        min_context_slot: Option<Slot>,
+        limit: usize,
+        commitment: Commitment,
+    ) -> BackendFuture<'a, Vec<SignatureInfo>> {
+        self.rpc.get_signatures_for_address(address, before, min_context_slot, limit, commitment)
+    }
+    
+    fn get_account_info<'a>(&'a self, address: &'a str, commitment: Commitment) -> BackendFuture<'a, Option<AccountInfo>> {
+        self.rpc.get_account_info(address, commitment)
+    }
+    
+    fn get_recent_performance_samples(&self, limit: usize) -> BackendFuture<'_, Vec<PerformanceSample>> {
+        self.rpc.get_recent_performance_samples(limit)
+    }
+    
+    fn get_vote_accounts(&self, commitment: Commitment) -> BackendFuture<'_, VoteAccounts> {
+        self.rpc.get_vote_accounts(commitment)
+    }
+}
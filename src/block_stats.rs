@@ -0,0 +1,223 @@
+//! Statistics over the transactions of a block
+//!
+//! The functions here read a block as returned by
+//! [`BlockFinder::get_full_block`](crate::BlockFinder::get_full_block), i.e.
+//! `getBlock` with full transaction details in `json` encoding. Vote
+//! transactions, which validators send for every slot they see, make up most
+//! of a block but never pay a priority fee, so fee statistics leave them out.
+//! Compute units count every transaction, since votes take up block space too.
+//!
+//! [`BlockFinder::find_first_invocation`] applies the same filtering to a range
+//! of blocks, to tell when a program was first called after some time.
+
+use std::error::Error;
+use futures::future::join_all;
+use serde_json::Value;
+
+use crate::finder::BlockFinder;
+use crate::types::{Slot, UnixTs};
+
+/// Lamports each signature of a transaction pays, on top of any priority fee
+pub const BASE_FEE_PER_SIGNATURE: u64 = 5000;
+
+/// Program of the vote transactions validators send
+pub const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
+
+/// Slots listed per `getBlocks` call while scanning for a program invocation
+const INVOCATION_SCAN_SLOTS: u64 = 1000;
+
+/// Full blocks fetched at once while scanning for a program invocation
+const INVOCATION_SCAN_BATCH: usize = 8;
+
+/// A transaction of a block that invokes a given program
+#[derive(Debug, Clone)]
+pub struct ProgramTransaction {
+    pub signature: String,
+    /// First signer, who pays the fees
+    pub fee_payer: String,
+    /// Error the transaction failed with, `None` when it succeeded
+    pub err: Option<Value>,
+}
+
+/// Transactions of a block, split into the vote transactions validators send and the rest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransactionCounts {
+    pub votes: usize,
+    pub non_votes: usize,
+}
+
+impl TransactionCounts {
+    pub fn total(&self) -> usize {
+        self.votes + self.non_votes
+    }
+}
+
+/// Fees paid by all transactions of a block, in lamports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockFees {
+    pub total: u64,
+    /// Part paid per signature
+    pub base: u64,
+    pub priority: u64,
+}
+
+/// The first transaction calling a program within a range of blocks
+#[derive(Debug, Clone)]
+pub struct FirstInvocation {
+    pub slot: Slot,
+    pub block_time: Option<UnixTs>,
+    pub transaction: ProgramTransaction,
+}
+
+/// Minimum, median and maximum of a value over some transactions of a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Distribution {
+    pub transactions: usize,
+    pub min: u64,
+    /// Mean of the two middle values for an even number of transactions
+    pub median: u64,
+    pub max: u64,
+    pub total: u64,
+}
+
+impl Distribution {
+    /// `None` without any values
+    pub fn of(mut values: Vec<u64>) -> Option<Self> {
+        values.sort_unstable();
+        let (&min, &max) = (values.first()?, values.last()?);
+        let middle = values.len() / 2;
+        let median = if values.len().is_multiple_of(2) { values[middle - 1].midpoint(values[middle]) } else { values[middle] };
+        Some(Distribution { transactions: values.len(), min, median, max, total: values.iter().sum() })
+    }
+}
+
+/// The transactions of `block`, none when it was fetched without them
+pub fn transactions(block: &Value) -> &[Value] {
+    block.get("transactions").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default()
+}
+
+/// Account keys of `transaction` by index: the message's own, then the writable and the
+/// read-only ones loaded from address lookup tables
+pub fn account_keys(transaction: &Value) -> Vec<&str> {
+    let loaded = &transaction["meta"]["loadedAddresses"];
+    [&transaction["transaction"]["message"]["accountKeys"], &loaded["writable"], &loaded["readonly"]]
+        .into_iter()
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+/// Whether `transaction` calls `program_id`, in one of its instructions or through a
+/// cross-program invocation
+pub fn invokes(transaction: &Value, program_id: &str) -> bool {
+    let account_keys = account_keys(transaction);
+    let inner_instructions = transaction["meta"]["innerInstructions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|inner| inner["instructions"].as_array())
+        .flatten();
+    transaction["transaction"]["message"]["instructions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .chain(inner_instructions)
+        .filter_map(|instruction| instruction["programIdIndex"].as_u64())
+        .any(|index| account_keys.get(index as usize) == Some(&program_id))
+}
+
+/// Whether `transaction` calls the vote program
+pub fn is_vote(transaction: &Value) -> bool {
+    invokes(transaction, VOTE_PROGRAM_ID)
+}
+
+/// Vote and non-vote transactions of `block`
+pub fn transaction_counts(block: &Value) -> TransactionCounts {
+    let votes = transactions(block).iter().filter(|transaction| is_vote(transaction)).count();
+    TransactionCounts { votes, non_votes: transactions(block).len() - votes }
+}
+
+/// Priority fee `transaction` paid, in lamports: its fee minus the base fee of its signatures
+pub fn priority_fee(transaction: &Value) -> Option<u64> {
+    let fee = transaction["meta"]["fee"].as_u64()?;
+    let signatures = transaction["transaction"]["signatures"].as_array()?.len() as u64;
+    Some(fee.saturating_sub(signatures * BASE_FEE_PER_SIGNATURE))
+}
+
+/// Priority fees paid by the non-vote transactions of `block`, `None` when it has none
+pub fn priority_fees(block: &Value) -> Option<Distribution> {
+    let fees = transactions(block)
+        .iter()
+        .filter(|transaction| !is_vote(transaction))
+        .filter_map(priority_fee)
+        .collect();
+    Distribution::of(fees)
+}
+
+/// Sum of `meta.fee` over all transactions of `block`, votes included
+pub fn total_fees(block: &Value) -> BlockFees {
+    let mut fees = BlockFees::default();
+    for transaction in transactions(block) {
+        let Some(fee) = transaction["meta"]["fee"].as_u64() else {
+            continue;
+        };
+        let priority = priority_fee(transaction).unwrap_or_default();
+        fees.total += fee;
+        fees.priority += priority;
+        fees.base += fee - priority;
+    }
+    fees
+}
+
+/// Compute units consumed by the transactions of `block`, `None` when it has no transactions
+/// or predates the `computeUnitsConsumed` field
+pub fn compute_units(block: &Value) -> Option<Distribution> {
+    let units = transactions(block)
+        .iter()
+        .filter_map(|transaction| transaction["meta"]["computeUnitsConsumed"].as_u64())
+        .collect();
+    Distribution::of(units)
+}
+
+/// The transactions of `block` that invoke `program_id`, in block order
+pub fn transactions_invoking(block: &Value, program_id: &str) -> Vec<ProgramTransaction> {
+    transactions(block)
+        .iter()
+        .filter(|transaction| invokes(transaction, program_id))
+        .map(|transaction| ProgramTransaction {
+            signature: transaction["transaction"]["signatures"][0].as_str().unwrap_or_default().to_string(),
+            fee_payer: account_keys(transaction).first().copied().unwrap_or_default().to_string(),
+            err: Some(transaction["meta"]["err"].clone()).filter(|err| !err.is_null()),
+        })
+        .collect()
+}
+
+impl BlockFinder {
+    /// Finds the first transaction invoking `program_id` between `from_slot` and `to_slot`,
+    /// inclusive, or `None` if no block in the range calls it.
+    ///
+    /// The produced slots are listed with `getBlocks` a thousand slots at a time, and their
+    /// blocks are fetched with full transactions a few at once, in slot order, so the scan
+    /// stops at the first block that calls the program. Every block up to that one is read in
+    /// full, which makes long ranges expensive.
+    pub async fn find_first_invocation(&self, program_id: &str, from_slot: Slot, to_slot: Slot) -> Result<Option<FirstInvocation>, Box<dyn Error>> {
+        let mut chunk_start = from_slot;
+        while chunk_start <= to_slot {
+            let chunk_end = chunk_start.saturating_add(INVOCATION_SCAN_SLOTS - 1).min(to_slot);
+            let slots = self.get_blocks(chunk_start, chunk_end).await?;
+            for batch in slots.chunks(INVOCATION_SCAN_BATCH) {
+                let blocks = join_all(batch.iter().map(|slot| self.get_full_block(*slot))).await;
+                for (slot, block) in batch.iter().zip(blocks) {
+                    let block = block?;
+                    if let Some(transaction) = transactions_invoking(&block, program_id).into_iter().next() {
+                        let block_time = block["blockTime"].as_i64().map(UnixTs);
+                        return Ok(Some(FirstInvocation { slot: *slot, block_time, transaction }));
+                    }
+                }
+            }
+            chunk_start = chunk_end + 1;
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,292 @@
+//! A blocking finder
+//!
+//! [`BlockFinder`] wraps the async [`crate::BlockFinder`] together with its
+//! own single-threaded Tokio runtime, the same way `reqwest::blocking`
+//! wraps the async client. Every method blocks the calling thread until the
+//! request or search completes.
+//!
+//! Like `reqwest::blocking`, this must not be used from within an async
+//! runtime; use the async finder there instead.
+//!
+//! ```no_run
+//! use solana_block_finder::blocking::BlockFinder;
+//! use solana_block_finder::UnixTs;
+//!
+//! let finder = BlockFinder::builder().api_key("your-api-key").build()?;
+//! let found = finder.find_block_by_timestamp(UnixTs(1750921805))?;
+//! println!("{} {}", found.slot, found.blockhash);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::error::Error;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::dns::Resolve;
+use reqwest::Client;
+use serde_json::Value;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::cache::{BlockTimeCache, DiscoveryMap};
+use crate::calibration::Calibration;
+use crate::cassette::Cassette;
+use crate::clock::Clock;
+use crate::epoch::EpochSchedule;
+use crate::chain::ChainProfile;
+use crate::finder::{Commitment, RateLimit, RetryPolicy};
+use crate::rpc::{BlockInfo, EpochInfo, VoteAccounts};
+use crate::search::{SearchProgress, TimestampUncertainty};
+use crate::types::{BlockMatch, Slot, UnixTs};
+
+/// Blocking counterpart of [`crate::BlockFinder`]
+pub struct BlockFinder {
+    inner: crate::BlockFinder,
+    runtime: Runtime,
+}
+
+impl BlockFinder {
+    pub fn builder() -> BlockFinderBuilder {
+        BlockFinderBuilder::default()
+    }
+    
+    /// Wraps an already configured async finder.
+    pub fn from_async(inner: crate::BlockFinder) -> Result<Self, Box<dyn Error>> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(BlockFinder { inner, runtime })
+    }
+    
+    /// The wrapped async finder
+    pub fn inner(&self) -> &crate::BlockFinder {
+        &self.inner
+    }
+    
+    pub fn endpoints(&self) -> &[String] {
+        self.inner.endpoints()
+    }
+    
+    pub fn rpc_calls(&self) -> u64 {
+        self.inner.rpc_calls()
+    }
+    
+    pub fn search_progress(&self) -> Option<SearchProgress> {
+        self.inner.search_progress()
+    }
+    
+    /// Finds the latest block at or before `target_timestamp` and fetches its details.
+    pub fn find_block_by_timestamp(&self, target_timestamp: UnixTs) -> Result<BlockMatch, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.find_block_by_timestamp(target_timestamp))
+    }
+    
+    pub fn find_block_by_timestamp_in_range(&self, target_timestamp: UnixTs, low_slot: Slot, high_slot: Slot) -> Result<BlockMatch, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.find_block_by_timestamp_in_range(target_timestamp, low_slot, high_slot))
+    }
+    
+    pub fn get_slot_by_timestamp_optimized(&self, target_timestamp: UnixTs) -> Result<Slot, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_slot_by_timestamp_optimized(target_timestamp))
+    }
+    
+    pub fn get_slot_by_timestamp_in_range(&self, target_timestamp: UnixTs, low_slot: Slot, high_slot: Slot) -> Result<Slot, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_slot_by_timestamp_in_range(target_timestamp, low_slot, high_slot))
+    }
+    
+    pub fn resume_slot_search(&self, progress: SearchProgress) -> Result<Slot, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.resume_slot_search(progress))
+    }
+    
+    pub fn get_timestamp_uncertainty(&self, slot: Slot, block_time: UnixTs) -> Result<TimestampUncertainty, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_timestamp_uncertainty(slot, block_time))
+    }
+    
+    pub fn get_current_slot(&self) -> Result<Slot, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_current_slot())
+    }
+    
+    pub fn is_finalized(&self, slot: Slot) -> Result<bool, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.is_finalized(slot))
+    }
+    
+    pub fn get_block_time(&self, slot: Slot) -> Result<Option<UnixTs>, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_block_time(slot))
+    }
+    
+    pub fn get_block_info(&self, slot: Slot, include_rewards: bool) -> Result<BlockInfo, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_block_info(slot, include_rewards))
+    }
+    
+    pub fn get_full_block(&self, slot: Slot) -> Result<Value, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_full_block(slot))
+    }
+    
+    pub fn get_epoch_schedule(&self) -> Result<EpochSchedule, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_epoch_schedule())
+    }
+    
+    pub fn get_epoch_info(&self) -> Result<EpochInfo, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_epoch_info())
+    }
+    
+    pub fn get_vote_accounts(&self) -> Result<VoteAccounts, Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_vote_accounts())
+    }
+    
+    pub fn get_transaction_slot(&self, signature: &str) -> Result<(Slot, Option<UnixTs>), Box<dyn Error>> {
+        self.runtime.block_on(self.inner.get_transaction_slot(signature))
+    }
+}
+
+/// Blocking counterpart of [`crate::BlockFinderBuilder`], with the same settings
+#[derive(Default)]
+pub struct BlockFinderBuilder {
+    inner: crate::BlockFinderBuilder,
+}
+
+impl BlockFinderBuilder {
+    pub fn endpoint(self, url: impl Into<String>) -> Self {
+        BlockFinderBuilder { inner: self.inner.endpoint(url) }
+    }
+    
+    pub fn endpoints<I, S>(self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        BlockFinderBuilder { inner: self.inner.endpoints(urls) }
+    }
+    
+    pub fn api_key(self, key: impl Into<String>) -> Self {
+        BlockFinderBuilder { inner: self.inner.api_key(key) }
+    }
+    
+    pub fn bearer_token(self, token: impl Into<String>) -> Self {
+        BlockFinderBuilder { inner: self.inner.bearer_token(token) }
+    }
+    
+    pub fn commitment(self, commitment: Commitment) -> Self {
+        BlockFinderBuilder { inner: self.inner.commitment(commitment) }
+    }
+    
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        BlockFinderBuilder { inner: self.inner.concurrency(concurrency) }
+    }
+    
+    pub fn rate_limit(self, url: impl Into<String>, limit: RateLimit) -> Self {
+        BlockFinderBuilder { inner: self.inner.rate_limit(url, limit) }
+    }
+    
+    pub fn retry_policy(self, retry: RetryPolicy) -> Self {
+        BlockFinderBuilder { inner: self.inner.retry_policy(retry) }
+    }
+    
+    pub fn discovery_map(self, discovery: Arc<DiscoveryMap>) -> Self {
+        BlockFinderBuilder { inner: self.inner.discovery_map(discovery) }
+    }
+    
+    pub fn cache(self, cache: Arc<dyn BlockTimeCache>) -> Self {
+        BlockFinderBuilder { inner: self.inner.cache(cache) }
+    }
+    
+    pub fn client(self, client: Client) -> Self {
+        BlockFinderBuilder { inner: self.inner.client(client) }
+    }
+    
+    pub fn remote_index(self, url: impl Into<String>) -> Self {
+        BlockFinderBuilder { inner: self.inner.remote_index(url) }
+    }
+    
+    pub fn cassette(self, cassette: Cassette) -> Self {
+        BlockFinderBuilder { inner: self.inner.cassette(cassette) }
+    }
+    
+    pub fn proxy(self, url: impl Into<String>) -> Self {
+        BlockFinderBuilder { inner: self.inner.proxy(url) }
+    }
+    
+    pub fn user_agent(self, user_agent: impl Into<String>) -> Self {
+        BlockFinderBuilder { inner: self.inner.user_agent(user_agent) }
+    }
+    
+    pub fn timeout(self, timeout: Duration) -> Self {
+        BlockFinderBuilder { inner: self.inner.timeout(timeout) }
+    }
+    
+    pub fn connect_timeout(self, timeout: Duration) -> Self {
+        BlockFinderBuilder { inner: self.inner.connect_timeout(timeout) }
+    }
+    
+    pub fn pool_max_idle_per_host(self, max: usize) -> Self {
+        BlockFinderBuilder { inner: self.inner.pool_max_idle_per_host(max) }
+    }
+    
+    pub fn pool_idle_timeout(self, timeout: Duration) -> Self {
+        BlockFinderBuilder { inner: self.inner.pool_idle_timeout(timeout) }
+    }
+    
+    pub fn tcp_keepalive(self, interval: Duration) -> Self {
+        BlockFinderBuilder { inner: self.inner.tcp_keepalive(interval) }
+    }
+    
+    pub fn clock(self, clock: Arc<dyn Clock>) -> Self {
+        BlockFinderBuilder { inner: self.inner.clock(clock) }
+    }
+    
+    pub fn tip_ttl(self, ttl: Duration) -> Self {
+        BlockFinderBuilder { inner: self.inner.tip_ttl(ttl) }
+    }
+    
+    pub fn probe_window(self, slots: u64) -> Self {
+        BlockFinderBuilder { inner: self.inner.probe_window(slots) }
+    }
+    
+    pub fn probe_delay(self, delay: Duration) -> Self {
+        BlockFinderBuilder { inner: self.inner.probe_delay(delay) }
+    }
+    
+    pub fn scan_delay(self, delay: Duration) -> Self {
+        BlockFinderBuilder { inner: self.inner.scan_delay(delay) }
+    }
+    
+    pub fn max_rpc_calls(self, calls: u64) -> Self {
+        BlockFinderBuilder { inner: self.inner.max_rpc_calls(calls) }
+    }
+    
+    pub fn deadline(self, deadline: Duration) -> Self {
+        BlockFinderBuilder { inner: self.inner.deadline(deadline) }
+    }
+    
+    pub fn max_iterations(self, iterations: u32) -> Self {
+        BlockFinderBuilder { inner: self.inner.max_iterations(iterations) }
+    }
+    
+    pub fn best_effort(self, best_effort: bool) -> Self {
+        BlockFinderBuilder { inner: self.inner.best_effort(best_effort) }
+    }
+    
+    pub fn lowest(self, lowest: bool) -> Self {
+        BlockFinderBuilder { inner: self.inner.lowest(lowest) }
+    }
+    
+    pub fn chain(self, chain: ChainProfile) -> Self {
+        BlockFinderBuilder { inner: self.inner.chain(chain) }
+    }
+    
+    pub fn calibration(self, calibration: Calibration) -> Self {
+        BlockFinderBuilder { inner: self.inner.calibration(calibration) }
+    }
+    
+    pub fn resolve(self, host: impl Into<String>, ip: IpAddr) -> Self {
+        BlockFinderBuilder { inner: self.inner.resolve(host, ip) }
+    }
+    
+    pub fn dns_resolver<R: Resolve + 'static>(self, resolver: Arc<R>) -> Self {
+        BlockFinderBuilder { inner: self.inner.dns_resolver(resolver) }
+    }
+    
+    pub fn checkpoint_file(self, path: impl Into<PathBuf>) -> Self {
+        BlockFinderBuilder { inner: self.inner.checkpoint_file(path) }
+    }
+    
+    pub fn build(self) -> Result<BlockFinder, Box<dyn Error>> {
+        BlockFinder::from_async(self.inner.build()?)
+    }
+}
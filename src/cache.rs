@@ -0,0 +1,344 @@
+//! Cache backends for block times
+//!
+//! Finalized block times never change, so every probe answered once can be
+//! answered again for free. The finder consults its cache before issuing a
+//! `getBlockTime` request.
+//!
+//! Slots close to the tip are the exception: a block that is not available
+//! yet, or a confirmed block time, can change within seconds. [`LruCache`],
+//! the default, keeps those entries only for a short TTL and bounds the
+//! number of entries.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock};
+use crate::types::{Slot, UnixTs};
+
+/// Default number of entries kept by [`LruCache`]
+pub const DEFAULT_CACHE_CAPACITY: usize = 100_000;
+
+/// Default distance from the tip within which [`LruCache`] entries expire, about a minute of slots
+pub const DEFAULT_NEAR_TIP_SLOTS: u64 = 150;
+
+/// Default lifetime of [`LruCache`] entries near the tip
+pub const DEFAULT_NEAR_TIP_TTL: Duration = Duration::from_secs(5);
+
+/// Storage for `slot → blockTime` lookups.
+///
+/// `None` as a cached value means the slot is known to have no block time
+/// (skipped or not available), which is just as useful to remember.
+pub trait BlockTimeCache: Send + Sync {
+    fn get(&self, slot: Slot) -> Option<Option<UnixTs>>;
+    fn insert(&self, slot: Slot, block_time: Option<UnixTs>);
+    
+    /// Called with every current slot the finder fetches, for caches that treat
+    /// slots near the tip differently
+    fn observe_tip(&self, _tip: Slot) {}
+}
+
+/// Cache that never stores anything
+#[derive(Debug, Default)]
+pub struct NoCache;
+
+impl BlockTimeCache for NoCache {
+    fn get(&self, _slot: Slot) -> Option<Option<UnixTs>> {
+        None
+    }
+    
+    fn insert(&self, _slot: Slot, _block_time: Option<UnixTs>) {}
+}
+
+/// Unbounded in-memory cache that keeps every entry forever
+#[derive(Debug, Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<Slot, Option<UnixTs>>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl BlockTimeCache for MemoryCache {
+    fn get(&self, slot: Slot) -> Option<Option<UnixTs>> {
+        self.entries.lock().ok()?.get(&slot).copied()
+    }
+    
+    fn insert(&self, slot: Slot, block_time: Option<UnixTs>) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(slot, block_time);
+        }
+    }
+}
+
+/// Bounded in-memory cache, the default backend.
+///
+/// Evicts the least recently used entry beyond `capacity` entries. Entries within
+/// `near_tip_slots` of the latest observed tip expire after `near_tip_ttl`, older ones
+/// are kept until evicted. Until a tip has been observed, every entry expires. The TTL
+/// runs on the system clock unless another one is set with [`LruCache::with_clock`]; the
+/// finder's default cache uses the finder's clock.
+pub struct LruCache {
+    capacity: usize,
+    near_tip_slots: u64,
+    near_tip_ttl: Duration,
+    clock: Arc<dyn Clock>,
+    state: Mutex<LruState>,
+}
+
+#[derive(Default)]
+struct LruState {
+    entries: HashMap<Slot, LruEntry>,
+    // Last use → slot, the first entry is the least recently used
+    recency: BTreeMap<u64, Slot>,
+    uses: u64,
+    tip: Option<Slot>,
+}
+
+struct LruEntry {
+    block_time: Option<UnixTs>,
+    last_use: u64,
+    expires_at_ms: Option<u64>,
+}
+
+impl LruCache {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+    
+    pub fn with_capacity(capacity: usize) -> Self {
+        LruCache {
+            capacity: capacity.max(1),
+            near_tip_slots: DEFAULT_NEAR_TIP_SLOTS,
+            near_tip_ttl: DEFAULT_NEAR_TIP_TTL,
+            clock: Arc::new(SystemClock::new()),
+            state: Mutex::new(LruState::default()),
+        }
+    }
+    
+    /// Sets how close to the tip entries expire, and after how long
+    pub fn near_tip(mut self, slots: u64, ttl: Duration) -> Self {
+        self.near_tip_slots = slots;
+        self.near_tip_ttl = ttl;
+        self
+    }
+    
+    /// Expires entries near the tip on `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    pub fn len(&self) -> usize {
+        self.state.lock().map_or(0, |state| state.entries.len())
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for LruCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LruState {
+    fn touch(&mut self, slot: Slot) {
+        self.uses += 1;
+        let uses = self.uses;
+        if let Some(entry) = self.entries.get_mut(&slot) {
+            self.recency.remove(&entry.last_use);
+            entry.last_use = uses;
+            self.recency.insert(uses, slot);
+        }
+    }
+    
+    fn remove(&mut self, slot: Slot) {
+        if let Some(entry) = self.entries.remove(&slot) {
+            self.recency.remove(&entry.last_use);
+        }
+    }
+}
+
+impl BlockTimeCache for LruCache {
+    fn get(&self, slot: Slot) -> Option<Option<UnixTs>> {
+        let mut state = self.state.lock().ok()?;
+        let entry = state.entries.get(&slot)?;
+        if entry.expires_at_ms.is_some_and(|expires| self.clock.elapsed_ms() >= expires) {
+            state.remove(slot);
+            return None;
+        }
+        let block_time = entry.block_time;
+        state.touch(slot);
+        Some(block_time)
+    }
+    
+    fn insert(&self, slot: Slot, block_time: Option<UnixTs>) {
+        let Ok(mut state) = self.state.lock() else { return };
+        let near_tip = state.tip.is_none_or(|tip| slot.0.saturating_add(self.near_tip_slots) > tip.0);
+        let expires_at_ms = near_tip.then(|| self.clock.elapsed_ms() + self.near_tip_ttl.as_millis() as u64);
+        
+        state.remove(slot);
+        state.entries.insert(slot, LruEntry { block_time, last_use: 0, expires_at_ms });
+        state.touch(slot);
+        
+        while state.entries.len() > self.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else { break };
+            state.entries.remove(&oldest);
+        }
+    }
+    
+    fn observe_tip(&self, tip: Slot) {
+        if let Ok(mut state) = self.state.lock() {
+            state.tip = Some(state.tip.map_or(tip, |known| known.max(tip)));
+        }
+    }
+}
+
+/// Every block time seen by a finder and its clones, ordered by time.
+///
+/// Block times grow with the slot, so a known block before the target and one after it
+/// bound where the target can be. Concurrent searches, e.g. the timestamps of a batch,
+/// use each other's probes this way to start from a narrower range.
+#[derive(Debug)]
+pub struct DiscoveryMap {
+    capacity: usize,
+    entries: Mutex<BTreeSet<(UnixTs, Slot)>>,
+}
+
+/// A known block: (slot, block time)
+pub type Anchor = (Slot, UnixTs);
+
+/// Default number of anchors kept by [`DiscoveryMap`]
+pub const DEFAULT_DISCOVERY_CAPACITY: usize = 1_000_000;
+
+impl DiscoveryMap {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_DISCOVERY_CAPACITY)
+    }
+    
+    pub fn with_capacity(capacity: usize) -> Self {
+        DiscoveryMap { capacity: capacity.max(2), entries: Mutex::new(BTreeSet::new()) }
+    }
+    
+    pub fn record(&self, slot: Slot, block_time: UnixTs) {
+        let Ok(mut entries) = self.entries.lock() else { return };
+        entries.insert((block_time, slot));
+        if entries.len() > self.capacity {
+            // Thin out every other anchor, which keeps them spread over the whole range
+            let mut keep = false;
+            entries.retain(|_| {
+                keep = !keep;
+                keep
+            });
+        }
+    }
+    
+    /// The latest known block before `target` and the earliest known block after it
+    pub fn bounds(&self, target: UnixTs) -> (Option<Anchor>, Option<Anchor>) {
+        let Ok(entries) = self.entries.lock() else { return (None, None) };
+        let before = entries.range(..(target, Slot(0))).next_back().map(|(time, slot)| (*slot, *time));
+        let after = entries.range((target + 1, Slot(0))..).next().map(|(time, slot)| (*slot, *time));
+        (before, after)
+    }
+    
+    pub fn len(&self) -> usize {
+        self.entries.lock().map_or(0, |entries| entries.len())
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for DiscoveryMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+    
+    fn cache_on(clock: &Arc<FakeClock>, capacity: usize) -> LruCache {
+        LruCache::with_capacity(capacity).with_clock(clock.clone())
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn evicts_the_least_recently_used_entry() {
+        let clock = Arc::new(FakeClock::new(UnixTs(1_750_000_000)));
+        let cache = cache_on(&clock, 2);
+        cache.observe_tip(Slot(10_000));
+        cache.insert(Slot(1), Some(UnixTs(100)));
+        cache.insert(Slot(2), Some(UnixTs(101)));
+        // Reading slot 1 makes slot 2 the least recently used
+        assert_eq!(cache.get(Slot(1)), Some(Some(UnixTs(100))));
+        cache.insert(Slot(3), None);
+        
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(Slot(2)), None);
+        assert_eq!(cache.get(Slot(1)), Some(Some(UnixTs(100))));
+        assert_eq!(cache.get(Slot(3)), Some(None));
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn reinserting_an_entry_counts_as_a_use() {
+        let clock = Arc::new(FakeClock::new(UnixTs(1_750_000_000)));
+        let cache = cache_on(&clock, 2);
+        cache.observe_tip(Slot(10_000));
+        cache.insert(Slot(1), Some(UnixTs(100)));
+        cache.insert(Slot(2), Some(UnixTs(101)));
+        cache.insert(Slot(1), Some(UnixTs(100)));
+        cache.insert(Slot(3), Some(UnixTs(102)));
+        
+        assert_eq!(cache.get(Slot(2)), None);
+        assert_eq!(cache.get(Slot(1)), Some(Some(UnixTs(100))));
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn entries_near_the_tip_expire_on_the_clock() {
+        let clock = Arc::new(FakeClock::new(UnixTs(1_750_000_000)));
+        let cache = cache_on(&clock, 10).near_tip(100, Duration::from_secs(5));
+        cache.observe_tip(Slot(1_000));
+        cache.insert(Slot(950), Some(UnixTs(200)));
+        cache.insert(Slot(800), Some(UnixTs(150)));
+        
+        clock.advance(Duration::from_millis(4_999));
+        assert_eq!(cache.get(Slot(950)), Some(Some(UnixTs(200))));
+        clock.advance(Duration::from_millis(1));
+        assert_eq!(cache.get(Slot(950)), None);
+        // Far enough from the tip to be final
+        clock.advance(Duration::from_secs(3_600));
+        assert_eq!(cache.get(Slot(800)), Some(Some(UnixTs(150))));
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn every_entry_expires_until_a_tip_is_observed() {
+        let clock = Arc::new(FakeClock::new(UnixTs(1_750_000_000)));
+        let cache = cache_on(&clock, 10).near_tip(100, Duration::from_secs(5));
+        cache.insert(Slot(1), Some(UnixTs(100)));
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(cache.get(Slot(1)), None);
+        assert!(cache.is_empty());
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn expires_as_paused_time_advances() {
+        let clock = Arc::new(FakeClock::new(UnixTs(1_750_000_000)));
+        let cache = cache_on(&clock, 10).near_tip(100, Duration::from_secs(5));
+        cache.observe_tip(Slot(1_000));
+        cache.insert(Slot(950), Some(UnixTs(200)));
+        // The fake clock follows the runtime's paused time, which only moves when told to
+        tokio::time::advance(Duration::from_secs(4)).await;
+        assert_eq!(cache.get(Slot(950)), Some(Some(UnixTs(200))));
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(cache.get(Slot(950)), None);
+    }
+}
@@ -0,0 +1,215 @@
+//! Slot timing per era of a chain's history
+//!
+//! Slots have not always taken the nominal duration: early mainnet ran slower,
+//! and cluster halts left hours without a single block. Converting between
+//! slots and times at one fixed rate is therefore far off for old timestamps.
+//! A [`Calibration`] is a table of eras, each starting at a known block and
+//! holding the average slot duration until the next era starts.
+//!
+//! Searches use the table to probe the blocks around the expected slot first,
+//! which takes the binary search straight to a narrow window, and `offset`
+//! estimates scale by the slot duration of the era they start in.
+//!
+//! The built-in table, [`Calibration::nominal`], only knows the chain's target
+//! slot duration from its genesis time, if configured. Refresh it from the
+//! actual chain with [`BlockFinder::measure_calibration`]; tables are saved and
+//! loaded as TOML files. A table can also hold the recent slot rate measured
+//! over a chosen window, which estimates of future slots then use.
+
+use std::error::Error;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+use crate::chain::ChainProfile;
+use crate::estimate::SlotRate;
+use crate::finder::BlockFinder;
+use crate::types::{Slot, UnixTs};
+
+/// Blocks sampled by default when measuring a calibration
+pub const DEFAULT_CALIBRATION_SAMPLES: usize = 64;
+
+/// Consecutive samples whose slot durations differ by less than this share of the
+/// era's duration belong to the same era
+const ERA_TOLERANCE: f64 = 0.05;
+
+/// A stretch of history with one average slot duration, from `start_slot` up to the
+/// start of the next era
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Era {
+    pub start_slot: Slot,
+    /// Block time of `start_slot`
+    pub start_time: UnixTs,
+    pub slot_duration_ms: f64,
+}
+
+/// Eras of a chain, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    #[serde(default, rename = "era")]
+    pub eras: Vec<Era>,
+    /// Recent slot timing, which estimates of future slots use while it is fresh (see
+    /// [`BlockFinder::estimation_slot_rate`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measured_rate: Option<MeasuredRate>,
+}
+
+/// A [`SlotRate`] measured with [`BlockFinder::measure_slot_rate`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MeasuredRate {
+    pub measured_at: UnixTs,
+    pub window_secs: i64,
+    pub mean_ms: f64,
+    pub fastest_ms: f64,
+    pub slowest_ms: f64,
+    pub stddev_ms: f64,
+    pub samples: usize,
+}
+
+impl MeasuredRate {
+    pub fn new(measured_at: UnixTs, window_secs: i64, rate: SlotRate) -> Self {
+        MeasuredRate {
+            measured_at,
+            window_secs,
+            mean_ms: rate.mean_ms,
+            fastest_ms: rate.fastest_ms,
+            slowest_ms: rate.slowest_ms,
+            stddev_ms: rate.stddev_ms,
+            samples: rate.samples,
+        }
+    }
+    
+    pub fn rate(&self) -> SlotRate {
+        SlotRate {
+            mean_ms: self.mean_ms,
+            fastest_ms: self.fastest_ms,
+            slowest_ms: self.slowest_ms,
+            stddev_ms: self.stddev_ms,
+            samples: self.samples,
+        }
+    }
+}
+
+impl Calibration {
+    /// The built-in table: a single era at the chain's target slot duration from its genesis,
+    /// or no eras at all when the genesis time is not configured
+    pub fn nominal(chain: &ChainProfile) -> Self {
+        let eras = chain.genesis_timestamp
+            .map(|genesis| Era { start_slot: Slot(0), start_time: genesis, slot_duration_ms: chain.slot_duration_ms as f64 })
+            .into_iter()
+            .collect();
+        Calibration { eras, measured_rate: None }
+    }
+    
+    /// Builds eras from known `(slot, block_time)` points, merging neighbouring stretches with
+    /// about the same slot duration. Needs at least two points.
+    pub fn from_block_times(points: &[(Slot, UnixTs)]) -> Self {
+        let mut points = points.to_vec();
+        points.sort_unstable();
+        points.dedup_by_key(|(slot, _)| *slot);
+        let mut eras: Vec<Era> = Vec::new();
+        for pair in points.windows(2) {
+            let ((start_slot, start_time), (end_slot, end_time)) = (pair[0], pair[1]);
+            let slot_duration_ms = (end_time - start_time) as f64 * 1000.0 / (end_slot - start_slot) as f64;
+            match eras.last_mut() {
+                Some(era) if (slot_duration_ms - era.slot_duration_ms).abs() <= era.slot_duration_ms * ERA_TOLERANCE => {
+                    era.slot_duration_ms = (end_time - era.start_time) as f64 * 1000.0 / (end_slot - era.start_slot) as f64;
+                }
+                _ => eras.push(Era { start_slot, start_time, slot_duration_ms }),
+            }
+        }
+        Calibration { eras, measured_rate: None }
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.eras.is_empty()
+    }
+    
+    /// The era `slot` falls in, `None` before the first era
+    pub fn era(&self, slot: Slot) -> Option<&Era> {
+        self.eras.iter().rev().find(|era| era.start_slot <= slot)
+    }
+    
+    /// Expected block time of `slot`. The last era extends to the future.
+    pub fn time_at(&self, slot: Slot) -> Option<UnixTs> {
+        let era = self.era(slot)?;
+        Some(era.start_time + ((slot - era.start_slot) as f64 * era.slot_duration_ms / 1000.0).round() as i64)
+    }
+    
+    /// Expected slot at `timestamp`, `None` before the first era
+    pub fn slot_at(&self, timestamp: UnixTs) -> Option<Slot> {
+        let index = self.eras.iter().rposition(|era| era.start_time <= timestamp)?;
+        let era = &self.eras[index];
+        let mut slot = era.start_slot;
+        if era.slot_duration_ms > 0.0 {
+            slot = slot.saturating_add(((timestamp - era.start_time) as f64 * 1000.0 / era.slot_duration_ms).round() as u64);
+        }
+        // Within an era, slots before the next one starts
+        match self.eras.get(index + 1) {
+            Some(next) => Some(slot.min(next.start_slot)),
+            None => Some(slot),
+        }
+    }
+    
+    /// Slots that take about `seconds` in the era of `slot`, at the chain's target slot
+    /// duration outside the table
+    pub fn slots_in(&self, slot: Slot, seconds: i64, chain: &ChainProfile) -> u64 {
+        let slot_duration_ms = self.era(slot)
+            .map(|era| era.slot_duration_ms)
+            .filter(|ms| *ms > 0.0)
+            .unwrap_or(chain.slot_duration_ms as f64);
+        (seconds.unsigned_abs() as f64 * 1000.0 / slot_duration_ms).round() as u64
+    }
+    
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("❌ Cannot read calibration {}: {}", path.display(), e))?;
+        toml::from_str(&contents).map_err(|e| format!("❌ Invalid calibration {}: {}", path.display(), e).into())
+    }
+    
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)
+            .map_err(|e| format!("❌ Cannot write calibration {}: {}", path.display(), e).into())
+    }
+}
+
+impl BlockFinder {
+    /// The era table searches and estimates use
+    pub fn calibration(&self) -> &Calibration {
+        &self.calibration
+    }
+    
+    /// Measures the eras of the chain from `samples` blocks spread evenly between the oldest
+    /// available block and the tip. Skipped sample slots use the next produced block.
+    pub async fn measure_calibration(&self, samples: usize) -> Result<Calibration, Box<dyn Error>> {
+        if samples < 2 {
+            return Err("A calibration needs at least 2 samples".into());
+        }
+        let first_slot = self.get_first_available_block().await?.max(self.chain.first_slot());
+        let (tip_slot, tip_time) = self.latest_block_time().await?;
+        let span = tip_slot.0.saturating_sub(first_slot.0);
+        
+        let mut points = Vec::with_capacity(samples);
+        for i in 0..samples as u64 - 1 {
+            let slot = first_slot.saturating_add(span * i / (samples as u64 - 1));
+            let Some(&produced) = self.get_blocks_with_limit(slot, 1).await?.first() else {
+                continue;
+            };
+            if let Some(block_time) = self.get_block_time(produced).await? {
+                points.push((produced, block_time));
+            }
+            self.clock.sleep(self.probe_delay).await;
+        }
+        points.push((tip_slot, tip_time));
+        
+        let calibration = Calibration::from_block_times(&points);
+        if calibration.is_empty() {
+            return Err(format!("❌ Not enough blocks with a block time between slots {} and {}", first_slot, tip_slot).into());
+        }
+        Ok(calibration)
+    }
+}
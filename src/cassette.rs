@@ -0,0 +1,134 @@
+//! Recording and replaying RPC sessions
+//!
+//! A [`Cassette`] attached to the [`HttpBackend`](crate::rpc::HttpBackend)
+//! either records every JSON-RPC request and the response the node sent, or
+//! answers requests from such a recording without touching the network. A
+//! search replayed from a cassette sees exactly the data of the recorded one,
+//! so changes to the search can be checked against real cluster data offline
+//! and reproducibly.
+//!
+//! Cassettes are JSON lines, one `{"method", "params", "response"}` object per
+//! request in the order the responses arrived. Endpoints and credentials are
+//! not recorded.
+
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    params: Value,
+    response: Value,
+}
+
+/// A replayed request that the cassette has no response for
+#[derive(Debug)]
+pub struct MissingRecording {
+    pub method: String,
+    pub params: Value,
+}
+
+impl fmt::Display for MissingRecording {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No response to {} {} in the cassette", self.method, self.params)
+    }
+}
+
+impl Error for MissingRecording {}
+
+// Responses for one request, and the last one handed out
+#[derive(Default)]
+struct Recorded {
+    pending: VecDeque<Value>,
+    last: Option<Value>,
+}
+
+enum Mode {
+    Record(Mutex<File>),
+    Replay(Mutex<HashMap<String, Recorded>>),
+}
+
+/// An RPC session being recorded to, or replayed from, a file
+pub struct Cassette {
+    mode: Mode,
+}
+
+impl Cassette {
+    /// Starts recording to `path`, replacing an existing file.
+    pub fn record(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| format!("❌ Cannot create cassette {}: {}", path.display(), e))?;
+        Ok(Cassette { mode: Mode::Record(Mutex::new(file)) })
+    }
+    
+    /// Loads the recording in `path` for replay.
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|e| format!("❌ Cannot read cassette {}: {}", path.display(), e))?;
+        let mut recorded: HashMap<String, Recorded> = HashMap::new();
+        for (number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let interaction: Interaction = serde_json::from_str(&line)
+                .map_err(|e| format!("❌ Invalid cassette {} at line {}: {}", path.display(), number + 1, e))?;
+            recorded
+                .entry(key(&interaction.method, &interaction.params))
+                .or_default()
+                .pending
+                .push_back(interaction.response);
+        }
+        Ok(Cassette { mode: Mode::Replay(Mutex::new(recorded)) })
+    }
+    
+    /// Whether requests are answered from the recording instead of the network
+    pub fn is_replay(&self) -> bool {
+        matches!(self.mode, Mode::Replay(_))
+    }
+    
+    /// Appends a request and its response to the recording. Does nothing when replaying.
+    pub(crate) fn save(&self, method: &str, params: &Value, response: &Value) -> Result<(), Box<dyn Error>> {
+        let Mode::Record(file) = &self.mode else {
+            return Ok(());
+        };
+        // The id only matches the request within the recorded session
+        let mut response = response.clone();
+        if let Some(envelope) = response.as_object_mut() {
+            envelope.remove("id");
+        }
+        let interaction = Interaction { method: method.to_string(), params: params.clone(), response };
+        let mut line = serde_json::to_string(&interaction)?;
+        line.push('\n');
+        let mut file = file.lock().map_err(|_| "Cassette lock poisoned")?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+    
+    /// The next recorded response to a request. Identical requests get their responses in
+    /// recorded order, and the last one again once the recording runs out, since e.g. the
+    /// current slot may be asked for more often than during recording.
+    pub(crate) fn play(&self, method: &str, params: &Value) -> Result<Value, Box<dyn Error>> {
+        let Mode::Replay(recorded) = &self.mode else {
+            return Err("Cassette is not replaying".into());
+        };
+        let mut recorded = recorded.lock().map_err(|_| "Cassette lock poisoned")?;
+        let missing = || MissingRecording { method: method.to_string(), params: params.clone() };
+        let entry = recorded.get_mut(&key(method, params)).ok_or_else(missing)?;
+        if let Some(response) = entry.pending.pop_front() {
+            entry.last = Some(response);
+        }
+        Ok(entry.last.clone().ok_or_else(missing)?)
+    }
+}
+
+fn key(method: &str, params: &Value) -> String {
+    format!("{} {}", method, params)
+}
@@ -0,0 +1,97 @@
+//! Parameters of the chain the finder runs against
+//!
+//! The search itself only needs block times, but estimates, nominal timings
+//! and some RPC calls depend on the chain. [`ChainProfile::solana`] describes
+//! Solana mainnet and is the default. Other SVM chains such as Eclipse, SOON
+//! or private forks get their own profile, usually from the CLI config file.
+
+use std::error::Error;
+use serde::Deserialize;
+
+use crate::finder::BlockFinder;
+use crate::types::{Slot, UnixTs};
+use crate::NOMINAL_SLOT_DURATION_MS;
+
+/// Chain parameters used by a [`BlockFinder`](crate::BlockFinder)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainProfile {
+    /// Shown in output and errors
+    pub name: String,
+    /// Target slot duration, used wherever slots are converted to time without block times
+    pub slot_duration_ms: u64,
+    /// Read block times from `getBlock` for RPC nodes that do not implement `getBlockTime`.
+    /// Costs a larger response per probe.
+    pub block_time_from_get_block: bool,
+    /// When slot 0 was produced. Searches for earlier timestamps fail right away.
+    pub genesis_timestamp: Option<UnixTs>,
+    /// Oldest slot with block data on the chain's nodes, where searches and estimates
+    /// start instead of slot 0, e.g. for clusters restarted from a snapshot
+    pub first_available_slot: Option<Slot>,
+}
+
+impl ChainProfile {
+    /// Solana mainnet
+    pub fn solana() -> Self {
+        ChainProfile {
+            name: "solana".to_string(),
+            slot_duration_ms: NOMINAL_SLOT_DURATION_MS,
+            block_time_from_get_block: false,
+            genesis_timestamp: None,
+            first_available_slot: None,
+        }
+    }
+    
+    /// Lowest slot searches look at
+    pub fn first_slot(&self) -> Slot {
+        self.first_available_slot.unwrap_or(Slot(0))
+    }
+}
+
+impl Default for ChainProfile {
+    fn default() -> Self {
+        ChainProfile::solana()
+    }
+}
+
+/// A public Solana cluster, identified by its genesis hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Testnet,
+}
+
+impl Cluster {
+    pub const ALL: [Cluster; 3] = [Cluster::Mainnet, Cluster::Devnet, Cluster::Testnet];
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Cluster::Mainnet => "mainnet",
+            Cluster::Devnet => "devnet",
+            Cluster::Testnet => "testnet",
+        }
+    }
+    
+    pub fn genesis_hash(&self) -> &'static str {
+        match self {
+            Cluster::Mainnet => "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d",
+            Cluster::Devnet => "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG",
+            Cluster::Testnet => "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY",
+        }
+    }
+    
+    /// `None` for private clusters and other chains
+    pub fn from_genesis_hash(hash: &str) -> Option<Cluster> {
+        Cluster::ALL.into_iter().find(|cluster| cluster.genesis_hash() == hash)
+    }
+}
+
+impl BlockFinder {
+    /// The genesis hash of the endpoints' cluster, and which public cluster it belongs to
+    pub async fn detect_cluster(&self) -> Result<(String, Option<Cluster>), Box<dyn Error>> {
+        let hash = self.get_genesis_hash().await?;
+        let cluster = Cluster::from_genesis_hash(&hash);
+        Ok((hash, cluster))
+    }
+}
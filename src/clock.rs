@@ -0,0 +1,134 @@
+//! Time source of the finder
+//!
+//! Everything in the library that reads the time or waits goes through a
+//! [`Clock`]: the current time that targets are checked against, the tip
+//! TTL, probe and scan delays, retry backoff, search deadlines and rate
+//! limits. [`SystemClock`] is the default.
+//!
+//! Both clocks here measure elapsed time and sleep with Tokio's timers, so on
+//! a runtime with paused time (Tokio's `test-util` feature, e.g.
+//! `#[tokio::test(start_paused = true)]`) sleeps return as soon as the runtime
+//! is idle and no real time passes. [`FakeClock`] also pins the wall clock to
+//! a chosen Unix time, which makes checks such as "is this timestamp in the
+//! future" deterministic.
+
+use std::error::Error;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::runtime::{self, Stopwatch};
+use crate::types::UnixTs;
+
+/// Future returned by [`Clock::sleep`]
+#[cfg(not(target_arch = "wasm32"))]
+pub type ClockFuture<'a> = futures::future::BoxFuture<'a, ()>;
+
+/// Future returned by [`Clock::sleep`] (browser timers are not `Send`)
+#[cfg(target_arch = "wasm32")]
+pub type ClockFuture<'a> = futures::future::LocalBoxFuture<'a, ()>;
+
+/// Wall time, monotonic time and timers
+pub trait Clock: Send + Sync {
+    /// Current Unix time in seconds
+    fn unix_now(&self) -> Result<UnixTs, Box<dyn Error>>;
+    
+    /// Milliseconds since a fixed point, e.g. the creation of the clock. Never goes backwards.
+    fn elapsed_ms(&self) -> u64;
+    
+    /// Waits for `duration`
+    fn sleep(&self, duration: Duration) -> ClockFuture<'_>;
+}
+
+/// The system's wall clock and Tokio's timers (browser timers on `wasm32`)
+pub struct SystemClock {
+    started: Stopwatch,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock { started: Stopwatch::start() }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn unix_now(&self) -> Result<UnixTs, Box<dyn Error>> {
+        runtime::unix_now()
+    }
+    
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed_ms()
+    }
+    
+    fn sleep(&self, duration: Duration) -> ClockFuture<'_> {
+        Box::pin(runtime::sleep(duration))
+    }
+}
+
+/// A clock whose wall time starts at a fixed Unix time and then follows the elapsed
+/// time of the runtime, plus whatever [`FakeClock::advance`] adds
+pub struct FakeClock {
+    start: UnixTs,
+    started: Stopwatch,
+    advanced_ms: AtomicU64,
+}
+
+impl FakeClock {
+    pub fn new(now: UnixTs) -> Self {
+        FakeClock { start: now, started: Stopwatch::start(), advanced_ms: AtomicU64::new(0) }
+    }
+    
+    /// Moves the clock forward by `duration`. Pending sleeps are not woken early; advance
+    /// Tokio's paused time for that.
+    pub fn advance(&self, duration: Duration) {
+        self.advanced_ms.fetch_add(duration.as_millis() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for FakeClock {
+    fn unix_now(&self) -> Result<UnixTs, Box<dyn Error>> {
+        Ok(self.start + (self.elapsed_ms() / 1000) as i64)
+    }
+    
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed_ms() + self.advanced_ms.load(Ordering::Relaxed)
+    }
+    
+    fn sleep(&self, duration: Duration) -> ClockFuture<'_> {
+        Box::pin(runtime::sleep(duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[tokio::test(start_paused = true)]
+    async fn fake_clock_starts_at_its_time() {
+        let clock = FakeClock::new(UnixTs(1_750_000_000));
+        assert_eq!(clock.unix_now().unwrap(), UnixTs(1_750_000_000));
+        assert_eq!(clock.elapsed_ms(), 0);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn fake_clock_advances_on_request() {
+        let clock = FakeClock::new(UnixTs(1_750_000_000));
+        clock.advance(Duration::from_millis(1_500));
+        assert_eq!(clock.elapsed_ms(), 1_500);
+        assert_eq!(clock.unix_now().unwrap(), UnixTs(1_750_000_001));
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn fake_clock_sleeps_in_paused_time() {
+        let clock = FakeClock::new(UnixTs(1_750_000_000));
+        let started = std::time::Instant::now();
+        clock.sleep(Duration::from_secs(3_600)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(clock.unix_now().unwrap(), UnixTs(1_750_003_600));
+    }
+}
@@ -0,0 +1,144 @@
+//! Epoch schedule arithmetic, mirroring the Solana runtime's `EpochSchedule`
+
+use serde::Deserialize;
+
+use crate::types::Slot;
+
+/// Length of the shortest epoch during warmup, as defined by the Solana runtime
+pub const MINIMUM_SLOTS_PER_EPOCH: u64 = 32;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochSchedule {
+    pub slots_per_epoch: u64,
+    #[serde(default)]
+    pub leader_schedule_slot_offset: u64,
+    pub warmup: bool,
+    pub first_normal_epoch: u64,
+    pub first_normal_slot: Slot,
+}
+
+impl EpochSchedule {
+    /// Returns the epoch containing `slot` and the slot's index within that epoch.
+    pub fn get_epoch_and_slot_index(&self, slot: Slot) -> (u64, u64) {
+        if slot < self.first_normal_slot {
+            let epoch = (slot.0 + MINIMUM_SLOTS_PER_EPOCH + 1)
+                .next_power_of_two()
+                .trailing_zeros()
+                - MINIMUM_SLOTS_PER_EPOCH.trailing_zeros()
+                - 1;
+            let epoch_len = 2u64.pow(epoch + MINIMUM_SLOTS_PER_EPOCH.trailing_zeros());
+            (epoch as u64, slot.0 - (epoch_len - MINIMUM_SLOTS_PER_EPOCH))
+        } else {
+            let normal_slot_index = slot - self.first_normal_slot;
+            (
+                self.first_normal_epoch + normal_slot_index / self.slots_per_epoch,
+                normal_slot_index % self.slots_per_epoch,
+            )
+        }
+    }
+    
+    pub fn get_slots_in_epoch(&self, epoch: u64) -> u64 {
+        if epoch < self.first_normal_epoch {
+            2u64.pow(epoch as u32 + MINIMUM_SLOTS_PER_EPOCH.trailing_zeros())
+        } else {
+            self.slots_per_epoch
+        }
+    }
+    
+    /// The first slot of `epoch`, `None` if it lies beyond the last slot a `u64` can hold
+    pub fn get_first_slot_in_epoch(&self, epoch: u64) -> Option<Slot> {
+        if epoch <= self.first_normal_epoch {
+            2u64.checked_pow(u32::try_from(epoch).ok()?)
+                .and_then(|warmup_epochs| (warmup_epochs - 1).checked_mul(MINIMUM_SLOTS_PER_EPOCH))
+                .map(Slot)
+        } else {
+            (epoch - self.first_normal_epoch)
+                .checked_mul(self.slots_per_epoch)
+                .and_then(|slots| self.first_normal_slot.checked_add(slots))
+        }
+    }
+    
+    /// The last slot of `epoch`, `None` if it lies beyond the last slot a `u64` can hold
+    pub fn get_last_slot_in_epoch(&self, epoch: u64) -> Option<Slot> {
+        self.get_first_slot_in_epoch(epoch)?.checked_add(self.get_slots_in_epoch(epoch).checked_sub(1)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // As returned by getEpochSchedule on mainnet-beta, which started without warmup
+    fn mainnet() -> EpochSchedule {
+        EpochSchedule {
+            slots_per_epoch: 432_000,
+            leader_schedule_slot_offset: 432_000,
+            warmup: false,
+            first_normal_epoch: 0,
+            first_normal_slot: Slot(0),
+        }
+    }
+    
+    // As returned by getEpochSchedule on devnet, whose first 14 epochs were warmup epochs
+    fn devnet() -> EpochSchedule {
+        EpochSchedule {
+            slots_per_epoch: 432_000,
+            leader_schedule_slot_offset: 432_000,
+            warmup: true,
+            first_normal_epoch: 14,
+            first_normal_slot: Slot(524_256),
+        }
+    }
+    
+    #[test]
+    fn normal_epoch_boundaries() {
+        let schedule = mainnet();
+        assert_eq!(schedule.get_first_slot_in_epoch(0), Some(Slot(0)));
+        assert_eq!(schedule.get_last_slot_in_epoch(0), Some(Slot(431_999)));
+        assert_eq!(schedule.get_first_slot_in_epoch(600), Some(Slot(259_200_000)));
+        assert_eq!(schedule.get_last_slot_in_epoch(600), Some(Slot(259_631_999)));
+        assert_eq!(schedule.get_slots_in_epoch(600), 432_000);
+    }
+    
+    #[test]
+    fn normal_epoch_of_slot() {
+        let schedule = mainnet();
+        assert_eq!(schedule.get_epoch_and_slot_index(Slot(0)), (0, 0));
+        assert_eq!(schedule.get_epoch_and_slot_index(Slot(259_199_999)), (599, 431_999));
+        assert_eq!(schedule.get_epoch_and_slot_index(Slot(259_200_000)), (600, 0));
+    }
+    
+    #[test]
+    fn warmup_epoch_boundaries() {
+        let schedule = devnet();
+        assert_eq!(schedule.get_first_slot_in_epoch(0), Some(Slot(0)));
+        assert_eq!(schedule.get_last_slot_in_epoch(0), Some(Slot(31)));
+        assert_eq!(schedule.get_first_slot_in_epoch(1), Some(Slot(32)));
+        assert_eq!(schedule.get_last_slot_in_epoch(1), Some(Slot(95)));
+        assert_eq!(schedule.get_first_slot_in_epoch(13), Some(Slot(262_112)));
+        assert_eq!(schedule.get_last_slot_in_epoch(13), Some(Slot(524_255)));
+        assert_eq!(schedule.get_slots_in_epoch(13), 262_144);
+        // The first normal epoch starts right after the last warmup epoch
+        assert_eq!(schedule.get_first_slot_in_epoch(14), Some(schedule.first_normal_slot));
+        assert_eq!(schedule.get_first_slot_in_epoch(15), Some(Slot(956_256)));
+    }
+    
+    #[test]
+    fn warmup_epoch_of_slot() {
+        let schedule = devnet();
+        assert_eq!(schedule.get_epoch_and_slot_index(Slot(31)), (0, 31));
+        assert_eq!(schedule.get_epoch_and_slot_index(Slot(32)), (1, 0));
+        assert_eq!(schedule.get_epoch_and_slot_index(Slot(524_255)), (13, 262_143));
+        assert_eq!(schedule.get_epoch_and_slot_index(Slot(524_256)), (14, 0));
+    }
+    
+    #[test]
+    fn epochs_beyond_the_last_slot() {
+        let schedule = mainnet();
+        let last_epoch = u64::MAX / 432_000 - 1;
+        assert!(schedule.get_last_slot_in_epoch(last_epoch).is_some());
+        assert_eq!(schedule.get_first_slot_in_epoch(u64::MAX), None);
+        assert_eq!(schedule.get_last_slot_in_epoch(u64::MAX / 432_000), None);
+        assert_eq!(devnet().get_first_slot_in_epoch(u64::MAX), None);
+    }
+}
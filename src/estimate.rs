@@ -0,0 +1,265 @@
+//! Timing estimates for slots the cluster has not reached yet
+//!
+//! Future slots have no block time, so they are projected from the latest
+//! block using the slot times reported by `getRecentPerformanceSamples`
+//! (one sample per minute). The average over all samples gives the estimate,
+//! the fastest and slowest sample bound the range. A rate measured over a
+//! chosen window with [`BlockFinder::measure_slot_rate`] and stored in the
+//! finder's [`Calibration`](crate::calibration::Calibration), as the CLI's
+//! `calibrate` does, replaces the samples while it is fresh.
+
+use std::error::Error;
+
+use crate::finder::BlockFinder;
+use crate::rpc::PerformanceSample;
+use crate::types::{BlockMatch, Slot, UnixTs};
+
+/// Performance samples used by default, about the last hour
+pub const DEFAULT_PERFORMANCE_SAMPLES: usize = 60;
+
+/// Most performance samples a node keeps, about the last 12 hours
+pub const MAX_PERFORMANCE_SAMPLES: usize = 720;
+
+/// How long a measured rate in the calibration is used for estimates
+pub const CALIBRATED_RATE_MAX_AGE_SECS: i64 = 86_400;
+
+/// How long [`BlockFinder::live_slot_rate`] reuses a rate; samples cover a minute each
+const LIVE_SLOT_RATE_TTL_MS: u64 = 60_000;
+
+/// Intervals between the blocks sampled when the node has too few performance samples
+const MAX_BLOCK_INTERVALS: u64 = 60;
+
+/// Recent slot timing, in milliseconds per slot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotRate {
+    /// Total sample time over total slots
+    pub mean_ms: f64,
+    pub fastest_ms: f64,
+    pub slowest_ms: f64,
+    /// Standard deviation of the slot time of the samples
+    pub stddev_ms: f64,
+    pub samples: usize,
+}
+
+impl SlotRate {
+    /// Returns `None` when no sample covers any slot.
+    pub fn from_samples(samples: &[PerformanceSample]) -> Option<SlotRate> {
+        let intervals: Vec<(u64, f64)> = samples.iter().map(|s| (s.num_slots, f64::from(s.sample_period_secs) * 1000.0)).collect();
+        Self::from_intervals(&intervals)
+    }
+    
+    /// Rate over `(slots, milliseconds)` intervals, e.g. between sampled blocks. Returns `None`
+    /// when no interval covers any slot.
+    pub fn from_intervals(intervals: &[(u64, f64)]) -> Option<SlotRate> {
+        let intervals: Vec<(u64, f64)> = intervals.iter().copied().filter(|(slots, _)| *slots > 0).collect();
+        if intervals.is_empty() {
+            return None;
+        }
+        let slot_ms: Vec<f64> = intervals.iter().map(|(slots, ms)| ms / *slots as f64).collect();
+        let total_ms: f64 = intervals.iter().map(|(_, ms)| ms).sum();
+        let total_slots: u64 = intervals.iter().map(|(slots, _)| slots).sum();
+        let average_ms = slot_ms.iter().sum::<f64>() / slot_ms.len() as f64;
+        let variance = slot_ms.iter().map(|ms| (ms - average_ms).powi(2)).sum::<f64>() / slot_ms.len() as f64;
+        Some(SlotRate {
+            mean_ms: total_ms / total_slots as f64,
+            fastest_ms: slot_ms.iter().copied().fold(f64::INFINITY, f64::min),
+            slowest_ms: slot_ms.iter().copied().fold(0.0, f64::max),
+            stddev_ms: variance.sqrt(),
+            samples: slot_ms.len(),
+        })
+    }
+    
+    /// Seconds that `slots` slots take at `ms_per_slot`
+    fn seconds(slots: u64, ms_per_slot: f64) -> i64 {
+        (slots as f64 * ms_per_slot / 1000.0).round() as i64
+    }
+}
+
+/// When a future slot is expected to be produced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotTimeEstimate {
+    pub slot: Slot,
+    /// The latest block, which the estimate is projected from
+    pub tip_slot: Slot,
+    pub tip_time: UnixTs,
+    /// At the recent average slot time
+    pub estimated_time: UnixTs,
+    /// If every slot is as fast as in the fastest sample
+    pub earliest_time: UnixTs,
+    /// If every slot is as slow as in the slowest sample
+    pub latest_time: UnixTs,
+    pub rate: SlotRate,
+}
+
+/// Which slot is expected to be produced at a future time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotEstimate {
+    pub target_timestamp: UnixTs,
+    /// The latest block, which the estimate is projected from
+    pub tip_slot: Slot,
+    pub tip_time: UnixTs,
+    /// At the recent average slot time
+    pub estimated_slot: Slot,
+    /// If every slot is as slow as in the slowest sample
+    pub lowest_slot: Slot,
+    /// If every slot is as fast as in the fastest sample
+    pub highest_slot: Slot,
+    pub rate: SlotRate,
+}
+
+impl SlotEstimate {
+    /// Projects `rate` from the block `tip_slot` produced at `tip_time`.
+    pub fn project(target_timestamp: UnixTs, tip_slot: Slot, tip_time: UnixTs, rate: SlotRate) -> SlotEstimate {
+        let remaining_ms = (target_timestamp - tip_time).max(0) as f64 * 1000.0;
+        let slots = |ms_per_slot: f64| tip_slot.saturating_add((remaining_ms / ms_per_slot).round() as u64);
+        SlotEstimate {
+            target_timestamp,
+            tip_slot,
+            tip_time,
+            estimated_slot: slots(rate.mean_ms),
+            lowest_slot: slots(rate.slowest_ms),
+            highest_slot: slots(rate.fastest_ms),
+            rate,
+        }
+    }
+    
+    /// Slots the chain still has to produce until the estimated slot
+    pub fn slots_to_go(&self) -> u64 {
+        self.estimated_slot - self.tip_slot
+    }
+}
+
+impl BlockFinder {
+    /// Slot timing over the last `samples` performance samples
+    pub async fn recent_slot_rate(&self, samples: usize) -> Result<SlotRate, Box<dyn Error>> {
+        let samples = self.get_recent_performance_samples(samples).await?;
+        SlotRate::from_samples(&samples).ok_or_else(|| "The node returned no performance samples".into())
+    }
+    
+    /// The slot rate estimates project with: the rate measured into the finder's calibration
+    /// within the last [`CALIBRATED_RATE_MAX_AGE_SECS`], otherwise the last
+    /// [`DEFAULT_PERFORMANCE_SAMPLES`] performance samples
+    pub async fn estimation_slot_rate(&self) -> Result<SlotRate, Box<dyn Error>> {
+        let now = self.now()?;
+        if let Some(measured) = self.calibration.measured_rate.filter(|measured| now - measured.measured_at <= CALIBRATED_RATE_MAX_AGE_SECS) {
+            return Ok(measured.rate());
+        }
+        self.recent_slot_rate(DEFAULT_PERFORMANCE_SAMPLES).await
+    }
+    
+    /// The [`BlockFinder::estimation_slot_rate`], fetched at most once a minute. Searches seed
+    /// their first guess for recent timestamps with it.
+    pub async fn live_slot_rate(&self) -> Result<SlotRate, Box<dyn Error>> {
+        if let Some((rate, fetched_at_ms)) = self.slot_rate.lock().ok().and_then(|rate| *rate) {
+            if self.clock.elapsed_ms() < fetched_at_ms + LIVE_SLOT_RATE_TTL_MS {
+                return Ok(rate);
+            }
+        }
+        let fetched_at_ms = self.clock.elapsed_ms();
+        let rate = self.estimation_slot_rate().await?;
+        if let Ok(mut cached) = self.slot_rate.lock() {
+            *cached = Some((rate, fetched_at_ms));
+        }
+        Ok(rate)
+    }
+    
+    /// Slot timing over the last `window_secs`, from one performance sample per minute when the
+    /// node keeps enough of them, otherwise from blocks sampled evenly across the window
+    pub async fn measure_slot_rate(&self, window_secs: i64) -> Result<SlotRate, Box<dyn Error>> {
+        let minutes = (window_secs / 60).max(1) as usize;
+        if minutes <= MAX_PERFORMANCE_SAMPLES {
+            // Nodes that do not serve samples, or only a few, fall back to block times
+            if let Ok(samples) = self.get_recent_performance_samples(minutes).await {
+                if samples.len() >= minutes {
+                    if let Some(rate) = SlotRate::from_samples(&samples) {
+                        return Ok(rate);
+                    }
+                }
+            }
+        }
+        
+        let (tip_slot, tip_time) = self.latest_block_time().await?;
+        let span = self.calibration.slots_in(tip_slot, window_secs, &self.chain);
+        let start_slot = tip_slot.saturating_sub(span).max(self.chain.first_slot());
+        let intervals = MAX_BLOCK_INTERVALS.min(minutes as u64);
+        let mut points = Vec::new();
+        for i in 0..intervals {
+            let slot = start_slot.saturating_add((tip_slot - start_slot) * i / intervals);
+            let Some(&produced) = self.get_blocks_with_limit(slot, 1).await?.first() else {
+                continue;
+            };
+            if let Some(block_time) = self.get_block_time(produced).await? {
+                points.push((produced, block_time));
+            }
+            self.clock.sleep(self.probe_delay).await;
+        }
+        points.push((tip_slot, tip_time));
+        points.dedup_by_key(|(slot, _)| *slot);
+        let intervals: Vec<(u64, f64)> = points
+            .windows(2)
+            .map(|pair| (pair[1].0 - pair[0].0, (pair[1].1 - pair[0].1) as f64 * 1000.0))
+            .collect();
+        SlotRate::from_intervals(&intervals)
+            .ok_or_else(|| format!("❌ Not enough blocks with a block time in the last {} seconds", window_secs).into())
+    }
+    
+    /// The latest produced block and its block time
+    pub async fn latest_block_time(&self) -> Result<(Slot, UnixTs), Box<dyn Error>> {
+        let current_slot = self.get_current_slot().await?;
+        let slot = self.find_last_block_at_or_before(current_slot, self.chain.first_slot()).await?
+            .ok_or("No block has been produced yet")?;
+        let block_time = self.get_block_time(slot).await?
+            .ok_or_else(|| format!("No block time for the latest block {}", slot))?;
+        Ok((slot, block_time))
+    }
+    
+    /// Estimates when `slot` will be produced at the [`BlockFinder::estimation_slot_rate`].
+    /// Fails if the slot has already been reached.
+    ///
+    /// Skipped slots take their time like produced ones, so no skip rate is needed. The range
+    /// assumes the fastest or slowest minute lasts until the slot, so it is wide for far slots.
+    pub async fn estimate_slot_time(&self, slot: Slot) -> Result<SlotTimeEstimate, Box<dyn Error>> {
+        let (tip_slot, tip_time) = self.latest_block_time().await?;
+        if slot <= tip_slot {
+            return Err(format!("Slot {} has already been reached (latest block {})", slot, tip_slot).into());
+        }
+        let rate = self.estimation_slot_rate().await?;
+        let slots_ahead = slot - tip_slot;
+        Ok(SlotTimeEstimate {
+            slot,
+            tip_slot,
+            tip_time,
+            estimated_time: tip_time + SlotRate::seconds(slots_ahead, rate.mean_ms),
+            earliest_time: tip_time + SlotRate::seconds(slots_ahead, rate.fastest_ms),
+            latest_time: tip_time + SlotRate::seconds(slots_ahead, rate.slowest_ms),
+            rate,
+        })
+    }
+    
+    /// Estimates which slot will be produced at `target_timestamp`, the inverse of
+    /// [`BlockFinder::estimate_slot_time`]. Fails if the latest block is already at or past it;
+    /// use [`BlockFinder::find_block_by_timestamp`] then.
+    pub async fn estimate_slot_at(&self, target_timestamp: UnixTs) -> Result<SlotEstimate, Box<dyn Error>> {
+        let (tip_slot, tip_time) = self.latest_block_time().await?;
+        if tip_time >= target_timestamp {
+            return Err(format!("{} has already been reached (latest block {} at {})", target_timestamp, tip_slot, tip_time).into());
+        }
+        let rate = self.estimation_slot_rate().await?;
+        Ok(SlotEstimate::project(target_timestamp, tip_slot, tip_time, rate))
+    }
+    
+    /// Like [`BlockFinder::find_block_by_timestamp`], but for a timestamp the chain has not
+    /// reached yet returns the projected slot from [`BlockFinder::estimate_slot_at`], marked
+    /// [`BlockMatch::estimated`], instead of failing.
+    pub async fn find_or_estimate_block(&self, target_timestamp: UnixTs) -> Result<BlockMatch, Box<dyn Error>> {
+        if target_timestamp > self.now()? {
+            let (tip_slot, tip_time) = self.latest_block_time().await?;
+            // The local clock may be ahead of the cluster
+            if tip_time < target_timestamp {
+                let rate = self.estimation_slot_rate().await?;
+                return Ok(BlockMatch::projected(&SlotEstimate::project(target_timestamp, tip_slot, tip_time, rate)));
+            }
+        }
+        self.find_block_by_timestamp(target_timestamp).await
+    }
+}
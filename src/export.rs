@@ -0,0 +1,115 @@
+//! Columnar export of search results
+//!
+//! Enabled with the `parquet` feature. Writes [`BlockMatch`] rows as an
+//! Arrow IPC file or a Parquet file, ready for DuckDB, Polars or Spark:
+//!
+//! | column             | type           |
+//! |--------------------|----------------|
+//! | `target_timestamp` | Int64, null    |
+//! | `slot`             | UInt64         |
+//! | `block_time`       | Int64, null    |
+//! | `blockhash`        | Utf8           |
+//! | `block_height`     | UInt64, null   |
+//! | `time_diff`        | Int64, null    |
+//! | `exact`            | Boolean        |
+//! | `approximate`      | Boolean        |
+//! | `finalized`        | Boolean, null  |
+//! | `estimated`        | Boolean        |
+//! | `estimate_low`     | UInt64, null   |
+//! | `estimate_high`    | UInt64, null   |
+//!
+//! Dense slot/time dumps use just `slot` (UInt64) and `block_time` (Int64).
+
+use std::error::Error;
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use arrow_array::{ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::types::{BlockMatch, Slot, UnixTs};
+
+/// Arrow schema of exported [`BlockMatch`] rows
+pub fn block_match_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("target_timestamp", DataType::Int64, true),
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("block_time", DataType::Int64, true),
+        Field::new("blockhash", DataType::Utf8, false),
+        Field::new("block_height", DataType::UInt64, true),
+        Field::new("time_diff", DataType::Int64, true),
+        Field::new("exact", DataType::Boolean, false),
+        Field::new("approximate", DataType::Boolean, false),
+        Field::new("finalized", DataType::Boolean, true),
+        Field::new("estimated", DataType::Boolean, false),
+        Field::new("estimate_low", DataType::UInt64, true),
+        Field::new("estimate_high", DataType::UInt64, true),
+    ])
+}
+
+/// Converts search results into a single Arrow record batch
+pub fn block_matches_to_record_batch(matches: &[BlockMatch]) -> Result<RecordBatch, Box<dyn Error>> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(matches.iter().map(|m| m.target_timestamp.map(i64::from)).collect::<Int64Array>()),
+        Arc::new(matches.iter().map(|m| Some(u64::from(m.slot))).collect::<UInt64Array>()),
+        Arc::new(matches.iter().map(|m| m.block_time.map(i64::from)).collect::<Int64Array>()),
+        Arc::new(matches.iter().map(|m| Some(m.blockhash.as_str())).collect::<StringArray>()),
+        Arc::new(matches.iter().map(|m| m.block_height).collect::<UInt64Array>()),
+        Arc::new(matches.iter().map(|m| m.time_diff).collect::<Int64Array>()),
+        Arc::new(matches.iter().map(|m| Some(m.exact)).collect::<BooleanArray>()),
+        Arc::new(matches.iter().map(|m| Some(m.approximate)).collect::<BooleanArray>()),
+        Arc::new(matches.iter().map(|m| m.finalized).collect::<BooleanArray>()),
+        Arc::new(matches.iter().map(|m| Some(m.estimated)).collect::<BooleanArray>()),
+        Arc::new(matches.iter().map(|m| m.estimate_range.map(|(low, _)| u64::from(low))).collect::<UInt64Array>()),
+        Arc::new(matches.iter().map(|m| m.estimate_range.map(|(_, high)| u64::from(high))).collect::<UInt64Array>()),
+    ];
+    Ok(RecordBatch::try_new(Arc::new(block_match_schema()), columns)?)
+}
+
+/// Arrow schema of exported `(slot, block_time)` rows
+pub fn slot_time_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("slot", DataType::UInt64, false),
+        Field::new("block_time", DataType::Int64, false),
+    ])
+}
+
+/// Converts `(slot, block_time)` pairs, e.g. from [`BlockFinder::stream_blocks`](crate::BlockFinder::stream_blocks), into a record batch
+pub fn slot_times_to_record_batch(slot_times: &[(Slot, UnixTs)]) -> Result<RecordBatch, Box<dyn Error>> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(slot_times.iter().map(|(slot, _)| u64::from(*slot)).collect::<UInt64Array>()),
+        Arc::new(slot_times.iter().map(|(_, block_time)| i64::from(*block_time)).collect::<Int64Array>()),
+    ];
+    Ok(RecordBatch::try_new(Arc::new(slot_time_schema()), columns)?)
+}
+
+/// Writes search results as an Arrow IPC file (`.arrow`)
+pub fn write_arrow(path: impl AsRef<Path>, matches: &[BlockMatch]) -> Result<(), Box<dyn Error>> {
+    write_arrow_batch(path, &block_matches_to_record_batch(matches)?)
+}
+
+/// Writes search results as a Snappy-compressed Parquet file
+pub fn write_parquet(path: impl AsRef<Path>, matches: &[BlockMatch]) -> Result<(), Box<dyn Error>> {
+    write_parquet_batch(path, &block_matches_to_record_batch(matches)?)
+}
+
+/// Writes any record batch as an Arrow IPC file
+pub fn write_arrow_batch(path: impl AsRef<Path>, batch: &RecordBatch) -> Result<(), Box<dyn Error>> {
+    let mut writer = FileWriter::try_new(File::create(path)?, &batch.schema())?;
+    writer.write(batch)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Writes any record batch as a Snappy-compressed Parquet file
+pub fn write_parquet_batch(path: impl AsRef<Path>, batch: &RecordBatch) -> Result<(), Box<dyn Error>> {
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), Some(properties))?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
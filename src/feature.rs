@@ -0,0 +1,160 @@
+//! Feature gate activations
+//!
+//! Runtime changes are rolled out behind feature gates: accounts owned by the
+//! feature program whose data, a bincode `Option<u64>`, holds the slot the
+//! feature activated at once it has. Activation happens when the first bank of
+//! an epoch is created, so that slot is the first produced block of the epoch
+//! and the epoch's boundary tells when the change went live.
+
+use std::error::Error;
+
+use crate::finder::BlockFinder;
+use crate::types::{Slot, UnixTs};
+
+/// Owner of every feature gate account
+pub const FEATURE_PROGRAM_ID: &str = "Feature111111111111111111111111111111111111";
+
+/// When a feature gate activated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureActivation {
+    pub slot: Slot,
+    pub epoch: u64,
+    /// `None` when the node no longer has the block
+    pub block_time: Option<UnixTs>,
+}
+
+impl BlockFinder {
+    /// The slot the feature gate `feature_id` activated at, or `None` while it is still
+    /// pending. Fails when the account does not exist or is not a feature gate.
+    pub async fn feature_activation_slot(&self, feature_id: &str) -> Result<Option<Slot>, Box<dyn Error>> {
+        let account = self.get_account_info(feature_id).await?
+            .ok_or_else(|| format!("Account {} does not exist", feature_id))?;
+        if account.owner != FEATURE_PROGRAM_ID {
+            return Err(format!("Account {} is not a feature gate (owner: {})", feature_id, account.owner).into());
+        }
+        // The node cannot parse feature accounts, so jsonParsed falls back to base64
+        let data = account.data
+            .get(0)
+            .and_then(|data| data.as_str())
+            .filter(|_| account.data.get(1).and_then(|encoding| encoding.as_str()) == Some("base64"))
+            .and_then(decode_base64)
+            .ok_or_else(|| format!("The node did not return the data of feature {}", feature_id))?;
+        activation_slot(&data).ok_or_else(|| format!("Feature {} holds malformed data", feature_id).into())
+    }
+    
+    /// The slot, epoch and block time the feature gate `feature_id` activated at, or `None`
+    /// while it is still pending.
+    pub async fn feature_activation(&self, feature_id: &str) -> Result<Option<FeatureActivation>, Box<dyn Error>> {
+        let Some(slot) = self.feature_activation_slot(feature_id).await? else {
+            return Ok(None);
+        };
+        let schedule = self.get_epoch_schedule().await?;
+        let (epoch, _) = schedule.get_epoch_and_slot_index(slot);
+        let block_time = self.get_block_time(slot).await?;
+        Ok(Some(FeatureActivation { slot, epoch, block_time }))
+    }
+}
+
+/// The activation slot in the data of a feature account, a bincode `Option<u64>`: `Some(None)`
+/// while the feature is pending, `None` when the data is malformed
+fn activation_slot(data: &[u8]) -> Option<Option<Slot>> {
+    match data {
+        [0, ..] => Some(None),
+        [1, slot @ ..] => Some(Some(Slot(u64::from_le_bytes(slot.get(..8)?.try_into().ok()?)))),
+        _ => None,
+    }
+}
+
+/// Decodes standard base64 with or without padding, `None` when `input` is not valid base64
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    fn value(byte: u8) -> Option<u32> {
+        match byte {
+            b'A'..=b'Z' => Some((byte - b'A') as u32),
+            b'a'..=b'z' => Some((byte - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((byte - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+    
+    let input = input.as_bytes();
+    // Padding may only fill up the last chunk
+    let unpadded = input.strip_suffix(b"==").or_else(|| input.strip_suffix(b"=")).unwrap_or(input);
+    if (unpadded.len() != input.len() && !input.len().is_multiple_of(4)) || unpadded.len() % 4 == 1 {
+        return None;
+    }
+    let mut output = Vec::with_capacity(unpadded.len() * 3 / 4);
+    for chunk in unpadded.chunks(4) {
+        let mut bits: u32 = 0;
+        for byte in chunk {
+            bits = bits << 6 | value(*byte)?;
+        }
+        bits <<= 6 * (4 - chunk.len()) as u32;
+        output.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn decodes_full_chunks() {
+        assert_eq!(decode_base64("TWFu"), Some(b"Man".to_vec()));
+        assert_eq!(decode_base64("AQAAAAAAAAAA"), Some(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(decode_base64(""), Some(Vec::new()));
+    }
+    
+    #[test]
+    fn decodes_padded_chunks() {
+        assert_eq!(decode_base64("TWE="), Some(b"Ma".to_vec()));
+        assert_eq!(decode_base64("TQ=="), Some(b"M".to_vec()));
+        assert_eq!(decode_base64("AA=="), Some(vec![0]));
+        assert_eq!(decode_base64("+/+/"), Some(vec![0xfb, 0xff, 0xbf]));
+    }
+    
+    #[test]
+    fn decodes_unpadded_chunks() {
+        assert_eq!(decode_base64("TWE"), Some(b"Ma".to_vec()));
+        assert_eq!(decode_base64("TQ"), Some(b"M".to_vec()));
+        assert_eq!(decode_base64("TWFuTQ"), Some(b"ManM".to_vec()));
+    }
+    
+    #[test]
+    fn rejects_invalid_base64() {
+        // Characters outside the alphabet, including the URL-safe ones
+        assert_eq!(decode_base64("TW-u"), None);
+        assert_eq!(decode_base64("TW_u"), None);
+        assert_eq!(decode_base64("TW u"), None);
+        // A lone character of a chunk cannot encode a byte
+        assert_eq!(decode_base64("TWFuT"), None);
+        // Padding in the middle, too much of it, or not completing a chunk
+        assert_eq!(decode_base64("TQ==TWFu"), None);
+        assert_eq!(decode_base64("T==="), None);
+        assert_eq!(decode_base64("TWFuTQ="), None);
+    }
+    
+    #[test]
+    fn reads_pending_features() {
+        assert_eq!(activation_slot(&[0]), Some(None));
+    }
+    
+    #[test]
+    fn reads_activation_slots() {
+        let mut data = vec![1];
+        data.extend_from_slice(&432_000u64.to_le_bytes());
+        assert_eq!(activation_slot(&data), Some(Some(Slot(432_000))));
+        assert_eq!(activation_slot(&decode_base64("AYCXBgAAAAAA").unwrap()), Some(Some(Slot(432_000))));
+    }
+    
+    #[test]
+    fn rejects_malformed_feature_data() {
+        assert_eq!(activation_slot(&[]), None);
+        assert_eq!(activation_slot(&[2, 0, 0, 0, 0, 0, 0, 0, 0]), None);
+        // Activated, but the slot is cut short
+        assert_eq!(activation_slot(&[1]), None);
+        assert_eq!(activation_slot(&[1, 0x80, 0x97, 0x06, 0, 0, 0, 0]), None);
+    }
+}
@@ -0,0 +1,698 @@
+//! The configured finder and its builder
+//!
+//! A [`BlockFinder`] bundles everything a search needs (RPC backend,
+//! commitment, concurrency limit and cache). The RPC methods live in
+//! [`crate::rpc`] and the search in [`crate::search`], both as methods on
+//! `BlockFinder`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use reqwest::Client;
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::dns::Resolve;
+use tokio::sync::Semaphore;
+
+use crate::backend::RpcBackend;
+use crate::cache::{BlockTimeCache, DiscoveryMap, LruCache};
+use crate::cassette::Cassette;
+use crate::calibration::Calibration;
+use crate::estimate::SlotRate;
+use crate::chain::ChainProfile;
+use crate::clock::{Clock, SystemClock};
+use crate::index::RemoteIndex;
+use crate::redact::Redactor;
+use crate::rpc::{HttpBackend, SharedError};
+use crate::search::SearchProgress;
+use crate::singleflight::InFlight;
+use crate::types::{EndpointStats, Slot, UnixTs};
+
+/// Endpoint used when no other endpoint is configured
+pub const DEFAULT_RPC_URL: &str = "https://mainnet.helius-rpc.com";
+
+/// Default number of RPC requests allowed in flight at once
+pub const DEFAULT_CONCURRENCY: usize = 20;
+
+/// Default time a fetched current slot is reused, about one slot
+pub const DEFAULT_TIP_TTL: Duration = Duration::from_millis(400);
+
+/// Default number of slots probed on each side of a slot without a timestamp
+pub const DEFAULT_PROBE_WINDOW: u64 = 20;
+
+/// Default pause between binary search steps
+pub const DEFAULT_PROBE_DELAY: Duration = Duration::from_millis(10);
+
+/// Default pause between batches of the forward scan for equal timestamps
+pub const DEFAULT_SCAN_DELAY: Duration = Duration::from_millis(5);
+
+/// Default cap on binary search steps. A mainnet search converges in about 30, so
+/// hitting it points at a bug or a very unusual chain state.
+pub const DEFAULT_MAX_ITERATIONS: u32 = 200;
+
+/// Default limit for a whole RPC request, including reading the response
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default limit for establishing a connection to an endpoint
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// User-Agent sent by the default client unless overridden
+pub const DEFAULT_USER_AGENT: &str = concat!("solana-block-finder/", env!("CARGO_PKG_VERSION"));
+
+/// Default number of idle connections kept open per host
+pub const DEFAULT_POOL_MAX_IDLE_PER_HOST: usize = 20;
+
+/// Default time an idle pooled connection is kept before closing it
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default TCP keepalive interval
+pub const DEFAULT_TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// Commitment level used for RPC methods that accept one
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Commitment {
+    #[default]
+    Finalized,
+    Confirmed,
+}
+
+impl Commitment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Commitment::Finalized => "finalized",
+            Commitment::Confirmed => "confirmed",
+        }
+    }
+}
+
+/// How requests authenticate against the RPC provider
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Auth {
+    #[default]
+    None,
+    /// Sent as the `x-api-key` header (Helius style)
+    ApiKey(String),
+    /// Sent as `Authorization: Bearer <token>`
+    Bearer(String),
+}
+
+impl Auth {
+    /// The API key or token, if any
+    pub fn secret(&self) -> Option<&str> {
+        match self {
+            Auth::None => None,
+            Auth::ApiKey(secret) | Auth::Bearer(secret) => Some(secret),
+        }
+    }
+}
+
+/// Retry behaviour for failed requests, with exponential backoff between attempts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        RetryPolicy { max_retries: 0, ..Default::default() }
+    }
+    
+    /// Delay before retry number `attempt` (starting at 0)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+}
+
+/// Request rate allowed on a single endpoint, enforced with a token bucket
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Sustained requests per second
+    pub max_rps: f64,
+    /// Requests that may be sent at once after an idle period
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// `max_rps` requests per second with a burst of the same size
+    pub fn per_second(max_rps: f64) -> Self {
+        RateLimit { max_rps, burst: max_rps.ceil().max(1.0) as u32 }
+    }
+}
+
+/// Finds Solana blocks by timestamp. Create one with [`BlockFinder::builder`].
+#[derive(Clone)]
+pub struct BlockFinder {
+    pub(crate) backend: Arc<dyn RpcBackend>,
+    pub(crate) commitment: Commitment,
+    pub(crate) cache: Arc<dyn BlockTimeCache>,
+    pub(crate) limiter: Arc<Semaphore>,
+    pub(crate) checkpoint_file: Option<PathBuf>,
+    pub(crate) progress: Arc<Mutex<Option<SearchProgress>>>,
+    pub(crate) rpc_calls: Arc<AtomicU64>,
+    pub(crate) block_time_lookups: Arc<AtomicU64>,
+    pub(crate) cache_hits: Arc<AtomicU64>,
+    pub(crate) probe_window: u64,
+    pub(crate) probe_delay: Duration,
+    pub(crate) scan_delay: Duration,
+    pub(crate) max_rpc_calls: Option<u64>,
+    pub(crate) deadline: Option<Duration>,
+    pub(crate) max_iterations: u32,
+    pub(crate) best_effort: bool,
+    pub(crate) lowest: bool,
+    pub(crate) redactor: Arc<Redactor>,
+    pub(crate) block_time_flights: Arc<InFlight<Slot, Result<Option<UnixTs>, SharedError>>>,
+    // Last current slot and when it was fetched, on `clock`
+    pub(crate) tip: Arc<Mutex<Option<(Slot, u64)>>>,
+    pub(crate) tip_ttl: Duration,
+    // Last live slot rate and when it was fetched, on `clock`
+    pub(crate) slot_rate: Arc<Mutex<Option<(SlotRate, u64)>>>,
+    pub(crate) clock: Arc<dyn Clock>,
+    pub(crate) discovery: Arc<DiscoveryMap>,
+    pub(crate) chain: Arc<ChainProfile>,
+    pub(crate) calibration: Arc<Calibration>,
+    pub(crate) remote_index: Option<Arc<RemoteIndex>>,
+}
+
+impl BlockFinder {
+    pub fn builder() -> BlockFinderBuilder {
+        BlockFinderBuilder::default()
+    }
+    
+    /// The configured endpoints, in failover order
+    pub fn endpoints(&self) -> &[String] {
+        self.backend.endpoints()
+    }
+    
+    pub fn commitment(&self) -> Commitment {
+        self.commitment
+    }
+    
+    /// The chain this finder was configured for
+    pub fn chain(&self) -> &ChainProfile {
+        &self.chain
+    }
+    
+    /// Number of RPC calls made so far by this finder and its clones. Cache hits are not counted.
+    pub fn rpc_calls(&self) -> u64 {
+        self.rpc_calls.load(Ordering::Relaxed)
+    }
+    
+    /// Block time lookups answered by the cache so far, by this finder and its clones
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+    
+    /// Requests sent to each endpoint so far, if the backend counts them
+    pub fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        self.backend.endpoint_stats()
+    }
+    
+    /// Current Unix time on the finder's [`Clock`]
+    pub fn now(&self) -> Result<UnixTs, Box<dyn Error>> {
+        self.clock.unix_now()
+    }
+    
+    /// Whether `timestamp` is still ahead of the finder's [`Clock`], so no block can have it yet
+    pub fn is_in_future(&self, timestamp: UnixTs) -> Result<bool, Box<dyn Error>> {
+        Ok(timestamp > self.now()?)
+    }
+    
+    /// Block times seen so far, which searches use to narrow their starting range
+    pub fn discovery(&self) -> &DiscoveryMap {
+        &self.discovery
+    }
+    
+    /// Masks the API key, bearer token, secrets added with [`BlockFinderBuilder::secret`]
+    /// and key-like URL parameters in `text`, e.g. before logging an error
+    pub fn redact(&self, text: &str) -> String {
+        self.redactor.redact(text)
+    }
+    
+    /// Returns the state of the most recent search, if any.
+    pub fn search_progress(&self) -> Option<SearchProgress> {
+        self.progress.lock().ok().and_then(|p| p.clone())
+    }
+}
+
+/// Fluent configuration for [`BlockFinder`]
+#[derive(Default)]
+pub struct BlockFinderBuilder {
+    endpoints: Vec<String>,
+    auth: Auth,
+    commitment: Commitment,
+    concurrency: Option<usize>,
+    retry: RetryPolicy,
+    cache: Option<Arc<dyn BlockTimeCache>>,
+    discovery: Option<Arc<DiscoveryMap>>,
+    client: Option<Client>,
+    proxy: Option<String>,
+    user_agent: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    dns_overrides: HashMap<String, Vec<IpAddr>>,
+    #[cfg(not(target_arch = "wasm32"))]
+    dns_resolver: Option<Box<dyn Fn(reqwest::ClientBuilder) -> reqwest::ClientBuilder + Send + Sync>>,
+    backend: Option<Arc<dyn RpcBackend>>,
+    checkpoint_file: Option<PathBuf>,
+    rate_limits: Vec<(String, RateLimit)>,
+    secrets: Vec<String>,
+    tip_ttl: Option<Duration>,
+    probe_window: Option<u64>,
+    probe_delay: Option<Duration>,
+    scan_delay: Option<Duration>,
+    max_rpc_calls: Option<u64>,
+    deadline: Option<Duration>,
+    max_iterations: Option<u32>,
+    best_effort: bool,
+    lowest: bool,
+    chain: Option<ChainProfile>,
+    calibration: Option<Calibration>,
+    remote_index: Option<String>,
+    cassette: Option<Cassette>,
+    clock: Option<Arc<dyn Clock>>,
+}
+
+impl BlockFinderBuilder {
+    /// Adds an RPC endpoint. Additional endpoints are used as failover targets when retrying.
+    pub fn endpoint(mut self, url: impl Into<String>) -> Self {
+        self.endpoints.push(url.into());
+        self
+    }
+    
+    pub fn endpoints<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.endpoints.extend(urls.into_iter().map(Into::into));
+        self
+    }
+    
+    /// Authenticates with an `x-api-key` header
+    pub fn api_key(mut self, key: impl Into<String>) -> Self {
+        self.auth = Auth::ApiKey(key.into());
+        self
+    }
+    
+    /// Authenticates with an `Authorization: Bearer` header
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.auth = Auth::Bearer(token.into());
+        self
+    }
+    
+    pub fn commitment(mut self, commitment: Commitment) -> Self {
+        self.commitment = commitment;
+        self
+    }
+    
+    /// Maximum number of RPC requests in flight at once
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+    
+    /// Limits the request rate on one of the configured endpoints. When it is exhausted,
+    /// requests go to the next endpoint with capacity left, or wait for a token.
+    pub fn rate_limit(mut self, url: impl Into<String>, limit: RateLimit) -> Self {
+        self.rate_limits.push((url.into(), limit));
+        self
+    }
+    
+    pub fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+    
+    /// Block time cache backend. The default is an [`LruCache`] with 100 000 entries whose
+    /// entries within 150 slots of the tip expire after 5 seconds on the finder's clock.
+    pub fn cache(mut self, cache: Arc<dyn BlockTimeCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+    
+    /// Shares block times seen by searches with other finders, e.g. ones for other
+    /// endpoints. Clones of a finder always share them.
+    pub fn discovery_map(mut self, discovery: Arc<DiscoveryMap>) -> Self {
+        self.discovery = Some(discovery);
+        self
+    }
+    
+    /// Uses a preconfigured HTTP client instead of the default one
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+    
+    /// Asks the index server at `url` (see `index serve`) for the indexed blocks around
+    /// every target before searching, so the search starts between them
+    pub fn remote_index(mut self, url: impl Into<String>) -> Self {
+        self.remote_index = Some(url.into());
+        self
+    }
+    
+    /// Records the session of the default HTTP backend to `cassette`, or replays a recorded
+    /// one without contacting any endpoint (see [`Cassette`])
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+    
+    /// Sends all requests of the default client through this `http://` or `https://` proxy.
+    /// Hosts listed in `NO_PROXY` still bypass it. Without this, the `HTTPS_PROXY`, `HTTP_PROXY`
+    /// and `NO_PROXY` environment variables are honored.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+    
+    /// User-Agent header of the default client, [`DEFAULT_USER_AGENT`] by default.
+    /// Providers use it to attribute traffic, e.g. `"my-team-indexer/2.1"`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+    
+    /// Time limit for each RPC request of the default client, [`DEFAULT_TIMEOUT`] by default.
+    /// Archival `getBlock` calls can take much longer on some providers.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    
+    /// Time limit for connecting to an endpoint, [`DEFAULT_CONNECT_TIMEOUT`] by default
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    
+    /// Idle connections kept open per host, [`DEFAULT_POOL_MAX_IDLE_PER_HOST`] by default.
+    /// Keep it at or below the provider's connection limit; 0 disables reuse.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+    
+    /// How long an idle pooled connection is kept, [`DEFAULT_POOL_IDLE_TIMEOUT`] by default
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+    
+    /// TCP keepalive interval, [`DEFAULT_TCP_KEEPALIVE`] by default
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+    
+    /// Resolves `host` to `ip` instead of asking DNS, like curl's `--resolve`.
+    /// Calling it again for the same host adds more addresses.
+    pub fn resolve(mut self, host: impl Into<String>, ip: IpAddr) -> Self {
+        self.dns_overrides.entry(host.into()).or_default().push(ip);
+        self
+    }
+    
+    /// Resolves hostnames with a custom resolver (e.g. DNS-over-HTTPS) instead of the system one.
+    /// Addresses given with [`resolve`](Self::resolve) still take precedence.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn dns_resolver<R: Resolve + 'static>(mut self, resolver: Arc<R>) -> Self {
+        // reqwest wants the concrete resolver type, so keep it inside a closure until the client is built
+        self.dns_resolver = Some(Box::new(move |builder| builder.dns_resolver(resolver.clone())));
+        self
+    }
+    
+    /// Uses a custom RPC backend. The endpoint, authentication, client and retry
+    /// settings only apply to the default HTTP backend and are ignored.
+    pub fn backend(mut self, backend: Arc<dyn RpcBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+    
+    /// Writes the search state to this file after every probe so the search can be resumed
+    pub fn checkpoint_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.checkpoint_file = Some(path.into());
+        self
+    }
+    
+    /// Reads the time and sleeps on `clock` instead of the system clock, e.g. a
+    /// [`FakeClock`](crate::clock::FakeClock) in tests
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+    
+    /// How long a fetched current slot is reused by later calls, 400 ms by default.
+    /// Zero fetches it every time.
+    pub fn tip_ttl(mut self, ttl: Duration) -> Self {
+        self.tip_ttl = Some(ttl);
+        self
+    }
+    
+    /// Masks `secret` in log lines, error messages and trace spans, e.g. a token that is part
+    /// of an endpoint's path. The API key, bearer token and key-like URL parameters are
+    /// masked without it.
+    pub fn secret(mut self, secret: impl Into<String>) -> Self {
+        self.secrets.push(secret.into());
+        self
+    }
+    
+    /// Initial number of slots checked on each side of a slot without a timestamp.
+    /// The window then adapts to the block density it finds.
+    pub fn probe_window(mut self, slots: u64) -> Self {
+        self.probe_window = Some(slots);
+        self
+    }
+    
+    /// Pause between binary search steps. Raise it for shared free-tier endpoints,
+    /// or set it to zero for a private node.
+    pub fn probe_delay(mut self, delay: Duration) -> Self {
+        self.probe_delay = Some(delay);
+        self
+    }
+    
+    /// Pause between batches of the forward scan for blocks with the same timestamp
+    pub fn scan_delay(mut self, delay: Duration) -> Self {
+        self.scan_delay = Some(delay);
+        self
+    }
+    
+    /// Stops searches with a [`SearchStopped`](crate::search::SearchStopped) error once the
+    /// finder and its clones have made this many RPC calls in total
+    pub fn max_rpc_calls(mut self, calls: u64) -> Self {
+        self.max_rpc_calls = Some(calls);
+        self
+    }
+    
+    /// Stops each search with a [`SearchStopped`](crate::search::SearchStopped) error once it has
+    /// run this long, also cutting short a probe that is still waiting for its response
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+    
+    /// Stops each search with a [`SearchStopped`](crate::search::SearchStopped) error after this
+    /// many binary search steps instead of looping indefinitely
+    pub fn max_iterations(mut self, iterations: u32) -> Self {
+        self.max_iterations = Some(iterations);
+        self
+    }
+    
+    /// When a limit stops a search, [`BlockFinder::find_block_by_timestamp`] returns the closest
+    /// block found before the target, marked [`approximate`](crate::BlockMatch::approximate),
+    /// instead of an error
+    pub fn best_effort(mut self, best_effort: bool) -> Self {
+        self.best_effort = best_effort;
+        self
+    }
+    
+    /// When several blocks share the block time a search lands on, return the first of them
+    /// instead of the last
+    pub fn lowest(mut self, lowest: bool) -> Self {
+        self.lowest = lowest;
+        self
+    }
+    
+    /// Parameters of the chain the endpoints serve. Defaults to [`ChainProfile::solana`];
+    /// other chains need their endpoints set, as the default endpoint is Solana mainnet.
+    pub fn chain(mut self, chain: ChainProfile) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+    
+    /// Slot timing per era of the chain's history, e.g. from
+    /// [`BlockFinder::measure_calibration`]. Defaults to [`Calibration::nominal`].
+    pub fn calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+    
+    pub fn build(mut self) -> Result<BlockFinder, Box<dyn Error>> {
+        let concurrency = self.concurrency.unwrap_or(DEFAULT_CONCURRENCY);
+        if concurrency == 0 {
+            return Err("Concurrency must be at least 1".into());
+        }
+        let probe_window = self.probe_window.unwrap_or(DEFAULT_PROBE_WINDOW);
+        if probe_window == 0 {
+            return Err("Probe window must be at least 1".into());
+        }
+        let max_iterations = self.max_iterations.unwrap_or(DEFAULT_MAX_ITERATIONS);
+        if max_iterations == 0 {
+            return Err("Max iterations must be at least 1".into());
+        }
+        let chain = self.chain.take().unwrap_or_default();
+        if chain.slot_duration_ms == 0 {
+            return Err(format!("Slot duration of chain {} must be at least 1 ms", chain.name).into());
+        }
+        if let Some((url, _)) = self.rate_limits.iter().find(|(_, limit)| !limit.max_rps.is_finite() || limit.max_rps <= 0.0) {
+            return Err(format!("Rate limit for {} must be a positive number of requests per second", url).into());
+        }
+        
+        let calibration = self.calibration.take().unwrap_or_else(|| Calibration::nominal(&chain));
+        
+        let mut redactor = Redactor::new();
+        if let Some(secret) = self.auth.secret() {
+            redactor.add(secret);
+        }
+        for secret in self.secrets.drain(..) {
+            redactor.add(secret);
+        }
+        
+        let remote_index = match self.remote_index.take() {
+            Some(url) => {
+                let client = match &self.client {
+                    Some(client) => client.clone(),
+                    None => self.default_client().map_err(|e| redactor.redact(&e.to_string()))?,
+                };
+                Some(Arc::new(RemoteIndex::new(client, url)))
+            }
+            None => None,
+        };
+        
+        let clock = self.clock.take().unwrap_or_else(|| Arc::new(SystemClock::new()));
+        let backend: Arc<dyn RpcBackend> = match self.backend.take() {
+            Some(backend) => backend,
+            None => {
+                let client = match self.client.take() {
+                    Some(client) => client,
+                    None => self.default_client().map_err(|e| redactor.redact(&e.to_string()))?,
+                };
+                let endpoints = if self.endpoints.is_empty() {
+                    if chain.name != ChainProfile::solana().name {
+                        return Err(format!("Chain {} needs an RPC endpoint, the default one serves Solana mainnet", chain.name).into());
+                    }
+                    vec![DEFAULT_RPC_URL.to_string()]
+                } else {
+                    self.endpoints
+                };
+                let mut backend = HttpBackend::new(client, endpoints, self.auth, self.retry)
+                    .with_redactor(redactor.clone())
+                    .with_clock(clock.clone());
+                for (url, limit) in self.rate_limits {
+                    backend = backend.with_rate_limit(&url, limit);
+                }
+                if let Some(cassette) = self.cassette {
+                    backend = backend.with_cassette(cassette);
+                }
+                Arc::new(backend)
+            }
+        };
+        
+        Ok(BlockFinder {
+            backend,
+            commitment: self.commitment,
+            cache: self.cache.unwrap_or_else(|| Arc::new(LruCache::new().with_clock(clock.clone()))),
+            limiter: Arc::new(Semaphore::new(concurrency)),
+            checkpoint_file: self.checkpoint_file,
+            progress: Arc::new(Mutex::new(None)),
+            rpc_calls: Arc::new(AtomicU64::new(0)),
+            block_time_lookups: Arc::new(AtomicU64::new(0)),
+            cache_hits: Arc::new(AtomicU64::new(0)),
+            redactor: Arc::new(redactor),
+            block_time_flights: Arc::new(InFlight::default()),
+            tip: Arc::new(Mutex::new(None)),
+            slot_rate: Arc::new(Mutex::new(None)),
+            tip_ttl: self.tip_ttl.unwrap_or(DEFAULT_TIP_TTL),
+            clock,
+            discovery: self.discovery.unwrap_or_default(),
+            probe_window,
+            probe_delay: self.probe_delay.unwrap_or(DEFAULT_PROBE_DELAY),
+            scan_delay: self.scan_delay.unwrap_or(DEFAULT_SCAN_DELAY),
+            max_rpc_calls: self.max_rpc_calls,
+            deadline: self.deadline,
+            max_iterations,
+            best_effort: self.best_effort,
+            lowest: self.lowest,
+            chain: Arc::new(chain),
+            calibration: Arc::new(calibration),
+            remote_index,
+        })
+    }
+}
+
+impl BlockFinderBuilder {
+    // Initialize HTTP client with connection pooling and optimized settings
+    #[cfg(not(target_arch = "wasm32"))]
+    fn default_client(&self) -> Result<Client, Box<dyn Error>> {
+        let mut builder = Client::builder()
+            .user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+            .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+            .connect_timeout(self.connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT))
+            .pool_max_idle_per_host(self.pool_max_idle_per_host.unwrap_or(DEFAULT_POOL_MAX_IDLE_PER_HOST))
+            .pool_idle_timeout(self.pool_idle_timeout.unwrap_or(DEFAULT_POOL_IDLE_TIMEOUT))
+            .tcp_keepalive(self.tcp_keepalive.unwrap_or(DEFAULT_TCP_KEEPALIVE))
+            // Full blocks are megabytes of JSON and compress very well
+            .gzip(true)
+            .brotli(true);
+        if let Some(proxy) = &self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| format!("Invalid proxy URL '{}': {}", proxy, e))?
+                .no_proxy(reqwest::NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+        if let Some(with_resolver) = &self.dns_resolver {
+            builder = with_resolver(builder);
+        }
+        for (host, ips) in &self.dns_overrides {
+            // The port is ignored by reqwest, the URL's port is used
+            let addrs: Vec<std::net::SocketAddr> = ips.iter().map(|ip| (*ip, 0).into()).collect();
+            builder = builder.resolve_to_addrs(host, &addrs);
+        }
+        Ok(with_tls_backend(builder).build()?)
+    }
+    
+    // The browser's fetch owns timeouts, connection pooling, proxies and DNS
+    #[cfg(target_arch = "wasm32")]
+    fn default_client(&self) -> Result<Client, Box<dyn Error>> {
+        Ok(Client::builder().build()?)
+    }
+}
+
+/// Selects the TLS implementation chosen with the `rustls` / `native-tls` features.
+/// Without either, only plain `http://` endpoints work.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_tls_backend(builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    #[cfg(feature = "rustls")]
+    let builder = builder.use_rustls_tls();
+    #[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+    let builder = builder.use_native_tls();
+    builder
+}
@@ -0,0 +1,86 @@
+//! Live slots and block times from a Yellowstone Geyser gRPC endpoint
+//!
+//! Enabled with the `geyser` feature. Long-running processes that keep one
+//! [`BlockFinder`] around can subscribe it to slot and block-meta updates
+//! instead of polling. [`BlockFinder::follow_geyser`] keeps the current slot
+//! fresh, so `getSlot` is no longer called while the stream is live, and
+//! caches the block time of every new block, so searches near the tip are
+//! answered without RPC calls.
+
+use std::collections::HashMap;
+use std::error::Error;
+use futures::{SinkExt, StreamExt};
+use yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient};
+use yellowstone_grpc_proto::prelude::subscribe_update::UpdateOneof;
+use yellowstone_grpc_proto::prelude::{
+    CommitmentLevel, SlotStatus, SubscribeRequest, SubscribeRequestFilterBlocksMeta, SubscribeRequestFilterSlots, SubscribeRequestPing,
+};
+
+use crate::finder::{BlockFinder, Commitment};
+use crate::types::{Slot, UnixTs};
+
+impl BlockFinder {
+    /// Subscribes to `endpoint` at the finder's commitment and feeds every update into the
+    /// finder and its clones. Runs until the stream fails or the server closes it, callers
+    /// that want to stay subscribed reconnect by calling it again.
+    ///
+    /// `x_token` is the access token most Geyser providers require.
+    pub async fn follow_geyser(&self, endpoint: &str, x_token: Option<String>) -> Result<(), Box<dyn Error>> {
+        let mut builder = GeyserGrpcClient::build_from_shared(endpoint.to_string())?.x_token(x_token)?;
+        if endpoint.starts_with("https://") {
+            builder = builder.tls_config(ClientTlsConfig::new().with_native_roots())?;
+        }
+        let mut client = builder.connect().await?;
+        
+        let (commitment, status) = match self.commitment {
+            Commitment::Finalized => (CommitmentLevel::Finalized, SlotStatus::SlotFinalized),
+            Commitment::Confirmed => (CommitmentLevel::Confirmed, SlotStatus::SlotConfirmed),
+        };
+        let request = SubscribeRequest {
+            slots: HashMap::from([(
+                "block-finder".to_string(),
+                SubscribeRequestFilterSlots { filter_by_commitment: Some(true), ..Default::default() },
+            )]),
+            blocks_meta: HashMap::from([("block-finder".to_string(), SubscribeRequestFilterBlocksMeta::default())]),
+            commitment: Some(commitment as i32),
+            ..Default::default()
+        };
+        let (mut sink, mut stream) = client.subscribe_with_request(Some(request)).await?;
+        
+        while let Some(update) = stream.next().await {
+            match update?.update_oneof {
+                Some(UpdateOneof::Slot(update)) if update.status == status as i32 => self.observe_live_tip(Slot(update.slot)),
+                Some(UpdateOneof::BlockMeta(meta)) => {
+                    if let Some(block_time) = meta.block_time {
+                        self.observe_live_block(Slot(meta.slot), UnixTs(block_time.timestamp));
+                    }
+                }
+                // Providers close subscriptions that do not answer their pings
+                Some(UpdateOneof::Ping(_)) => {
+                    let pong = SubscribeRequest { ping: Some(SubscribeRequestPing { id: 1 }), ..Default::default() };
+                    sink.send(pong).await?;
+                }
+                _ => {}
+            }
+        }
+        Err(format!("Geyser stream from {} ended", endpoint).into())
+    }
+    
+    /// Takes a streamed slot as the current slot, as if `getSlot` had just returned it
+    fn observe_live_tip(&self, slot: Slot) {
+        let fetched_at_ms = self.clock.elapsed_ms();
+        if let Ok(mut tip) = self.tip.lock() {
+            // Updates can arrive out of order around forks
+            if tip.is_some_and(|(current, _)| current > slot) {
+                return;
+            }
+            *tip = Some((slot, fetched_at_ms));
+        }
+        self.cache.observe_tip(slot);
+    }
+    
+    fn observe_live_block(&self, slot: Slot, block_time: UnixTs) {
+        self.cache.insert(slot, Some(block_time));
+        self.discovery.record(slot, block_time);
+    }
+}
@@ -0,0 +1,77 @@
+//! Account history from `getSignaturesForAddress`
+//!
+//! Signatures are returned newest first, at most 1000 per page, so finding
+//! the start of an account's history means paging through all of it. Nodes
+//! only return the history they keep, so on a non-archival node the oldest
+//! signature is only the oldest one still retained.
+
+use std::error::Error;
+
+use crate::finder::BlockFinder;
+use crate::rpc::SignatureInfo;
+
+/// Signatures requested per `getSignaturesForAddress` page, the RPC maximum
+pub const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+/// Owner of programs deployed with `solana program deploy`
+pub const UPGRADEABLE_LOADER_ID: &str = "BPFLoaderUpgradeab1e11111111111111111111111";
+
+impl BlockFinder {
+    /// Pages through the whole signature history of `address` and returns its oldest
+    /// transaction, normally the one that created the account. Returns `None` for an
+    /// address without transactions.
+    pub async fn earliest_signature(&self, address: &str) -> Result<Option<SignatureInfo>, Box<dyn Error>> {
+        let mut oldest = None;
+        self.for_each_signature_page(address, |page| oldest = page.into_iter().last().or(oldest.take())).await?;
+        Ok(oldest)
+    }
+    
+    /// Every signature of `address`, oldest first. Meant for accounts with short histories,
+    /// such as program data accounts, since the whole history is held in memory.
+    pub async fn signature_history(&self, address: &str) -> Result<Vec<SignatureInfo>, Box<dyn Error>> {
+        let mut history = Vec::new();
+        self.for_each_signature_page(address, |page| history.extend(page)).await?;
+        history.reverse();
+        Ok(history)
+    }
+    
+    /// The program data account of a program deployed with the upgradeable loader, where its
+    /// code lives and which every deployment and upgrade writes to. `None` for programs of
+    /// the older, immutable loaders.
+    pub async fn program_data_address(&self, program_id: &str) -> Result<Option<String>, Box<dyn Error>> {
+        let account = self.get_account_info(program_id).await?
+            .ok_or_else(|| format!("Account {} does not exist", program_id))?;
+        if !account.executable {
+            return Err(format!("Account {} is not a program", program_id).into());
+        }
+        if account.owner != UPGRADEABLE_LOADER_ID {
+            return Ok(None);
+        }
+        let program_data = account.data
+            .pointer("/parsed/info/programData")
+            .and_then(|address| address.as_str())
+            .ok_or_else(|| format!("The node did not return the program data account of {}", program_id))?;
+        Ok(Some(program_data.to_string()))
+    }
+    
+    /// Calls `visit` with every page of signatures of `address`, newest first.
+    async fn for_each_signature_page(&self, address: &str, mut visit: impl FnMut(Vec<SignatureInfo>)) -> Result<(), Box<dyn Error>> {
+        let mut before: Option<String> = None;
+        let mut pages: usize = 0;
+        loop {
+            let page = self.get_signatures_for_address(address, before.as_deref(), None, SIGNATURE_PAGE_SIZE).await?;
+            let page_len = page.len();
+            before = page.last().map(|info| info.signature.clone());
+            visit(page);
+            if page_len < SIGNATURE_PAGE_SIZE {
+                return Ok(());
+            }
+            
+            pages += 1;
+            if pages.is_multiple_of(10) {
+                tracing::debug!("Paged through {} signatures of {} so far", pages * SIGNATURE_PAGE_SIZE, address);
+            }
+            self.clock.sleep(self.scan_delay).await;
+        }
+    }
+}
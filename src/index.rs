@@ -0,0 +1,324 @@
+//! Local slot → block time index
+//!
+//! A [`SlotIndex`] is a file of known block times, built up over time by
+//! [`BlockFinder::crawl_index`]. Every entry is an anchor for later searches:
+//! seeded into a finder's [`DiscoveryMap`], the nearest anchors around a
+//! target narrow the search to a few slots before any RPC call is made.
+//!
+//! Indices move between machines as exports: [`SlotIndex::export`] writes a
+//! versioned JSONL file that [`SlotIndex::import`] merges into another index.
+//! An index can also be shared live: the CLI's `index serve` exposes it over HTTP,
+//! and finders built with [`remote_index`](crate::BlockFinderBuilder::remote_index)
+//! ask such a server for the anchors around each target through [`RemoteIndex`].
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use futures::future::join_all;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::DiscoveryMap;
+use crate::finder::BlockFinder;
+use crate::schema::SlotTimeRecord;
+use crate::types::{Slot, UnixTs};
+
+/// Default distance between the slots a crawl samples, about 7 minutes of blocks
+pub const DEFAULT_CRAWL_STRIDE: u64 = 1_000;
+
+/// Samples fetched between two saves of a crawl
+pub const CRAWL_BATCH_SAMPLES: usize = 100;
+
+/// Most entries an index server returns for one range request
+pub const MAX_RANGE_ENTRIES: usize = 10_000;
+
+/// Name in the header line of an index export
+pub const EXPORT_FORMAT: &str = "solana-block-finder-index";
+
+/// Version of the export format. Readers reject versions they do not know.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// First line of an export; every further line is one `[slot, block_time]` entry
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportHeader {
+    format: String,
+    version: u32,
+    entries: usize,
+}
+
+/// Block times of sampled slots, plus where a crawl left off
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SlotIndex {
+    pub entries: BTreeMap<Slot, UnixTs>,
+    #[serde(default)]
+    pub crawl: Option<CrawlProgress>,
+}
+
+/// State of a crawl, saved with the index so a stopped crawl resumes where it was.
+///
+/// A pass walks down from the tip at the time it started to `pass_floor`. The first pass
+/// goes all the way to the oldest available block, later ones only down to where the
+/// previous pass started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlProgress {
+    pub stride: u64,
+    pub pass_top: Slot,
+    pub pass_floor: Slot,
+    /// Next slot to sample, `None` once the pass is complete
+    pub next_slot: Option<Slot>,
+}
+
+impl SlotIndex {
+    /// Reads the index at `path`, failing if it does not exist
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("❌ Cannot read index {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("❌ Invalid index {}: {}", path.display(), e).into())
+    }
+    
+    /// Reads the index at `path`, or starts an empty one if there is none yet
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        if path.as_ref().exists() {
+            Self::read(path)
+        } else {
+            Ok(SlotIndex::default())
+        }
+    }
+    
+    /// Writes the index atomically so an interrupted crawl never leaves a corrupt file behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        std::fs::write(&temp_path, serde_json::to_string(self)?)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+    
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    
+    /// Makes every entry known to the finders sharing `discovery`
+    pub fn seed(&self, discovery: &DiscoveryMap) {
+        for (&slot, &block_time) in &self.entries {
+            discovery.record(slot, block_time);
+        }
+    }
+    
+    /// Writes every entry in the export format: a JSON header line followed by one
+    /// `[slot, block_time]` line per entry, in slot order
+    pub fn export(&self, mut writer: impl Write) -> Result<(), Box<dyn Error>> {
+        let header = ExportHeader { format: EXPORT_FORMAT.to_string(), version: EXPORT_VERSION, entries: self.entries.len() };
+        writeln!(writer, "{}", serde_json::to_string(&header)?)?;
+        for (slot, block_time) in &self.entries {
+            writeln!(writer, "[{},{}]", slot, block_time)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+    
+    /// Merges an export into this index and returns how many entries it contained.
+    /// Where both have a slot, the imported block time wins. Crawl progress is kept.
+    pub fn import(&mut self, reader: impl BufRead) -> Result<usize, Box<dyn Error>> {
+        let mut lines = reader.lines();
+        let header: ExportHeader = match lines.next() {
+            Some(line) => serde_json::from_str(&line?).map_err(|_| "Not an index export: the header line is missing")?,
+            None => return Err("Not an index export: the file is empty".into()),
+        };
+        if header.format != EXPORT_FORMAT {
+            return Err(format!("Not an index export: unknown format '{}'", header.format).into());
+        }
+        if header.version != EXPORT_VERSION {
+            return Err(format!("Index export version {} is not supported, this build reads version {}", header.version, EXPORT_VERSION).into());
+        }
+        
+        // Nothing is merged unless the whole export is valid
+        let mut entries: Vec<(Slot, UnixTs)> = Vec::new();
+        for (number, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|e| format!("Invalid entry on line {}: {}", number + 2, e))?;
+            entries.push(entry);
+        }
+        if entries.len() != header.entries {
+            return Err(format!("Truncated index export: {} of {} entries", entries.len(), header.entries).into());
+        }
+        let count = entries.len();
+        self.entries.extend(entries);
+        Ok(count)
+    }
+    
+    /// Shrinks the index: drops entries with a block time before `oldest`, then thins the rest
+    /// so kept entries are at least `resolution_secs` apart. The oldest and newest entries
+    /// are always kept. Returns how many entries were removed.
+    pub fn compact(&mut self, resolution_secs: i64, oldest: Option<UnixTs>) -> usize {
+        let before = self.entries.len();
+        if let Some(oldest) = oldest {
+            self.entries.retain(|_, block_time| *block_time >= oldest);
+        }
+        
+        let newest = self.entries.last_key_value().map(|(&slot, _)| slot);
+        let mut last_kept: Option<UnixTs> = None;
+        self.entries.retain(|&slot, &mut block_time| {
+            let keep = last_kept.is_none_or(|kept| block_time - kept >= resolution_secs) || Some(slot) == newest;
+            if keep {
+                last_kept = Some(block_time);
+            }
+            keep
+        });
+        before - self.entries.len()
+    }
+    
+    /// Entries from `from_slot` to `to_slot`, at most [`MAX_RANGE_ENTRIES`] of them
+    pub fn range(&self, from_slot: Slot, to_slot: Slot) -> Vec<SlotTimeRecord> {
+        if from_slot > to_slot {
+            return Vec::new();
+        }
+        self.entries
+            .range(from_slot..=to_slot)
+            .take(MAX_RANGE_ENTRIES)
+            .map(|(&slot, &block_time)| SlotTimeRecord::new(slot, block_time))
+            .collect()
+    }
+}
+
+/// The indexed blocks closest to a timestamp, as returned by an index server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NearestAnchors {
+    /// Latest indexed block before the timestamp
+    pub before: Option<SlotTimeRecord>,
+    /// Earliest indexed block after the timestamp
+    pub after: Option<SlotTimeRecord>,
+}
+
+impl NearestAnchors {
+    pub fn of(discovery: &DiscoveryMap, target: UnixTs) -> Self {
+        let (before, after) = discovery.bounds(target);
+        NearestAnchors {
+            before: before.map(|(slot, block_time)| SlotTimeRecord::new(slot, block_time)),
+            after: after.map(|(slot, block_time)| SlotTimeRecord::new(slot, block_time)),
+        }
+    }
+}
+
+/// Client of an index served by `index serve`
+#[derive(Debug, Clone)]
+pub struct RemoteIndex {
+    client: Client,
+    url: String,
+}
+
+impl RemoteIndex {
+    /// `url` is the server's base URL, e.g. `http://indexer.internal:8900`
+    pub fn new(client: Client, url: impl Into<String>) -> Self {
+        let url = url.into().trim_end_matches('/').to_string();
+        RemoteIndex { client, url }
+    }
+    
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+    
+    pub async fn nearest(&self, target: UnixTs) -> Result<NearestAnchors, Box<dyn Error>> {
+        let response = self.client
+            .get(format!("{}/nearest", self.url))
+            .query(&[("timestamp", target.0)])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+    
+    /// Indexed blocks from `from_slot` to `to_slot`. Servers return at most
+    /// [`MAX_RANGE_ENTRIES`] per request, continue after the last one for more.
+    pub async fn range(&self, from_slot: Slot, to_slot: Slot) -> Result<Vec<SlotTimeRecord>, Box<dyn Error>> {
+        let response = self.client
+            .get(format!("{}/range", self.url))
+            .query(&[("from_slot", from_slot.0), ("to_slot", to_slot.0)])
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+}
+
+impl BlockFinder {
+    /// Records the remote index's anchors around `target_timestamp`, so the search starts
+    /// between them. An unreachable index only costs the search its head start.
+    pub(crate) async fn consult_remote_index(&self, target_timestamp: UnixTs) {
+        let Some(index) = &self.remote_index else { return };
+        match index.nearest(target_timestamp).await {
+            Ok(anchors) => {
+                for anchor in anchors.before.iter().chain(&anchors.after) {
+                    self.discovery.record(anchor.slot, anchor.block_time);
+                }
+            }
+            Err(e) => tracing::warn!("Index {} unavailable: {}", index.url(), self.redact(&e.to_string())),
+        }
+    }
+    
+    /// Walks the chain backward from the tip, recording the block time of every `stride`th
+    /// slot in `index`. `on_batch` is called after every [`CRAWL_BATCH_SAMPLES`] samples,
+    /// typically to save the index, so the crawl can be stopped at any time and resumed by
+    /// calling this again with the saved index.
+    ///
+    /// Requests go through the finder's rate limits and concurrency limit like any other.
+    /// Once a pass has reached the oldest block, the next call crawls only the blocks
+    /// produced since.
+    pub async fn crawl_index(
+        &self,
+        index: &mut SlotIndex,
+        stride: u64,
+        mut on_batch: impl FnMut(&SlotIndex) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(), Box<dyn Error>> {
+        if stride == 0 {
+            return Err("Crawl stride must be at least one slot".into());
+        }
+        let mut progress = match index.crawl.take() {
+            Some(progress) if progress.next_slot.is_some() => progress,
+            previous => {
+                let current_slot = self.get_current_slot().await?;
+                let pass_floor = match &previous {
+                    Some(previous) => previous.pass_top + 1,
+                    None => self.get_first_available_block().await?.max(self.chain.first_slot()),
+                };
+                CrawlProgress {
+                    stride,
+                    pass_top: current_slot,
+                    pass_floor,
+                    next_slot: (current_slot >= pass_floor).then_some(current_slot),
+                }
+            }
+        };
+        progress.stride = stride;
+        
+        while let Some(next_slot) = progress.next_slot {
+            let slots: Vec<Slot> = (0..CRAWL_BATCH_SAMPLES as u64)
+                .map_while(|i| next_slot.checked_sub(i.saturating_mul(stride)).filter(|&slot| slot >= progress.pass_floor))
+                .collect();
+            let block_times = join_all(slots.iter().map(|&slot| self.get_block_time(slot))).await;
+            for (&slot, block_time) in slots.iter().zip(block_times) {
+                // Skipped slots have no block time and are left out
+                if let Some(block_time) = block_time? {
+                    index.entries.insert(slot, block_time);
+                }
+            }
+            
+            let last_slot = slots.last().copied().unwrap_or(next_slot);
+            progress.next_slot = last_slot.checked_sub(stride).filter(|&slot| slot >= progress.pass_floor);
+            index.crawl = Some(progress.clone());
+            on_batch(index)?;
+        }
+        index.crawl = Some(progress);
+        Ok(())
+    }
+}
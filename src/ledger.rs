@@ -0,0 +1,209 @@
+//! [`RpcBackend`] reading a validator's local ledger
+//!
+//! Enabled with the `ledger` feature. Validator operators have the whole
+//! block history on disk, so [`LedgerBackend`] answers the search straight
+//! from the blockstore (the RocksDB database in the ledger directory), offline
+//! and without rate limits. The blockstore is opened with secondary access,
+//! which works next to a running validator and sees the ledger as it was when
+//! opened.
+//!
+//! Only rooted slots are visible, so every commitment behaves like
+//! `finalized`. Lookups the blockstore cannot answer, such as accounts or the
+//! epoch schedule, fail with an error.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use serde_json::Value;
+use solana_ledger::blockstore::{Blockstore, BlockstoreError};
+use solana_ledger::blockstore_meta::PerfSample;
+use solana_ledger::blockstore_options::{AccessType, BlockstoreOptions};
+
+use crate::backend::{BackendFuture, RpcBackend};
+use crate::epoch::EpochSchedule;
+use crate::finder::Commitment;
+use crate::rpc::{AccountInfo, BlockInfo, EpochInfo, PerformanceSample, Reward, RpcError, SignatureInfo, VersionInfo, VoteAccounts};
+use crate::types::{Slot, UnixTs};
+
+type LedgerResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Backend on top of `solana_ledger::blockstore::Blockstore`
+pub struct LedgerBackend {
+    blockstore: Arc<Blockstore>,
+    endpoints: Vec<String>,
+}
+
+impl LedgerBackend {
+    /// Opens the ledger directory of a validator, the one passed to it as `--ledger`.
+    pub fn open(ledger_path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let ledger_path = ledger_path.as_ref();
+        let options = BlockstoreOptions {
+            access_type: AccessType::Secondary,
+            ..BlockstoreOptions::default()
+        };
+        let blockstore = Blockstore::open_with_options(ledger_path, options)
+            .map_err(|e| format!("Cannot open the ledger at {}: {}", ledger_path.display(), e))?;
+        Ok(Self::from_blockstore(Arc::new(blockstore), ledger_path.to_path_buf()))
+    }
+    
+    /// Wraps a blockstore that is already open, e.g. inside a validator plugin.
+    pub fn from_blockstore(blockstore: Arc<Blockstore>, ledger_path: PathBuf) -> Self {
+        let endpoints = vec![format!("ledger:{}", ledger_path.display())];
+        LedgerBackend { blockstore, endpoints }
+    }
+    
+    /// Runs a blockstore read on the blocking thread pool, RocksDB reads hit the disk
+    fn read<T, F>(&self, read: F) -> BackendFuture<'_, T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Blockstore) -> LedgerResult<T> + Send + 'static,
+    {
+        let blockstore = Arc::clone(&self.blockstore);
+        Box::pin(async move {
+            let result = tokio::task::spawn_blocking(move || read(&blockstore)).await?;
+            result.map_err(|e| -> Box<dyn Error> { e })
+        })
+    }
+}
+
+/// Whether the blockstore has no block for the slot, like a skipped slot over RPC
+fn is_missing(error: &BlockstoreError) -> bool {
+    matches!(error, BlockstoreError::SlotNotRooted | BlockstoreError::SlotUnavailable | BlockstoreError::SlotCleanedUp)
+}
+
+/// Reports a missing block with the error code an RPC node uses, so the search probes around it
+fn missing_block_error(slot: Slot, error: BlockstoreError) -> Box<dyn Error + Send + Sync> {
+    if is_missing(&error) {
+        Box::new(RpcError {
+            code: -32007,
+            message: format!("Slot {} was skipped, or missing in the local ledger", slot),
+        })
+    } else {
+        error.into()
+    }
+}
+
+fn unsupported<'a, T: Send + 'a>(method: &'static str) -> BackendFuture<'a, T> {
+    Box::pin(async move { Err(format!("{} is not available from a local ledger", method).into()) })
+}
+
+impl RpcBackend for LedgerBackend {
+    fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+    
+    fn get_slot(&self, _commitment: Commitment) -> BackendFuture<'_, Slot> {
+        self.read(|blockstore| Ok(Slot(blockstore.max_root())))
+    }
+    
+    fn get_block_time(&self, slot: Slot) -> BackendFuture<'_, Option<UnixTs>> {
+        self.read(move |blockstore| match blockstore.get_rooted_block_time(slot.0) {
+            Ok(block_time) => Ok(Some(UnixTs(block_time))),
+            Err(e) if is_missing(&e) => Ok(None),
+            Err(e) => Err(e.into()),
+        })
+    }
+    
+    fn get_block_info(&self, slot: Slot, include_rewards: bool, _commitment: Commitment) -> BackendFuture<'_, BlockInfo> {
+        self.read(move |blockstore| {
+            let block = blockstore.get_rooted_block(slot.0, false).map_err(|e| missing_block_error(slot, e))?;
+            let rewards = if include_rewards { block.rewards } else { Vec::new() };
+            Ok(BlockInfo {
+                blockhash: block.blockhash,
+                parent_slot: Slot(block.parent_slot),
+                block_time: block.block_time.map(UnixTs),
+                block_height: block.block_height,
+                rewards: rewards
+                    .into_iter()
+                    .map(|reward| Reward {
+                        pubkey: reward.pubkey,
+                        lamports: reward.lamports,
+                        post_balance: reward.post_balance,
+                        reward_type: reward.reward_type.map(|t| t.to_string()),
+                        commission: reward.commission,
+                    })
+                    .collect(),
+            })
+        })
+    }
+    
+    fn get_full_block(&self, _slot: Slot, _commitment: Commitment) -> BackendFuture<'_, Value> {
+        unsupported("getBlock with transactions")
+    }
+    
+    fn get_epoch_schedule(&self) -> BackendFuture<'_, EpochSchedule> {
+        unsupported("getEpochSchedule")
+    }
+    
+    fn get_epoch_info(&self, _commitment: Commitment) -> BackendFuture<'_, EpochInfo> {
+        unsupported("getEpochInfo")
+    }
+    
+    fn get_version(&self) -> BackendFuture<'_, VersionInfo> {
+        unsupported("getVersion")
+    }
+    
+    fn get_genesis_hash(&self) -> BackendFuture<'_, String> {
+        unsupported("getGenesisHash")
+    }
+    
+    fn get_first_available_block(&self) -> BackendFuture<'_, Slot> {
+        self.read(|blockstore| Ok(Slot(blockstore.get_first_available_block()?)))
+    }
+    
+    fn get_blocks_with_limit(&self, start_slot: Slot, limit: u64, _commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        self.read(move |blockstore| {
+            let limit = usize::try_from(limit)?;
+            Ok(blockstore.rooted_slot_iterator(start_slot.0)?.take(limit).map(Slot).collect())
+        })
+    }
+    
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot, _commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        self.read(move |blockstore| {
+            Ok(blockstore.rooted_slot_iterator(start_slot.0)?.take_while(|slot| *slot <= end_slot.0).map(Slot).collect())
+        })
+    }
+    
+    fn get_slot_leaders(&self, _start_slot: Slot, _limit: u64) -> BackendFuture<'_, Vec<String>> {
+        unsupported("getSlotLeaders")
+    }
+    
+    fn get_transaction_slot<'a>(&'a self, _signature: &'a str, _commitment: Commitment) -> BackendFuture<'a, (Slot, Option<UnixTs>)> {
+        unsupported("getTransaction")
+    }
+    
+    fn get_signatures_for_address<'a>(
+        &'a self,
+        _address: &'a str,
+        _before: Option<&'a str>,
+        _min_context_slot: Option<Slot>,
+        _limit: usize,
+        _commitment: Commitment,
+    ) -> BackendFuture<'a, Vec<SignatureInfo>> {
+        unsupported("getSignaturesForAddress")
+    }
+    
+    fn get_account_info<'a>(&'a self, _address: &'a str, _commitment: Commitment) -> BackendFuture<'a, Option<AccountInfo>> {
+        unsupported("getAccountInfo")
+    }
+    
+    fn get_recent_performance_samples(&self, limit: usize) -> BackendFuture<'_, Vec<PerformanceSample>> {
+        self.read(move |blockstore| {
+            let samples = blockstore.get_recent_perf_samples(limit)?;
+            Ok(samples
+                .into_iter()
+                .map(|(slot, sample)| {
+                    let (num_slots, num_transactions, sample_period_secs) = match sample {
+                        PerfSample::V1(s) => (s.num_slots, s.num_transactions, s.sample_period_secs),
+                        PerfSample::V2(s) => (s.num_slots, s.num_transactions, s.sample_period_secs),
+                    };
+                    PerformanceSample { slot: Slot(slot), num_slots, num_transactions, sample_period_secs }
+                })
+                .collect())
+        })
+    }
+    
+    fn get_vote_accounts(&self, _commitment: Commitment) -> BackendFuture<'_, VoteAccounts> {
+        unsupported("getVoteAccounts")
+    }
+}
@@ -0,0 +1,57 @@
+//! Find Solana blocks by timestamp.
+//!
+//! The library exposes the RPC helpers and the binary search used by the
+//! `solana-block-finder` CLI. Configure a [`BlockFinder`] with
+//! [`BlockFinder::builder`], then call
+//! [`BlockFinder::find_block_by_timestamp`], which returns a [`BlockMatch`].
+
+#[cfg(all(feature = "blocking", not(target_arch = "wasm32")))]
+pub mod blocking;
+pub mod backend;
+pub mod batch;
+pub mod block_stats;
+pub mod cache;
+pub mod calibration;
+pub mod cassette;
+pub mod chain;
+pub mod clock;
+pub mod epoch;
+pub mod estimate;
+#[cfg(feature = "parquet")]
+pub mod export;
+pub mod feature;
+pub mod finder;
+#[cfg(all(feature = "geyser", not(target_arch = "wasm32")))]
+pub mod geyser;
+pub mod history;
+pub mod index;
+#[cfg(all(feature = "ledger", not(target_arch = "wasm32")))]
+pub mod ledger;
+#[cfg(all(feature = "bigtable", not(target_arch = "wasm32")))]
+pub mod bigtable;
+mod ratelimit;
+pub mod redact;
+pub mod rpc;
+mod runtime;
+pub mod schema;
+pub mod search;
+mod search_state;
+mod singleflight;
+#[cfg(feature = "solana-client")]
+pub mod solana_client;
+pub mod stream;
+#[cfg(all(feature = "otlp", not(target_arch = "wasm32")))]
+pub mod telemetry;
+pub mod types;
+
+pub use backend::RpcBackend;
+pub use cache::{BlockTimeCache, DiscoveryMap, LruCache, MemoryCache, NoCache};
+pub use chain::{ChainProfile, Cluster};
+pub use finder::{Auth, BlockFinder, BlockFinderBuilder, Commitment, RateLimit, RetryPolicy};
+pub use rpc::HttpBackend;
+pub use types::{BlockMatch, EndpointStats, SearchDiagnostics, Slot, UnixTs};
+
+pub const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Target slot duration of the Solana mainnet cluster
+pub const NOMINAL_SLOT_DURATION_MS: u64 = 400;
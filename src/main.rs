@@ -1,365 +1,410 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
 use std::env;
 use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use reqwest::Client;
-use serde::Deserialize;
-use serde_json::{json, Value};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, SecondsFormat};
+use clap::{Arg, ArgAction};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tokio::time::sleep;
-use futures::future::join_all;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
 
-// RPC response structures
-#[derive(Debug, Deserialize)]
-struct RpcResponse<T> {
-    jsonrpc: String,
-    id: String,
-    result: Option<T>,
-    error: Option<RpcError>,
+use solana_block_finder::batch::DEFAULT_BATCH_CONCURRENCY;
+use solana_block_finder::block_stats;
+use solana_block_finder::epoch::EpochSchedule;
+use solana_block_finder::calibration::{Calibration, MeasuredRate, DEFAULT_CALIBRATION_SAMPLES};
+use solana_block_finder::estimate::{SlotEstimate, CALIBRATED_RATE_MAX_AGE_SECS};
+use solana_block_finder::cache::DiscoveryMap;
+use solana_block_finder::cassette::Cassette;
+use solana_block_finder::index::{NearestAnchors, SlotIndex, DEFAULT_CRAWL_STRIDE};
+use solana_block_finder::redact::Redactor;
+use solana_block_finder::rpc::Reward;
+use solana_block_finder::schema::{BlockMatchRecord, SlotTimeRecord};
+use solana_block_finder::search::read_checkpoint;
+use solana_block_finder::{BlockFinder, BlockFinderBuilder, BlockMatch, ChainProfile, Cluster, Commitment, NoCache, RateLimit, RetryPolicy, Slot, UnixTs, LAMPORTS_PER_SOL, NOMINAL_SLOT_DURATION_MS};
+
+// Status output goes through these instead of the std macros, so that plain mode can replace the
+// emoji. Data (JSON, YAML, CSV, raw blocks, memos) is printed with the std macros, untouched.
+macro_rules! println {
+    () => { std::println!() };
+    ($($arg:tt)*) => { std::println!("{}", plain_text(std::io::stdout().is_terminal(), format!($($arg)*))) };
 }
 
-#[derive(Debug, Deserialize)]
-struct RpcError {
-    code: i32,
-    message: String,
+macro_rules! print {
+    ($($arg:tt)*) => { std::print!("{}", plain_text(std::io::stdout().is_terminal(), format!($($arg)*))) };
 }
 
-#[derive(Debug, Deserialize)]
-struct BlockInfo {
-    blockhash: String,
-    parentSlot: u64,
-    #[serde(default)]
-    blockTime: Option<i64>,
-    #[serde(default)]
-    blockHeight: Option<u64>,
+macro_rules! eprintln {
+    () => { std::eprintln!() };
+    ($($arg:tt)*) => { std::eprintln!("{}", plain_text(std::io::stderr().is_terminal(), format!($($arg)*))) };
 }
 
-#[derive(Debug, Deserialize)]
-struct BlockResponse {
-    #[serde(default)]
-    block: Option<Value>,
-    blockTime: Option<i64>,
-    #[serde(default)]
-    blockHeight: Option<u64>,
+macro_rules! eprint {
+    ($($arg:tt)*) => { std::eprint!("{}", plain_text(std::io::stderr().is_terminal(), format!($($arg)*))) };
 }
 
-async fn get_slot_by_timestamp_optimized(client: &Client, rpc_url: &str, api_key: &str, target_timestamp: i64) -> Result<u64, Box<dyn Error>> {
-    // Start with current slot
-    let current_slot = get_current_slot(client, rpc_url, api_key).await?;
-    println!("Current slot: {}", current_slot);
-    
-    // Binary search to find the slot with timestamp closest to target
-    let mut low_slot: u64 = 0;
-    let mut high_slot: u64 = current_slot;
-    let mut closest_slot: u64 = 0;
-    let mut closest_time_diff: i64 = i64::MAX;
-    
-    println!("Starting optimized binary search for timestamp: {}", target_timestamp);
-    
-    while low_slot <= high_slot {
-        let mid_slot = low_slot + (high_slot - low_slot) / 2;
-        
-        match get_block_time(client, rpc_url, api_key, mid_slot).await {
-            Ok(Some(block_time)) => {
-                println!("Slot {} has timestamp {}", mid_slot, block_time);
-                
-                let time_diff = block_time - target_timestamp;
-                
-                // If exact match, return immediately
-                if time_diff == 0 {
-                    // But first, find the highest slot with this exact timestamp!
-                    return find_highest_slot_with_timestamp(client, rpc_url, api_key, mid_slot, target_timestamp).await;
-                }
-                
-                // Update closest if this is closer or if it's the closest block before target
-                if time_diff < 0 && (time_diff.abs() < closest_time_diff.abs() || closest_time_diff > 0) {
-                    closest_slot = mid_slot;
-                    closest_time_diff = time_diff;
-                } else if time_diff > 0 && time_diff < closest_time_diff.abs() && closest_time_diff < 0 {
-                    closest_slot = mid_slot;
-                    closest_time_diff = time_diff;
-                }
-                
-                // Adjust search range
-                if block_time < target_timestamp {
-                    low_slot = mid_slot + 1;
-                } else {
-                    high_slot = mid_slot - 1;
-                }
-            },
-            Ok(None) => {
-                // Skip slots with no timestamp and try nearby slots in parallel
-                println!("No timestamp for slot {}, trying nearby slots in parallel", mid_slot);
-                
-                match find_nearby_slot_with_timestamp_parallel(client, rpc_url, api_key, mid_slot, target_timestamp).await {
-                    Some((found_slot, found_time)) => {
-                        println!("Found timestamp {} at nearby slot {}", found_time, found_slot);
-                        
-                        // Check if this is an exact match
-                        if found_time == target_timestamp {
-                            return find_highest_slot_with_timestamp(client, rpc_url, api_key, found_slot, target_timestamp).await;
-                        }
-                        
-                        // Adjust search range based on this nearby slot
-                        if found_time < target_timestamp {
-                            low_slot = found_slot + 1;
-                        } else {
-                            high_slot = found_slot - 1;
-                        }
-                        
-                        // Also update closest if this is closer
-                        let time_diff = found_time - target_timestamp;
-                        if time_diff < 0 && (time_diff.abs() < closest_time_diff.abs() || closest_time_diff > 0) {
-                            closest_slot = found_slot;
-                            closest_time_diff = time_diff;
-                        }
-                    },
-                    None => {
-                        // If we couldn't find any nearby slots with timestamps, just move on
-                        low_slot = mid_slot + 1;
-                    }
-                }
-            },
-            Err(e) => {
-                println!("Error getting block time for slot {}: {}", mid_slot, e);
-                // Try to continue by skipping this slot
-                low_slot = mid_slot + 1;
+// Set by --no-emoji
+static NO_EMOJI: AtomicBool = AtomicBool::new(false);
+
+/// Emoji are replaced with ASCII labels with --no-emoji, when the stream is not a terminal
+/// (log files and aggregators often mangle them) and on `TERM=dumb` terminals
+fn plain_text(is_terminal: bool, text: String) -> String {
+    let plain = NO_EMOJI.load(Ordering::Relaxed) || !is_terminal || env::var("TERM").is_ok_and(|term| term == "dumb");
+    if plain {
+        ascii_labels(&text)
+    } else {
+        text
+    }
+}
+
+/// Replaces status emoji with labels such as `[ok]` and drops decorative ones with their spacing
+fn ascii_labels(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let label = match c {
+            '✅' => "[ok]",
+            '❌' => "[error]",
+            '⚠' => "[warning]",
+            '🛑' => "[stopped]",
+            '→' => "->",
+            '•' => "-",
+            // Variation selectors and joiners only modify the preceding emoji
+            '\u{FE0F}' | '\u{200D}' => continue,
+            c if matches!(c as u32, 0x2100..=0x2BFF | 0x1F000..=0x1FAFF) => "",
+            c => {
+                out.push(c);
+                continue;
+            }
+        };
+        out.push_str(label);
+        if label.is_empty() || label.starts_with('[') {
+            // Emoji are followed by one or two spaces, depending on their width
+            let mut spaced = false;
+            while chars.next_if(|&next| next == ' ' || next == '\u{FE0F}').is_some() {
+                spaced = true;
+            }
+            if spaced && !label.is_empty() {
+                out.push(' ');
             }
         }
-        
-        // Much shorter delay since we're using parallel requests
-        sleep(Duration::from_millis(10)).await;
     }
-    
-    if closest_slot == 0 {
-        return Err("Could not find a suitable block".into());
+    out
+}
+
+// Set from --time-format or the config file
+static TIME_FORMAT: OnceLock<String> = OnceLock::new();
+
+/// Renders a block time in UTC with the --time-format template, or as Unix seconds without one
+fn display_time(time: UnixTs) -> String {
+    match (TIME_FORMAT.get(), DateTime::from_timestamp(time.into(), 0)) {
+        (Some(format), Some(utc)) => utc.format(format).to_string(),
+        _ => time.to_string(),
     }
-    
-    // Check if our closest block exactly matches the target timestamp
-    if let Ok(Some(block_time)) = get_block_time(client, rpc_url, api_key, closest_slot).await {
-        if block_time == target_timestamp {
-            return find_highest_slot_with_timestamp(client, rpc_url, api_key, closest_slot, target_timestamp).await;
+}
+
+// RPC endpoint used when no --endpoint is given
+const DEFAULT_ENDPOINT: &str = "https://mainnet.helius-rpc.com";
+
+// Where `key store` keeps the API key in the OS keyring
+#[cfg(feature = "keyring")]
+const KEYRING_SERVICE: &str = "solana-block-finder";
+#[cfg(feature = "keyring")]
+const KEYRING_USER: &str = "api-key";
+
+// RPC calls made against each endpoint by `bench` unless --mix is given
+const DEFAULT_BENCH_MIX: &str = "getSlot=10,getBlockTime=10,getBlock=5";
+
+/// Mainnet timestamp → slot pairs for `selftest`
+struct ReferenceVector {
+    timestamp: UnixTs,
+    slot: Slot,
+    /// Set when `slot` is the block a search for `timestamp` is expected to return, with the
+    /// blockhash that block should have. Otherwise `slot` is only expected to have this block
+    /// time, so the search must return it or a later block with the same time.
+    blockhash: Option<&'static str>,
+}
+
+// Probes logged by the example search in the README, spread over 2023-2025, and its result.
+// They were not checked against a mainnet node when they were added: a vector failing on a
+// healthy provider may be wrong itself, so check the slot's block time before blaming the search.
+const REFERENCE_VECTORS: &[ReferenceVector] = &[
+    ReferenceVector { timestamp: UnixTs(1674812766), slot: Slot(174637406), blockhash: None },
+    ReferenceVector { timestamp: UnixTs(1713952417), slot: Slot(261956109), blockhash: None },
+    ReferenceVector { timestamp: UnixTs(1742251579), slot: Slot(327445137), blockhash: None },
+    ReferenceVector { timestamp: UnixTs(1750921800), slot: Slot(349274146), blockhash: None },
+    ReferenceVector { timestamp: UnixTs(1750921805), slot: Slot(349274161), blockhash: Some("5yZQFCS5BjhjNeCpCcgwLKwAR2xMZYEvk5wyYxximxMs") },
+];
+
+// Exit code used when a search is interrupted with Ctrl-C (128 + SIGINT)
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// How results of series commands are rendered
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    #[default]
+    Text,
+    Csv,
+    Json,
+    Yaml,
+    Arrow,
+    Parquet,
+}
+
+impl OutputFormat {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        match input {
+            "text" => Ok(OutputFormat::Text),
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "arrow" => Ok(OutputFormat::Arrow),
+            "parquet" => Ok(OutputFormat::Parquet),
+            _ => Err(format!("❌ Invalid output format: '{}' (expected text, csv, json, yaml, arrow or parquet)", input).into()),
         }
     }
     
-    // If closest block is after the target timestamp, we need the previous block
-    if closest_time_diff > 0 {
-        // Find the previous block with a valid timestamp
-        let mut slot = closest_slot;
-        while slot > 0 {
-            slot -= 1;
-            if let Ok(Some(found_time)) = get_block_time(client, rpc_url, api_key, slot).await {
-                if found_time == target_timestamp {
-                    return find_highest_slot_with_timestamp(client, rpc_url, api_key, slot, target_timestamp).await;
-                } else if found_time < target_timestamp {
-                    return Ok(slot);
-                }
-            }
-        }
+    /// Binary formats are written to --output-file instead of stdout
+    fn is_binary(self) -> bool {
+        matches!(self, OutputFormat::Arrow | OutputFormat::Parquet)
     }
-    
-    Ok(closest_slot)
 }
 
-async fn find_nearby_slot_with_timestamp_parallel(
-    client: &Client,
-    rpc_url: &str,
-    api_key: &str,
-    center_slot: u64,
-    target_timestamp: i64,
-) -> Option<(u64, i64)> {
-    // Create parallel requests for nearby slots (much more limited than before)
-    let max_offset = 20;
-    let mut requests = Vec::new();
-    let mut slots = Vec::new();
-    
-    for offset in 1..=max_offset {
-        if center_slot >= offset {
-            slots.push(center_slot - offset);
-            requests.push(get_block_time(client, rpc_url, api_key, center_slot - offset));
+/// What `--copy` puts on the clipboard
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CopyTarget {
+    Slot,
+    Url,
+}
+
+impl CopyTarget {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        match input {
+            "slot" => Ok(CopyTarget::Slot),
+            "url" => Ok(CopyTarget::Url),
+            _ => Err(format!("❌ Invalid --copy value: '{}' (expected slot or url)", input).into()),
         }
-        
-        slots.push(center_slot + offset);
-        requests.push(get_block_time(client, rpc_url, api_key, center_slot + offset));
     }
     
-    // Execute all requests in parallel
-    let results = join_all(requests).await;
-    
-    // Find the best nearby slot
-    let mut best_slot = None;
-    let mut best_time_diff = i64::MAX;
-    
-    for (i, result) in results.into_iter().enumerate() {
-        if let Ok(Some(block_time)) = result {
-            let slot = slots[i];
-            let time_diff = block_time - target_timestamp;
-            
-            // Prefer slots before the target timestamp that are closest
-            if time_diff < 0 && time_diff.abs() < best_time_diff.abs() {
-                best_slot = Some((slot, block_time));
-                best_time_diff = time_diff;
-            } else if best_time_diff > 0 && time_diff > 0 && time_diff < best_time_diff {
-                best_slot = Some((slot, block_time));
-                best_time_diff = time_diff;
-            }
+    fn text(self, slot: Slot) -> String {
+        match self {
+            CopyTarget::Slot => slot.to_string(),
+            CopyTarget::Url => format!("https://explorer.solana.com/block/{}", slot),
         }
     }
-    
-    best_slot
 }
 
-async fn get_current_slot(client: &Client, rpc_url: &str, api_key: &str) -> Result<u64, Box<dyn Error>> {
-    let response = client
-        .post(rpc_url)
-        .header("Content-Type", "application/json")
-        .header("x-api-key", api_key)
-        .json(&json!({
-            "jsonrpc": "2.0",
-            "id": "1",
-            "method": "getSlot",
-            "params": [{"commitment": "finalized"}]
-        }))
-        .send()
-        .await?;
-    
-    let response_text = response.text().await?;
-    let parsed: RpcResponse<u64> = serde_json::from_str(&response_text)?;
-    
-    match parsed.result {
-        Some(slot) => Ok(slot),
-        None => Err(format!("Failed to get current slot: {:?}", parsed.error).into()),
-    }
+/// Command-line options shared by all commands
+#[derive(Debug, Default)]
+struct Options {
+    target_timestamp: Option<UnixTs>,
+    api_key: Option<String>,
+    endpoints: Vec<String>,
+    verbose: bool,
+    show_rewards: bool,
+    leader_details: bool,
+    show_fees: bool,
+    show_compute_units: bool,
+    program: Option<String>,
+    show_total_fees: bool,
+    full_block_output: Option<String>,
+    copy: Option<CopyTarget>,
+    from_timestamp: Option<UnixTs>,
+    to_timestamp: Option<UnixTs>,
+    every_seconds: Option<i64>,
+    slot: Option<Slot>,
+    offset_seconds: Option<i64>,
+    samples: Option<usize>,
+    batch_concurrency: Option<usize>,
+    bench_mix: Option<String>,
+    checkpoint_file: Option<String>,
+    record: Option<String>,
+    replay: Option<String>,
+    resume: bool,
+    estimate_future: bool,
+    stdin: bool,
+    best_effort: bool,
+    lowest: bool,
+    all_matching: bool,
+    diagnostics: bool,
+    time_format: Option<String>,
+    format: Option<Template>,
+    max_rpc_calls: Option<u64>,
+    deadline: Option<Duration>,
+    max_iterations: Option<u32>,
+    proxy: Option<String>,
+    resolve: Vec<(String, IpAddr)>,
+    rate_limits: Vec<(String, RateLimit)>,
+    user_agent: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_max_idle: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    probe_window: Option<u64>,
+    probe_delay: Option<Duration>,
+    otlp_endpoint: Option<String>,
+    config_file: Option<String>,
+    chain: Option<String>,
+    genesis_file: Option<String>,
+    chain_profile: ChainProfile,
+    cluster: Option<Cluster>,
+    strict: bool,
+    commitment: Commitment,
+    ledger: Option<String>,
+    index_file: Option<String>,
+    calibration_file: Option<String>,
+    stride: Option<u64>,
+    listen: Option<SocketAddr>,
+    resolution_seconds: Option<i64>,
+    window_seconds: Option<i64>,
+    horizon_seconds: Option<i64>,
+    output: OutputFormat,
+    output_file: Option<String>,
 }
 
-async fn get_block_time(client: &Client, rpc_url: &str, api_key: &str, slot: u64) -> Result<Option<i64>, Box<dyn Error>> {
-    let response = client
-        .post(rpc_url)
-        .header("Content-Type", "application/json")
-        .header("x-api-key", api_key)
-        .json(&json!({
-            "jsonrpc": "2.0",
-            "id": "1",
-            "method": "getBlockTime",
-            "params": [slot]
-        }))
-        .send()
-        .await?;
-    
-    let response_text = response.text().await?;
-    let parsed: RpcResponse<Option<i64>> = serde_json::from_str(&response_text)?;
-    
-    match parsed.result {
-        Some(time) => Ok(time),
-        None => {
-            if let Some(error) = parsed.error {
-                if error.code == -32009 { // Block not available
-                    return Ok(None);
-                }
-                return Err(format!("RPC error: {:?}", error).into());
-            }
-            Ok(None)
-        }
-    }
+/// Settings read from the config file. Command-line flags take precedence.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    /// RPC endpoints, replacing the default Helius endpoint
+    endpoints: Vec<EndpointConfig>,
+    /// User-Agent header sent to the RPC provider
+    user_agent: Option<String>,
+    /// Per-request timeout, e.g. "30s"
+    timeout: Option<String>,
+    /// Connect timeout, e.g. "5s"
+    connect_timeout: Option<String>,
+    /// Idle connections kept per host
+    pool_max_idle: Option<usize>,
+    /// How long idle connections are kept, e.g. "90s"
+    pool_idle_timeout: Option<String>,
+    /// TCP keepalive interval, e.g. "60s"
+    tcp_keepalive: Option<String>,
+    /// Initial slots probed on each side of a skipped slot
+    probe_window: Option<u64>,
+    /// Pause between sequential probes in milliseconds
+    probe_delay_ms: Option<u64>,
+    /// OTLP gRPC collector for trace export (needs the `otlp` feature)
+    otlp_endpoint: Option<String>,
+    /// strftime-style template for displayed block times, e.g. "%Y-%m-%d %H:%M:%S%z"
+    time_format: Option<String>,
+    /// Chain to run against, "solana" or one of `chains`
+    chain: Option<String>,
+    /// Other SVM chains by name
+    chains: HashMap<String, ChainConfig>,
+    /// Genesis file of a private cluster, see `GenesisConfig`
+    genesis: Option<String>,
+    /// Era table of the chain, see `calibration refresh`
+    calibration: Option<String>,
+    /// Cluster the endpoints must serve: "mainnet", "devnet" or "testnet"
+    cluster: Option<String>,
+    /// Fail instead of warning when the endpoints serve another cluster
+    strict: bool,
 }
 
-async fn get_block_info(client: &Client, rpc_url: &str, api_key: &str, slot: u64) -> Result<BlockInfo, Box<dyn Error>> {
-    let response = client
-        .post(rpc_url)
-        .header("Content-Type", "application/json")
-        .header("x-api-key", api_key)
-        .json(&json!({
-            "jsonrpc": "2.0",
-            "id": "1",
-            "method": "getBlock",
-            "params": [
-                slot,
-                {
-                    "encoding": "json",
-                    "maxSupportedTransactionVersion": 0,
-                    "transactionDetails": "none",
-                    "rewards": false
-                }
-            ]
-        }))
-        .send()
-        .await?;
+/// Timing of a private cluster, read from the file given with --genesis
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenesisConfig {
+    /// Unix time of slot 0
+    genesis_timestamp: Option<UnixTs>,
+    /// Target slot duration, overriding the chain's
+    slot_duration_ms: Option<u64>,
+    /// Oldest slot the cluster's nodes still have
+    first_available_slot: Option<Slot>,
+}
+
+/// Parameters of an SVM chain other than Solana mainnet
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChainConfig {
+    /// RPC endpoints of the chain, used when neither --endpoint nor `endpoints` is set
+    #[serde(default)]
+    endpoints: Vec<EndpointConfig>,
+    /// Target slot duration (default: 400)
+    slot_duration_ms: Option<u64>,
+    /// Read block times from getBlock, for nodes without getBlockTime
+    #[serde(default)]
+    block_time_from_get_block: bool,
+}
+
+/// An endpoint in the config file: a plain URL, or a table with its own rate limit
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum EndpointConfig {
+    Url(String),
+    Limited(LimitedEndpoint),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LimitedEndpoint {
+    url: String,
+    /// Sustained requests per second allowed on this endpoint
+    max_rps: Option<f64>,
+    /// Requests allowed at once after an idle period (default: max_rps)
+    burst: Option<u32>,
+}
+
+impl EndpointConfig {
+    fn url(&self) -> &str {
+        match self {
+            EndpointConfig::Url(url) => url,
+            EndpointConfig::Limited(endpoint) => &endpoint.url,
+        }
+    }
     
-    let response_text = response.text().await?;
-    let parsed: RpcResponse<Value> = serde_json::from_str(&response_text)?;
-    
-    match parsed.result {
-        Some(block_data) => {
-            Ok(BlockInfo {
-                blockhash: block_data.get("blockhash")
-                    .and_then(|h| h.as_str())
-                    .map(String::from)
-                    .unwrap_or_default(),
-                parentSlot: block_data.get("parentSlot")
-                    .and_then(|s| s.as_u64())
-                    .unwrap_or_default(),
-                blockTime: block_data.get("blockTime")
-                    .and_then(|t| t.as_i64()),
-                blockHeight: block_data.get("blockHeight")
-                    .and_then(|h| h.as_u64()),
-            })
-        },
-        None => Err(format!("Failed to get block info: {:?}", parsed.error).into()),
-    }
-}
-
-// New function to find the highest slot with a specific timestamp
-async fn find_highest_slot_with_timestamp(
-    client: &Client, 
-    rpc_url: &str, 
-    api_key: &str, 
-    start_slot: u64, 
-    target_timestamp: i64
-) -> Result<u64, Box<dyn Error>> {
-    println!("Finding highest slot with timestamp {}, starting from slot {}", target_timestamp, start_slot);
-    
-    let mut highest_slot = start_slot;
-    let mut current_slot = start_slot + 1;
-    let max_scan = 100; // Limit scan to avoid infinite loops
-    let mut scanned = 0;
-    
-    // Scan forward to find the highest slot with the same timestamp
-    while scanned < max_scan {
-        match get_block_time(client, rpc_url, api_key, current_slot).await {
-            Ok(Some(block_time)) => {
-                if block_time == target_timestamp {
-                    highest_slot = current_slot;
-                    println!("Found higher slot {} with same timestamp {}", current_slot, target_timestamp);
-                } else if block_time > target_timestamp {
-                    // We've moved past our target timestamp, stop scanning
-                    break;
-                } else {
-                    // Block time is less than target, this shouldn't happen in forward scan
-                    // but let's continue just in case
-                }
-                current_slot += 1;
-            },
-            Ok(None) => {
-                // Skip slots with no timestamp
-                current_slot += 1;
-            },
-            Err(_) => {
-                // Skip slots with errors
-                current_slot += 1;
+    fn rate_limit(&self) -> Result<Option<RateLimit>, Box<dyn Error>> {
+        let EndpointConfig::Limited(endpoint) = self else {
+            return Ok(None);
+        };
+        match (endpoint.max_rps, endpoint.burst) {
+            (None, None) => Ok(None),
+            (None, Some(_)) => Err(format!("❌ Invalid config: burst for {} needs max_rps", endpoint.url).into()),
+            (Some(max_rps), _) if !max_rps.is_finite() || max_rps <= 0.0 => {
+                Err(format!("❌ Invalid config: max_rps for {} must be a positive number", endpoint.url).into())
+            }
+            (Some(max_rps), burst) => {
+                let default = RateLimit::per_second(max_rps);
+                Ok(Some(RateLimit { burst: burst.unwrap_or(default.burst), ..default }))
             }
         }
-        scanned += 1;
-        
-        // Small delay to avoid overwhelming the RPC
-        sleep(Duration::from_millis(5)).await;
     }
-    
-    println!("Highest slot with timestamp {} is {}", target_timestamp, highest_slot);
-    Ok(highest_slot)
 }
 
+// Secrets to mask in the final error message, set once the API key is known
+static REDACTOR: OnceLock<Redactor> = OnceLock::new();
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    // Errors can quote keyed URLs or config lines, so mask secrets before they are printed
+    run().await.map_err(|e| {
+        let message = REDACTOR.get().cloned().unwrap_or_default().redact(&e.to_string());
+        plain_text(std::io::stderr().is_terminal(), message).into()
+    })
+}
+
+async fn run() -> Result<(), Box<dyn Error>> {
     // Parse command-line arguments
     let args: Vec<String> = env::args().collect();
+    if args.iter().any(|arg| arg == "--no-emoji") {
+        NO_EMOJI.store(true, Ordering::Relaxed);
+    }
     
     // Check for help flags
     if args.len() == 1 || args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
@@ -368,16 +413,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
     }
     
     // Parse parameters
-    let mut target_timestamp: Option<i64> = None;
-    let mut api_key: Option<String> = None;
-    let mut verbose = false;
+    let mut options = Options::default();
+    let mut positionals: Vec<String> = Vec::new();
     
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
             "--timestamp" | "-t" => {
                 if i + 1 < args.len() {
-                    target_timestamp = Some(parse_timestamp(&args[i + 1])?);
+                    options.target_timestamp = Some(parse_timestamp(&args[i + 1])?);
                     i += 2;
                 } else {
                     eprintln!("❌ Error: --timestamp requires a value");
@@ -387,7 +431,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
             }
             "--api-key" | "-k" => {
                 if i + 1 < args.len() {
-                    api_key = Some(args[i + 1].clone());
+                    options.api_key = Some(args[i + 1].clone());
                     i += 2;
                 } else {
                     eprintln!("❌ Error: --api-key requires a value");
@@ -395,95 +439,3292 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     return Ok(());
                 }
             }
+            "--endpoint" | "-e" => {
+                if i + 1 < args.len() {
+                    options.endpoints.push(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --endpoint requires a URL");
+                    print_usage();
+                    return Ok(());
+                }
+            }
             "--verbose" | "-v" => {
-                verbose = true;
+                options.verbose = true;
                 i += 1;
             }
-            _ => {
-                eprintln!("❌ Error: Unknown parameter '{}'", args[i]);
-                print_usage();
-                return Ok(());
+            "--rewards" => {
+                options.show_rewards = true;
+                i += 1;
+            }
+            "--leader-details" => {
+                options.leader_details = true;
+                i += 1;
+            }
+            "--fees" => {
+                options.show_fees = true;
+                i += 1;
+            }
+            "--compute-units" => {
+                options.show_compute_units = true;
+                i += 1;
+            }
+            "--total-fees" => {
+                options.show_total_fees = true;
+                i += 1;
+            }
+            "--program" => {
+                if i + 1 < args.len() {
+                    options.program = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --program requires a program ID");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--full-block" => {
+                if i + 1 < args.len() {
+                    options.full_block_output = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --full-block requires a file path (or '-' for stdout)");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--copy" => {
+                if i + 1 < args.len() {
+                    options.copy = Some(CopyTarget::parse(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --copy requires slot or url");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--from" => {
+                if i + 1 < args.len() {
+                    options.from_timestamp = Some(parse_timestamp(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --from requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--to" => {
+                if i + 1 < args.len() {
+                    options.to_timestamp = Some(parse_timestamp(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --to requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--every" => {
+                if i + 1 < args.len() {
+                    options.every_seconds = Some(parse_duration(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --every requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--slot" => {
+                if i + 1 < args.len() {
+                    options.slot = Some(args[i + 1].parse().map_err(|_| format!("❌ Invalid slot: '{}'", args[i + 1]))?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --slot requires a slot number");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--by" => {
+                if i + 1 < args.len() {
+                    options.offset_seconds = Some(parse_offset(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --by requires a duration such as 2h30m or -1h");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--output" | "-o" => {
+                if i + 1 < args.len() {
+                    options.output = OutputFormat::parse(&args[i + 1])?;
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --output requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--output-file" => {
+                if i + 1 < args.len() {
+                    options.output_file = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --output-file requires a file path");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--samples" => {
+                if i + 1 < args.len() {
+                    options.samples = Some(args[i + 1].parse().map_err(|_| format!("❌ Invalid sample count: '{}'", args[i + 1]))?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --samples requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--batch-concurrency" => {
+                if i + 1 < args.len() {
+                    options.batch_concurrency = Some(parse_batch_concurrency(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --batch-concurrency requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--mix" => {
+                if i + 1 < args.len() {
+                    options.bench_mix = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --mix requires a list like getSlot=10,getBlock=5");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--checkpoint" => {
+                if i + 1 < args.len() {
+                    options.checkpoint_file = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --checkpoint requires a file path");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--record" => {
+                if i + 1 < args.len() {
+                    options.record = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --record requires a file path");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--replay" => {
+                if i + 1 < args.len() {
+                    options.replay = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --replay requires a file path");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--resume" => {
+                options.resume = true;
+                i += 1;
+            }
+            "--estimate-future" => {
+                options.estimate_future = true;
+                i += 1;
+            }
+            "--stdin" => {
+                options.stdin = true;
+                i += 1;
+            }
+            "--no-emoji" => {
+                // Already applied before parsing, so that errors are plain too
+                i += 1;
+            }
+            "--format" => {
+                if i + 1 < args.len() {
+                    options.format = Some(Template::parse(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --format requires a template such as '{{slot}},{{blockhash}},{{block_time_iso}}'");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--time-format" => {
+                if i + 1 < args.len() {
+                    options.time_format = Some(parse_time_format(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --time-format requires a template such as '%Y-%m-%d %H:%M:%S%z'");
+                    print_usage();
+                    return Ok(());
+                }
             }
+            "--diagnostics" => {
+                options.diagnostics = true;
+                i += 1;
+            }
+            "--best-effort" => {
+                options.best_effort = true;
+                i += 1;
+            }
+            "--lowest" => {
+                options.lowest = true;
+                i += 1;
+            }
+            "--all-matching" => {
+                options.all_matching = true;
+                i += 1;
+            }
+            "--deadline" => {
+                if i + 1 < args.len() {
+                    options.deadline = Some(parse_timeout(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --deadline requires a duration");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--max-iterations" => {
+                if i + 1 < args.len() {
+                    options.max_iterations = Some(parse_max_iterations(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --max-iterations requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--max-rpc-calls" => {
+                if i + 1 < args.len() {
+                    options.max_rpc_calls = Some(args[i + 1].parse().map_err(|_| format!("❌ Invalid RPC call budget: '{}'", args[i + 1]))?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --max-rpc-calls requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--proxy" => {
+                if i + 1 < args.len() {
+                    options.proxy = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --proxy requires a URL");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--resolve" => {
+                if i + 1 < args.len() {
+                    options.resolve.push(parse_resolve(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --resolve requires HOST:IP");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--user-agent" => {
+                if i + 1 < args.len() {
+                    options.user_agent = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --user-agent requires a value");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--timeout" => {
+                if i + 1 < args.len() {
+                    options.timeout = Some(parse_timeout(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --timeout requires a duration");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--connect-timeout" => {
+                if i + 1 < args.len() {
+                    options.connect_timeout = Some(parse_timeout(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --connect-timeout requires a duration");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--pool-max-idle" => {
+                if i + 1 < args.len() {
+                    options.pool_max_idle = Some(parse_count(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --pool-max-idle requires a number");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--pool-idle-timeout" => {
+                if i + 1 < args.len() {
+                    options.pool_idle_timeout = Some(parse_timeout(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --pool-idle-timeout requires a duration");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--tcp-keepalive" => {
+                if i + 1 < args.len() {
+                    options.tcp_keepalive = Some(parse_timeout(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --tcp-keepalive requires a duration");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--probe-window" => {
+                if i + 1 < args.len() {
+                    options.probe_window = Some(parse_probe_window(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --probe-window requires a number of slots");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--probe-delay" => {
+                if i + 1 < args.len() {
+                    options.probe_delay = Some(parse_millis(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --probe-delay requires a delay in milliseconds");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--otlp-endpoint" => {
+                if i + 1 < args.len() {
+                    options.otlp_endpoint = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --otlp-endpoint requires a URL");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--chain" => {
+                if i + 1 < args.len() {
+                    options.chain = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --chain requires a chain name");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--cluster" => {
+                if i + 1 < args.len() {
+                    options.cluster = Some(parse_cluster(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --cluster requires mainnet, devnet or testnet");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--commitment" => {
+                if i + 1 < args.len() {
+                    options.commitment = parse_commitment(&args[i + 1])?;
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --commitment requires finalized or confirmed");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--strict" => {
+                options.strict = true;
+                i += 1;
+            }
+            "--ledger" => {
+                if i + 1 < args.len() {
+                    options.ledger = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --ledger requires a ledger directory");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--index" => {
+                if i + 1 < args.len() {
+                    options.index_file = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --index requires a file path");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--calibration" => {
+                if i + 1 < args.len() {
+                    options.calibration_file = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --calibration requires a file path");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--stride" => {
+                if i + 1 < args.len() {
+                    options.stride = Some(parse_stride(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --stride requires a number of slots");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--resolution" => {
+                if i + 1 < args.len() {
+                    options.resolution_seconds = Some(parse_positive_duration(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --resolution requires a duration");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--window" => {
+                if i + 1 < args.len() {
+                    options.window_seconds = Some(parse_positive_duration(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --window requires a duration");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--horizon" => {
+                if i + 1 < args.len() {
+                    options.horizon_seconds = Some(parse_positive_duration(&args[i + 1])?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --horizon requires a duration");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--listen" => {
+                if i + 1 < args.len() {
+                    options.listen = Some(args[i + 1].parse().map_err(|_| format!("❌ Invalid listen address: '{}' (expected IP:PORT)", args[i + 1]))?);
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --listen requires an address");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--genesis" => {
+                if i + 1 < args.len() {
+                    options.genesis_file = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --genesis requires a file path");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            "--config" => {
+                if i + 1 < args.len() {
+                    options.config_file = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    eprintln!("❌ Error: --config requires a file path");
+                    print_usage();
+                    return Ok(());
+                }
+            }
+            arg if arg == "-" || !arg.starts_with('-') => {
+                positionals.push(arg.to_string());
+                i += 1;
+            }
+            _ => {
+                eprintln!("❌ Error: Unknown parameter '{}'", args[i]);
+                print_usage();
+                return Ok(());
+            }
+        }
+    }
+    
+    // Fill in whatever the command line left open from the config file
+    let config = load_config(options.config_file.as_deref())?;
+    // Limits apply to an endpoint by URL, also when the endpoint itself was given with --endpoint
+    for endpoint in &config.endpoints {
+        if let Some(limit) = endpoint.rate_limit()? {
+            options.rate_limits.push((endpoint.url().to_string(), limit));
+        }
+    }
+    if options.endpoints.is_empty() {
+        options.endpoints = config.endpoints.iter().map(|endpoint| endpoint.url().to_string()).collect();
+    }
+    let chain_name = options.chain.take().or(config.chain).unwrap_or_else(|| ChainProfile::solana().name);
+    if chain_name != ChainProfile::solana().name {
+        let Some(chain) = config.chains.get(&chain_name) else {
+            let mut known: Vec<&str> = config.chains.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            return Err(format!("❌ Unknown chain '{}', add it as [chains.{}] to the config file (configured: {})",
+                chain_name, chain_name, if known.is_empty() { "none".to_string() } else { known.join(", ") }).into());
+        };
+        for endpoint in &chain.endpoints {
+            if let Some(limit) = endpoint.rate_limit()? {
+                options.rate_limits.push((endpoint.url().to_string(), limit));
+            }
+        }
+        if options.endpoints.is_empty() {
+            options.endpoints = chain.endpoints.iter().map(|endpoint| endpoint.url().to_string()).collect();
+        }
+        if options.endpoints.is_empty() {
+            return Err(format!("❌ Chain '{}' has no endpoints, set them in [chains.{}] or pass --endpoint", chain_name, chain_name).into());
+        }
+        options.chain_profile = ChainProfile {
+            name: chain_name,
+            slot_duration_ms: chain.slot_duration_ms.unwrap_or(NOMINAL_SLOT_DURATION_MS),
+            block_time_from_get_block: chain.block_time_from_get_block,
+            ..ChainProfile::solana()
+        };
+    }
+    if let Some(path) = options.genesis_file.take().or(config.genesis) {
+        let genesis = load_genesis(&path)?;
+        let profile = &mut options.chain_profile;
+        profile.genesis_timestamp = genesis.genesis_timestamp.or(profile.genesis_timestamp);
+        profile.slot_duration_ms = genesis.slot_duration_ms.unwrap_or(profile.slot_duration_ms);
+        profile.first_available_slot = genesis.first_available_slot.or(profile.first_available_slot);
+    }
+    if options.user_agent.is_none() {
+        options.user_agent = config.user_agent;
+    }
+    if options.timeout.is_none() {
+        options.timeout = config.timeout.as_deref().map(parse_timeout).transpose()?;
+    }
+    if options.connect_timeout.is_none() {
+        options.connect_timeout = config.connect_timeout.as_deref().map(parse_timeout).transpose()?;
+    }
+    if options.pool_max_idle.is_none() {
+        options.pool_max_idle = config.pool_max_idle;
+    }
+    if options.pool_idle_timeout.is_none() {
+        options.pool_idle_timeout = config.pool_idle_timeout.as_deref().map(parse_timeout).transpose()?;
+    }
+    if options.tcp_keepalive.is_none() {
+        options.tcp_keepalive = config.tcp_keepalive.as_deref().map(parse_timeout).transpose()?;
+    }
+    if options.probe_window.is_none() {
+        options.probe_window = config.probe_window;
+    }
+    if options.time_format.is_none() {
+        options.time_format = config.time_format.as_deref().map(parse_time_format).transpose()?;
+    }
+    if let Some(format) = &options.time_format {
+        let _ = TIME_FORMAT.set(format.clone());
+    }
+    if options.probe_delay.is_none() {
+        options.probe_delay = config.probe_delay_ms.map(Duration::from_millis);
+    }
+    if options.otlp_endpoint.is_none() {
+        options.otlp_endpoint = config.otlp_endpoint;
+    }
+    if options.cluster.is_none() {
+        options.cluster = config.cluster.as_deref().map(parse_cluster).transpose()?;
+    }
+    if options.calibration_file.is_none() {
+        options.calibration_file = config.calibration;
+    }
+    options.strict |= config.strict;
+    
+    if options.output.is_binary() && options.output_file.is_none() {
+        eprintln!("❌ Error: --output arrow/parquet requires --output-file <FILE>");
+        print_usage();
+        return Ok(());
+    }
+    
+    // The first positional argument selects the command
+    let command = positionals.first().cloned();
+    if options.format.is_some() {
+        if !matches!(command.as_deref(), None | Some("schedule") | Some("batch")) {
+            eprintln!("❌ Error: --format applies to timestamp searches, schedule and batch");
+            eprintln!();
+            print_usage();
+            return Ok(());
+        }
+        if options.output != OutputFormat::Text {
+            eprintln!("❌ Error: --format replaces the text output, it cannot be combined with --output");
+            eprintln!();
+            print_usage();
+            return Ok(());
+        }
+    }
+    if options.all_matching && (command.is_some() || options.stdin) {
+        eprintln!("❌ Error: --all-matching only applies to a single timestamp search");
+        eprintln!();
+        print_usage();
+        return Ok(());
+    }
+    if options.estimate_future && !matches!(command.as_deref(), None | Some("schedule")) {
+        eprintln!("❌ Error: --estimate-future only applies to timestamp searches and schedule");
+        eprintln!();
+        print_usage();
+        return Ok(());
+    }
+    match command.as_deref() {
+        None => {
+            if options.resume && options.checkpoint_file.is_none() {
+                eprintln!("❌ Error: --resume requires --checkpoint <FILE>");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+            if options.stdin {
+                if options.target_timestamp.is_some() || options.checkpoint_file.is_some() {
+                    eprintln!("❌ Error: --stdin reads the timestamps from stdin, it cannot be combined with --timestamp or --checkpoint");
+                    eprintln!();
+                    print_usage();
+                    return Ok(());
+                }
+                if !matches!(options.output, OutputFormat::Text | OutputFormat::Json) {
+                    eprintln!("❌ Error: --stdin writes one line per timestamp, use --output text or json");
+                    eprintln!();
+                    print_usage();
+                    return Ok(());
+                }
+            } else if options.target_timestamp.is_none() && !options.resume {
+                eprintln!("❌ Error: Missing required parameter --timestamp");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("find-tx") => {
+            if positionals.len() != 2 {
+                eprintln!("❌ Error: find-tx requires exactly one transaction signature");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("activity") => {
+            if positionals.len() != 2 || options.from_timestamp.is_none() || options.to_timestamp.is_none() {
+                eprintln!("❌ Error: activity requires an address plus --from and --to");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("created-at") => {
+            if positionals.len() != 2 {
+                eprintln!("❌ Error: created-at requires exactly one address");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("mint-created") => {
+            if positionals.len() != 2 {
+                eprintln!("❌ Error: mint-created requires exactly one mint address");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("deployments") => {
+            if positionals.len() != 2 {
+                eprintln!("❌ Error: deployments requires exactly one program id");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("first-call") => {
+            if positionals.len() != 2 || options.from_timestamp.is_none() {
+                eprintln!("❌ Error: first-call requires a program id plus --from");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("feature") => {
+            if positionals.len() != 2 {
+                eprintln!("❌ Error: feature requires exactly one feature id");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("last-activity") => {
+            if positionals.len() != 2 || options.target_timestamp.is_none() {
+                eprintln!("❌ Error: last-activity requires an address plus --timestamp");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("epoch-at") => {
+            if positionals.len() != 2 {
+                eprintln!("❌ Error: epoch-at requires exactly one timestamp");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+            options.target_timestamp = Some(parse_timestamp(&positionals[1])?);
+        }
+        Some("epoch") => {
+            if positionals.len() != 2 || positionals[1].parse::<u64>().is_err() {
+                eprintln!("❌ Error: epoch requires exactly one epoch number");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("countdown") => {
+            if options.target_timestamp.is_none() {
+                eprintln!("❌ Error: countdown requires --timestamp");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("eta") => {
+            if positionals.len() != 2 || positionals[1].parse::<u64>().is_err() {
+                eprintln!("❌ Error: eta requires exactly one slot number");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("between") => {
+            if positionals.len() != 3 || positionals[1..].iter().any(|slot| slot.parse::<u64>().is_err()) {
+                eprintln!("❌ Error: between requires exactly two slot numbers");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("dump-epoch") => {
+            if positionals.len() != 2 || positionals[1].parse::<u64>().is_err() {
+                eprintln!("❌ Error: dump-epoch requires exactly one epoch number");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("schedule") => {
+            if options.every_seconds.is_none() || options.from_timestamp.is_none() || options.to_timestamp.is_none() {
+                eprintln!("❌ Error: schedule requires --every, --from and --to");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("offset") => {
+            if options.slot.is_none() || options.offset_seconds.is_none() {
+                eprintln!("❌ Error: offset requires --slot and --by");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("batch") => {
+            if positionals.len() != 2 {
+                eprintln!("❌ Error: batch requires exactly one file of timestamps (or '-' for stdin)");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+            if options.checkpoint_file.is_some() {
+                eprintln!("❌ Error: --checkpoint only applies to a single --timestamp search");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("stats") => {
+            if options.from_timestamp.is_none() || options.to_timestamp.is_none() {
+                eprintln!("❌ Error: stats requires --from and --to");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("drift") => {
+            if options.from_timestamp.is_none() || options.to_timestamp.is_none() {
+                eprintln!("❌ Error: drift requires --from and --to");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        }
+        Some("index") => {
+            let local = options.index_file.as_deref().is_some_and(|path| !is_remote_index(path));
+            match positionals.get(1).map(String::as_str) {
+                Some("crawl") if positionals.len() == 2 && local => {}
+                // Serving needs no RPC endpoint or API key
+                Some("serve") if positionals.len() == 2 && local => return run_index_serve(&options).await,
+                Some("export") if positionals.len() == 3 && local => return run_index_export(&options, &positionals[2]),
+                Some("import") if positionals.len() == 3 && local => return run_index_import(&options, &positionals[2]),
+                Some("compact") if positionals.len() == 2 && local => {
+                    if options.resolution_seconds.is_none() && options.horizon_seconds.is_none() {
+                        eprintln!("❌ Error: index compact requires --resolution and/or --horizon");
+                        eprintln!();
+                        print_usage();
+                        return Ok(());
+                    }
+                    return run_index_compact(&options);
+                }
+                _ => {
+                    eprintln!("❌ Error: index requires 'crawl', 'serve', 'export <FILE>', 'import <FILE>' or 'compact' and a local --index <FILE>");
+                    eprintln!();
+                    print_usage();
+                    return Ok(());
+                }
+            }
+        }
+        Some("health") | Some("selftest") | Some("bench") if options.ledger.is_some() || options.record.is_some() || options.replay.is_some() => {
+            eprintln!("❌ Error: {} checks RPC endpoints and cannot use --ledger, --record or --replay", command.as_deref().unwrap_or_default());
+            print_usage();
+            return Ok(());
+        }
+        Some("health") | Some("selftest") => {}
+        Some("bench") => {
+            if let Some(mix) = &options.bench_mix {
+                parse_bench_mix(mix)?;
+            }
+        }
+        Some("calibration") => match positionals.get(1).map(String::as_str) {
+            Some("refresh") if positionals.len() == 2 => {}
+            // Showing the table needs no RPC endpoint or API key
+            Some("show") if positionals.len() == 2 => return run_calibration_show(&options),
+            _ => {
+                eprintln!("❌ Error: calibration requires 'refresh' or 'show'");
+                eprintln!();
+                print_usage();
+                return Ok(());
+            }
+        },
+        Some("calibrate") if positionals.len() != 1 => {
+            eprintln!("❌ Error: calibrate takes no arguments, set the window with --window");
+            eprintln!();
+            print_usage();
+            return Ok(());
+        }
+        Some("calibrate") => {}
+        Some("mangen") => return print_man_page(),
+        Some("key") => {
+            return match positionals.get(1).map(String::as_str) {
+                Some("store") if positionals.len() == 2 => run_key_store(),
+                Some("delete") if positionals.len() == 2 => run_key_delete(),
+                _ => {
+                    eprintln!("❌ Error: key requires 'store' or 'delete'");
+                    eprintln!();
+                    print_usage();
+                    Ok(())
+                }
+            };
+        }
+        Some(other) => {
+            eprintln!("❌ Error: Unknown command '{}'", other);
+            print_usage();
+            return Ok(());
+        }
+    }
+    
+    // Sessions are recorded and replayed by the HTTP backend
+    if options.record.is_some() && options.replay.is_some() {
+        eprintln!("❌ Error: --record and --replay cannot be combined");
+        print_usage();
+        return Ok(());
+    }
+    if options.ledger.is_some() && (options.record.is_some() || options.replay.is_some()) {
+        eprintln!("❌ Error: --record and --replay apply to RPC endpoints and cannot be combined with --ledger");
+        print_usage();
+        return Ok(());
+    }
+    
+    // Get API key from parameter, environment or the OS keyring. Custom endpoints may not need one.
+    let api_key = match options.api_key.clone() {
+        Some(key) => Some(key),
+        None => {
+            let stored = match env::var("HELIUS_API_KEY") {
+                Ok(key) => Ok(Some(key)),
+                Err(_) => load_keyring_key(),
+            };
+            match stored {
+                Ok(Some(key)) => Some(key),
+                _ if !options.endpoints.is_empty() || options.ledger.is_some() || options.replay.is_some() => None,
+                keyring => {
+                    if let Err(e) = keyring {
+                        eprintln!("⚠️  Could not read the API key from the keyring: {}", e);
+                    }
+                    eprintln!("❌ Error: No API key provided!");
+                    eprintln!();
+                    eprintln!("Please provide an API key by either:");
+                    eprintln!("  1. Setting the HELIUS_API_KEY environment variable:");
+                    eprintln!("     export HELIUS_API_KEY=your-api-key-here");
+                    eprintln!();
+                    eprintln!("  2. Or using the --api-key parameter:");
+                    eprintln!("     {} --timestamp <timestamp> --api-key <your-key>", env::args().next().unwrap_or_else(|| "solana-block-finder".to_string()));
+                    eprintln!();
+                    eprintln!("  3. Or storing it in the OS keyring (needs the keyring feature):");
+                    eprintln!("     {} key store", env::args().next().unwrap_or_else(|| "solana-block-finder".to_string()));
+                    eprintln!();
+                    eprintln!("You can get a free API key from: https://helius.xyz");
+                    return Err("Missing API key".into());
+                }
+            }
+        }
+    };
+    
+    let mut redactor = Redactor::new();
+    if let Some(key) = &api_key {
+        redactor.add(key.as_str());
+    }
+    let _ = REDACTOR.set(redactor);
+    
+    let endpoints = if options.endpoints.is_empty() {
+        vec![DEFAULT_ENDPOINT.to_string()]
+    } else {
+        options.endpoints.clone()
+    };
+    let mut builder = network_builder(&options, api_key.as_deref()).endpoints(endpoints.clone());
+    if let Some(path) = &options.checkpoint_file {
+        builder = builder.checkpoint_file(path);
+    }
+    if let Some(calls) = options.max_rpc_calls {
+        builder = builder.max_rpc_calls(calls);
+    }
+    if let Some(deadline) = options.deadline {
+        builder = builder.deadline(deadline);
+    }
+    if let Some(iterations) = options.max_iterations {
+        builder = builder.max_iterations(iterations);
+    }
+    if let Some(path) = &options.ledger {
+        builder = builder.backend(ledger_backend(path)?);
+    }
+    if let Some(path) = &options.record {
+        builder = builder.cassette(Cassette::record(path)?);
+    }
+    if let Some(path) = &options.replay {
+        builder = builder.cassette(Cassette::replay(path)?);
+    }
+    if let Some(url) = options.index_file.as_ref().filter(|path| is_remote_index(path)) {
+        builder = builder.remote_index(url);
+    }
+    // A refresh measures the eras itself, so it starts from the built-in table
+    if command.as_deref() != Some("calibration") {
+        if let Some(calibration) = load_calibration(&options, command.as_deref() == Some("calibrate"))? {
+            builder = builder.calibration(calibration);
+        }
+    }
+    let finder = builder.best_effort(options.best_effort).lowest(options.lowest).build()?;
+    let log = StderrLog { max_level: if options.verbose { Level::DEBUG } else { Level::INFO } };
+    let _telemetry = init_telemetry(options.otlp_endpoint.as_deref(), log)?;
+    // Known blocks from the index narrow every search; crawl reads the index itself
+    if let Some(path) = options.index_file.as_ref().filter(|path| !is_remote_index(path) && command.as_deref() != Some("index")) {
+        SlotIndex::read(path)?.seed(finder.discovery());
+    }
+    // health reports the cluster of every endpoint itself
+    if let Some(cluster) = options.cluster.filter(|_| command.as_deref() != Some("health")) {
+        check_cluster(&finder, cluster, options.strict).await?;
+    }
+    
+    let run = async {
+        match command.as_deref() {
+            Some("find-tx") => run_find_tx(&finder, &positionals[1], &options).await,
+            Some("activity") => run_activity(&finder, &positionals[1], &options).await,
+            Some("created-at") => run_created_at(&finder, &positionals[1], &options).await,
+            Some("mint-created") => run_mint_created(&finder, &positionals[1], &options).await,
+            Some("deployments") => run_deployments(&finder, &positionals[1], &options).await,
+            Some("first-call") => run_first_call(&finder, &positionals[1], &options).await,
+            Some("feature") => run_feature(&finder, &positionals[1], &options).await,
+            Some("last-activity") => run_last_activity(&finder, &positionals[1], &options).await,
+            Some("epoch-at") => run_epoch_at(&finder, &options).await,
+            Some("schedule") => run_schedule(&finder, &options).await,
+            Some("batch") => run_batch(&finder, &positionals[1], &options).await,
+            Some("stats") => run_stats(&finder, &options).await,
+            Some("drift") => run_drift(&finder, &options).await,
+            Some("selftest") => run_selftest(&endpoints, api_key.as_deref(), &options).await,
+            Some("health") => run_health(&endpoints, api_key.as_deref(), &options).await,
+            Some("bench") => run_bench(&endpoints, api_key.as_deref(), &options).await,
+            Some("epoch") => run_epoch(&finder, positionals[1].parse()?, &options).await,
+            Some("offset") => run_offset(&finder, &options).await,
+            Some("countdown") => run_countdown(&finder, &options).await,
+            Some("eta") => run_eta(&finder, positionals[1].parse()?).await,
+            Some("between") => run_between(&finder, positionals[1].parse()?, positionals[2].parse()?).await,
+            Some("dump-epoch") => run_dump_epoch(&finder, positionals[1].parse()?, &options).await,
+            Some("index") => run_index_crawl(&finder, &options).await,
+            Some("calibration") => run_calibration_refresh(&finder, &options).await,
+            Some("calibrate") => run_calibrate(&finder, &options).await,
+            _ if options.stdin => run_stdin(&finder, &options).await,
+            _ => run_timestamp_search(&finder, &options).await,
+        }
+    };
+    
+    tokio::select! {
+        result = run => result,
+        _ = tokio::signal::ctrl_c() => {
+            report_interrupted_search(&finder);
+            // exit() skips destructors, so flush the traces first
+            #[cfg(feature = "otlp")]
+            drop(_telemetry);
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+    }
+}
+
+/// Compares the cluster the endpoints serve with the one asked for, warning on a mismatch
+/// or failing under --strict
+async fn check_cluster(finder: &BlockFinder, expected: Cluster, strict: bool) -> Result<(), Box<dyn Error>> {
+    let message = match finder.detect_cluster().await {
+        Ok((_, Some(cluster))) if cluster == expected => return Ok(()),
+        Ok((_, Some(cluster))) => format!("The endpoint serves {}, not {}", cluster.as_str(), expected.as_str()),
+        Ok((hash, None)) => format!("The endpoint serves an unknown cluster (genesis hash {}), not {}", hash, expected.as_str()),
+        Err(e) => format!("Could not check that the endpoint serves {}: {}", expected.as_str(), finder.redact(&e.to_string())),
+    };
+    if strict {
+        return Err(format!("❌ {}", message).into());
+    }
+    eprintln!("⚠️  {}", message);
+    Ok(())
+}
+
+/// Builder with the connection settings shared by every finder the CLI creates
+fn network_builder(options: &Options, api_key: Option<&str>) -> BlockFinderBuilder {
+    let mut builder = BlockFinder::builder().chain(options.chain_profile.clone()).commitment(options.commitment);
+    if let Some(api_key) = api_key {
+        builder = builder.api_key(api_key);
+    }
+    if let Some(proxy) = &options.proxy {
+        builder = builder.proxy(proxy);
+    }
+    for (host, ip) in &options.resolve {
+        builder = builder.resolve(host, *ip);
+    }
+    for (url, limit) in &options.rate_limits {
+        builder = builder.rate_limit(url, *limit);
+    }
+    if let Some(user_agent) = &options.user_agent {
+        builder = builder.user_agent(user_agent);
+    }
+    if let Some(timeout) = options.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(timeout) = options.connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(max) = options.pool_max_idle {
+        builder = builder.pool_max_idle_per_host(max);
+    }
+    if let Some(timeout) = options.pool_idle_timeout {
+        builder = builder.pool_idle_timeout(timeout);
+    }
+    if let Some(interval) = options.tcp_keepalive {
+        builder = builder.tcp_keepalive(interval);
+    }
+    if let Some(slots) = options.probe_window {
+        builder = builder.probe_window(slots);
+    }
+    if let Some(delay) = options.probe_delay {
+        // The forward scan pauses between batches just like the binary search does between steps
+        builder = builder.probe_delay(delay).scan_delay(delay);
+    }
+    builder
+}
+
+#[cfg(feature = "ledger")]
+fn ledger_backend(path: &str) -> Result<Arc<dyn solana_block_finder::RpcBackend>, Box<dyn Error>> {
+    Ok(Arc::new(solana_block_finder::ledger::LedgerBackend::open(path)?))
+}
+
+#[cfg(not(feature = "ledger"))]
+fn ledger_backend(_path: &str) -> Result<Arc<dyn solana_block_finder::RpcBackend>, Box<dyn Error>> {
+    Err("❌ Error: --ledger requires building with --features ledger".into())
+}
+
+/// Prints the library's log events to stderr like the CLI's own status lines: warnings and
+/// progress always, the details of the search with --verbose
+struct StderrLog {
+    max_level: Level,
+}
+
+impl<S: Subscriber> Layer<S> for StderrLog {
+    fn on_event(&self, event: &Event<'_>, _context: Context<'_, S>) {
+        let metadata = event.metadata();
+        // Dependencies such as the HTTP client log too, but only at levels no one wants here
+        if *metadata.level() > self.max_level || !metadata.target().starts_with("solana_block_finder") {
+            return;
+        }
+        let mut message = EventMessage::default();
+        event.record(&mut message);
+        if *metadata.level() <= Level::WARN {
+            eprintln!("⚠️  {}", message.0);
+        } else {
+            eprintln!("{}", message.0);
+        }
+    }
+}
+
+/// The formatted message of a log event
+#[derive(Default)]
+struct EventMessage(String);
+
+impl Visit for EventMessage {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+/// Installs the tracing subscriber: log events go to stderr, and traces are exported when an
+/// OTLP endpoint is configured
+#[cfg(feature = "otlp")]
+fn init_telemetry(endpoint: Option<&str>, log: StderrLog) -> Result<Option<solana_block_finder::telemetry::Telemetry>, Box<dyn Error>> {
+    let Some(endpoint) = endpoint else {
+        tracing_subscriber::registry().with(log).try_init()?;
+        return Ok(None);
+    };
+    let (telemetry, exporter) = solana_block_finder::telemetry::layer(endpoint, "solana-block-finder")?;
+    tracing_subscriber::registry().with(log).with(exporter).try_init()?;
+    Ok(Some(telemetry))
+}
+
+#[cfg(not(feature = "otlp"))]
+fn init_telemetry(endpoint: Option<&str>, log: StderrLog) -> Result<Option<()>, Box<dyn Error>> {
+    if endpoint.is_some() {
+        return Err("❌ Error: --otlp-endpoint requires building with --features otlp".into());
+    }
+    tracing_subscriber::registry().with(log).try_init()?;
+    Ok(None)
+}
+
+/// Prints the best candidate of an interrupted search, clearly marked as partial.
+fn report_interrupted_search(finder: &BlockFinder) {
+    eprintln!("\n🛑 Interrupted!");
+    let progress = finder.search_progress();
+    match progress {
+        Some(progress) => {
+            println!("⚠️  PARTIAL RESULT - the search did not finish");
+            println!("🎯 Target timestamp: {}", progress.target_timestamp);
+            match progress.best_candidate() {
+                Some((slot, block_time)) => {
+                    println!("📍 Best candidate slot so far: {}", slot);
+                    println!("⏰ Block time: {} ({} seconds before the target)", display_time(block_time), progress.target_timestamp - block_time);
+                }
+                None => println!("📍 No candidate at or before the target was found yet"),
+            }
+            println!("🔎 Remaining search window: {} - {}", progress.low_slot, progress.high_slot);
+        }
+        None => eprintln!("No search was in progress."),
+    }
+}
+
+async fn run_timestamp_search(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    // A resumed search takes its target from the checkpoint
+    let resumed = match (&options.checkpoint_file, options.resume) {
+        (Some(path), true) => {
+            let progress = read_checkpoint(path)?;
+            if options.target_timestamp.is_some_and(|ts| ts != progress.target_timestamp) {
+                return Err(format!("❌ Error: Checkpoint {} is for timestamp {}, not {}", path, progress.target_timestamp, options.target_timestamp.unwrap_or_default()).into());
+            }
+            Some(progress)
+        }
+        _ => None,
+    };
+    let target_timestamp = match &resumed {
+        Some(progress) => progress.target_timestamp,
+        None => options.target_timestamp.ok_or("Missing required parameter --timestamp")?,
+    };
+    
+    // Current time check
+    if finder.is_in_future(target_timestamp)? {
+        if options.estimate_future {
+            return print_future_estimate(finder, target_timestamp, options).await;
+        }
+        return Err("❌ Error: Timestamp is in the future (use --estimate-future for a projected slot)".into());
+    }
+    
+    // With --format, or a machine-readable --output of --all-matching, stdout only holds the results
+    let templated = options.format.is_some() || (options.all_matching && options.output != OutputFormat::Text);
+    if !templated {
+        println!("🔍 Searching for block with timestamp {} or right before it...", target_timestamp);
+        if options.verbose {
+            println!("📊 Using RPC endpoint: {}", finder.redact(&finder.endpoints().join(", ")));
+        }
+    }
+    
+    // Use the optimized search function
+    let start_time = std::time::Instant::now();
+    let result = match resumed {
+        Some(progress) => {
+            if !templated {
+                println!("♻️  Resuming search in slots {} - {}", progress.low_slot, progress.high_slot);
+            }
+            finder.resume_slot_search(progress).await
+        }
+        None => finder.get_slot_by_timestamp_optimized(target_timestamp).await,
+    };
+    let search_duration = start_time.elapsed();
+    let (slot, approximate) = match result {
+        Ok(slot) => {
+            // The search finished, so there is nothing left to resume
+            if let Some(path) = &options.checkpoint_file {
+                let _ = std::fs::remove_file(path);
+            }
+            (slot, false)
+        }
+        Err(e) => match finder.best_effort_slot(e.as_ref()) {
+            Some(slot) if templated => {
+                eprintln!("⚠️  APPROXIMATE RESULT - {}", e);
+                (slot, true)
+            }
+            Some(slot) => {
+                println!("⚠️  APPROXIMATE RESULT - {}", e);
+                (slot, true)
+            }
+            None => return Err(e),
+        },
+    };
+    
+    if options.all_matching {
+        return print_all_matching(finder, slot, target_timestamp, approximate, options).await;
+    }
+    
+    if let Some(template) = &options.format {
+        let block_info = finder.get_block_info(slot, false).await?;
+        let finalized = finder.is_finalized(slot).await?;
+        let block_match = BlockMatch { approximate, finalized: Some(finalized), ..BlockMatch::new(slot, &block_info, Some(target_timestamp)) };
+        std::println!("{}", template.render(&block_match));
+        return Ok(());
+    }
+    
+    print_found_block(finder, slot, Some(target_timestamp), options).await?;
+    
+    if options.verbose {
+        println!("\n⚡ Performance: Search completed in {:.2} seconds", search_duration.as_secs_f64());
+        println!("🌐 Block Explorer: https://explorer.solana.com/block/{}", slot);
+    } else {
+        println!("\n⚡ Search completed in {:.2} seconds", search_duration.as_secs_f64());
+    }
+    
+    Ok(())
+}
+
+/// `--all-matching`: lists every block sharing the block time of the found one, in the
+/// `--format` or `--output` of schedule
+async fn print_all_matching(
+    finder: &BlockFinder,
+    slot: Slot,
+    target_timestamp: UnixTs,
+    approximate: bool,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let mut entries = Vec::new();
+    for slot in finder.blocks_sharing_timestamp(slot).await? {
+        let block_info = finder.get_block_info(slot, false).await?;
+        entries.push(BlockMatch { approximate, ..BlockMatch::new(slot, &block_info, Some(target_timestamp)) });
+    }
+    
+    match options.output {
+        OutputFormat::Text => match &options.format {
+            Some(template) => {
+                for entry in &entries {
+                    std::println!("{}", template.render(entry));
+                }
+            }
+            None => {
+                let block_time = entries.first().and_then(|entry| entry.block_time).unwrap_or_default();
+                println!("\n👥 {} blocks share block time {}:", entries.len(), display_time(block_time));
+                for entry in &entries {
+                    println!("   • slot {} {}", entry.slot, entry.blockhash);
+                }
+            }
+        },
+        OutputFormat::Csv => print_csv(&entries),
+        OutputFormat::Json => std::println!("{}", serde_json::to_string_pretty(&records(&entries, options))?),
+        OutputFormat::Yaml => std::print!("{}", serde_yaml::to_string(&records(&entries, options))?),
+        OutputFormat::Arrow | OutputFormat::Parquet => write_columnar(&entries, options)?,
+    }
+    
+    Ok(())
+}
+
+/// Prints the slot expected at a future timestamp, projected from the recent slot rate
+async fn print_future_estimate(finder: &BlockFinder, target_timestamp: UnixTs, options: &Options) -> Result<(), Box<dyn Error>> {
+    if let Some(template) = &options.format {
+        let estimate = finder.estimate_slot_at(target_timestamp).await?;
+        std::println!("{}", template.render(&BlockMatch::projected(&estimate)));
+        return Ok(());
+    }
+    
+    println!("🔮 {} is {} in the future, projecting its slot from the recent slot rate...", display_time(target_timestamp), human_duration(target_timestamp - finder.now()?));
+    let estimate = finder.estimate_slot_at(target_timestamp).await?;
+    println!("\n🔮 Estimated slot: ~{} (estimated, not produced yet)", estimate.estimated_slot);
+    println!("📐 Uncertainty window: slots {} - {}", estimate.lowest_slot, estimate.highest_slot);
+    println!("⛓️  Latest block: slot {} at {}, {} slots to go", estimate.tip_slot, display_time(estimate.tip_time), estimate.slots_to_go());
+    println!("⌛ Recent slot time: {:.1} ms on average, {:.1} - {:.1} ms over {} samples", estimate.rate.mean_ms, estimate.rate.fastest_ms, estimate.rate.slowest_ms, estimate.rate.samples);
+    Ok(())
+}
+
+async fn run_find_tx(finder: &BlockFinder, signature: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    println!("🔍 Looking up transaction {}...", signature);
+    if options.verbose {
+        println!("📊 Using RPC endpoint: {}", finder.redact(&finder.endpoints().join(", ")));
+    }
+    
+    let (slot, block_time) = finder.get_transaction_slot(signature).await?;
+    println!("🧾 Transaction landed in slot {}", slot);
+    if let Some(block_time) = block_time {
+        println!("⏰ Transaction block time: {}", display_time(block_time));
+    }
+    
+    print_found_block(finder, slot, None, options).await?;
+    
+    if options.verbose {
+        println!("\n🌐 Transaction: https://explorer.solana.com/tx/{}", signature);
+        println!("🌐 Block Explorer: https://explorer.solana.com/block/{}", slot);
+    }
+    
+    Ok(())
+}
+
+async fn run_activity(finder: &BlockFinder, address: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let from_timestamp = options.from_timestamp.ok_or("Missing required parameter --from")?;
+    let to_timestamp = options.to_timestamp.ok_or("Missing required parameter --to")?;
+    if from_timestamp > to_timestamp {
+        return Err("❌ Error: --from must not be after --to".into());
+    }
+    
+    println!("🔍 Listing transactions of {} between {} and {}...", address, from_timestamp, to_timestamp);
+    let (from_slot, to_slot) = finder.resolve_window_slots(from_timestamp, to_timestamp).await?;
+    println!("📍 Window slots: {} - {}", from_slot, to_slot);
+    
+    // Page backwards from the newest signature until we are past the start of the window
+    let page_size = 1000;
+    let mut before: Option<String> = None;
+    let mut found = 0;
+    'pages: loop {
+        let page = finder.get_signatures_for_address(address, before.as_deref(), None, page_size).await?;
+        let page_len = page.len();
+        
+        for info in page {
+            before = Some(info.signature.clone());
+            if info.slot > to_slot {
+                continue;
+            }
+            if info.slot < from_slot {
+                break 'pages;
+            }
+            // Without a blockTime the slot range above is all there is to go by
+            if info.block_time.is_some_and(|block_time| block_time < from_timestamp || block_time > to_timestamp) {
+                continue;
+            }
+            
+            found += 1;
+            let status = if info.err.is_some() { "❌" } else { "✅" };
+            let block_time = info.block_time.map(display_time).unwrap_or_else(|| "unknown".to_string());
+            print!("{} slot {} time {} {}", status, info.slot, block_time, info.signature);
+            if let Some(memo) = &info.memo {
+                std::print!(" memo: {}", memo);
+            }
+            println!();
+        }
+        
+        if page_len < page_size {
+            break;
+        }
+        
+        sleep(Duration::from_millis(10)).await;
+    }
+    
+    println!("\n✅ Found {} transactions in the window", found);
+    Ok(())
+}
+
+async fn run_last_activity(finder: &BlockFinder, address: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let target_timestamp = options.target_timestamp.ok_or("Missing required parameter --timestamp")?;
+    if finder.is_in_future(target_timestamp)? {
+        return Err("❌ Error: Timestamp is in the future".into());
+    }
+    
+    println!("🔍 Looking for the last transaction of {} at or before {}...", address, display_time(target_timestamp));
+    let bound_slot = finder.get_slot_by_timestamp_optimized(target_timestamp).await?;
+    println!("📍 Bounding slot: {}", bound_slot);
+    
+    // Signatures come newest first, so page backwards until one is at or before the bounding slot
+    let page_size = 1000;
+    let mut before: Option<String> = None;
+    let mut pages = 0;
+    let last = 'pages: loop {
+        let page = finder.get_signatures_for_address(address, before.as_deref(), Some(bound_slot), page_size).await?;
+        let page_len = page.len();
+        
+        for info in page {
+            before = Some(info.signature.clone());
+            if info.slot <= bound_slot && info.block_time.is_none_or(|t| t <= target_timestamp) {
+                break 'pages Some(info);
+            }
+        }
+        
+        if page_len < page_size {
+            break None;
+        }
+        pages += 1;
+        if pages % 10 == 0 {
+            eprintln!("⏳ Skipped {} newer signatures so far", pages * page_size);
+        }
+        
+        sleep(Duration::from_millis(10)).await;
+    };
+    
+    let Some(info) = last else {
+        println!("\n❌ {} has no transactions at or before {}", address, display_time(target_timestamp));
+        return Ok(());
+    };
+    let status = if info.err.is_some() { "failed" } else { "succeeded" };
+    println!("\n✅ Last transaction before the target:");
+    println!("🧾 Signature: {}", info.signature);
+    println!("📍 Slot: {}", info.slot);
+    if let Some(block_time) = info.block_time {
+        println!("⏰ Block time: {} ({} before the target)", display_time(block_time), human_duration(target_timestamp - block_time));
+    }
+    println!("🚦 Status: {}", status);
+    if let Some(memo) = &info.memo {
+        print!("📝 Memo: ");
+        std::println!("{}", memo);
+    }
+    if options.verbose {
+        println!("🌐 Transaction: https://explorer.solana.com/tx/{}", info.signature);
+    }
+    
+    Ok(())
+}
+
+async fn run_created_at(finder: &BlockFinder, address: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    println!("🔍 Paging through the signature history of {}...", address);
+    let creation = finder.earliest_signature(address).await?
+        .ok_or_else(|| format!("❌ Error: No transactions found for {}", address))?;
+    
+    println!("\n✅ Earliest transaction of {}:", address);
+    println!("🧾 Signature: {}", creation.signature);
+    println!("📍 Slot: {}", creation.slot);
+    let block_time = match creation.block_time {
+        Some(block_time) => Some(block_time),
+        None => finder.get_block_time(creation.slot).await?,
+    };
+    println!("⏰ Block time: {}", block_time.map(display_time).unwrap_or_else(|| "unknown".to_string()));
+    if creation.err.is_some() {
+        println!("⚠️  This transaction failed, so the account may have been created by a later one.");
+    }
+    if options.verbose {
+        println!("🌐 Transaction: https://explorer.solana.com/tx/{}", creation.signature);
+    }
+    
+    Ok(())
+}
+
+async fn run_mint_created(finder: &BlockFinder, mint: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    println!("🔍 Paging through the signature history of mint {}...", mint);
+    // The mint account is created together with its InitializeMint instruction, so the oldest
+    // transaction touching it is the creation
+    let creation = finder.earliest_signature(mint).await?
+        .ok_or_else(|| format!("❌ Error: No transactions found for {}", mint))?;
+    
+    println!("🪙 Mint created by transaction {}", creation.signature);
+    if let Some(block_time) = creation.block_time {
+        println!("⏰ Created at: {}", display_time(block_time));
+    }
+    print_found_block(finder, creation.slot, None, options).await?;
+    
+    if options.verbose {
+        println!("\n🌐 Transaction: https://explorer.solana.com/tx/{}", creation.signature);
+    }
+    
+    Ok(())
+}
+
+async fn run_deployments(finder: &BlockFinder, program_id: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    println!("🔍 Looking up the deployments of program {}...", program_id);
+    
+    // Deployments and upgrades of upgradeable programs all write the program data account,
+    // which nothing else touches, so its history is short. Older loaders cannot upgrade, so
+    // their only deployment is the program's own first transaction.
+    let events = match finder.program_data_address(program_id).await? {
+        Some(program_data) => {
+            println!("📦 Program data account: {}", program_data);
+            finder.signature_history(&program_data).await?
+        }
+        None => {
+            println!("📦 Not upgradeable, paging through the program's whole history for its deployment...");
+            finder.earliest_signature(program_id).await?.into_iter().collect()
+        }
+    };
+    if events.is_empty() {
+        return Err(format!("❌ Error: No transactions found for {}", program_id).into());
+    }
+    
+    println!();
+    for (index, event) in events.iter().enumerate() {
+        // Besides upgrades, authority changes and size extensions also write the program data
+        let label = if index == 0 { "🚀 Deployed" } else { "🔄 Upgraded or reconfigured" };
+        let block_info = finder.get_block_info(event.slot, false).await?;
+        let block_time = event.block_time.or(block_info.block_time);
+        let status = if event.err.is_some() { " (failed)" } else { "" };
+        println!("{}{} in slot {} at {}", label, status, event.slot, block_time.map(display_time).unwrap_or_else(|| "unknown".to_string()));
+        println!("   🔗 Block hash: {}", block_info.blockhash);
+        println!("   🧾 Transaction: {}", event.signature);
+        if options.verbose {
+            println!("   🌐 https://explorer.solana.com/tx/{}", event.signature);
+        }
+    }
+    
+    println!("\n✅ Found {} deployment transactions", events.len());
+    Ok(())
+}
+
+async fn run_first_call(finder: &BlockFinder, program_id: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let from_timestamp = options.from_timestamp.ok_or("Missing required parameter --from")?;
+    let to_timestamp = match options.to_timestamp {
+        Some(to_timestamp) => to_timestamp,
+        None => finder.now()?,
+    };
+    if from_timestamp > to_timestamp {
+        return Err("❌ Error: --from must not be after --to".into());
+    }
+    
+    println!("🔍 Looking for the first call of {} between {} and {}...", program_id, from_timestamp, to_timestamp);
+    let (from_slot, to_slot) = finder.resolve_window_slots(from_timestamp, to_timestamp).await?;
+    println!("📍 Window slots: {} - {}", from_slot, to_slot);
+    
+    let Some(invocation) = finder.find_first_invocation(program_id, from_slot, to_slot).await? else {
+        return Err(format!("❌ Error: No block between slots {} and {} calls {}", from_slot, to_slot, program_id).into());
+    };
+    let transaction = &invocation.transaction;
+    println!("\n✅ First call of {}:", program_id);
+    println!("🧾 Signature: {}", transaction.signature);
+    println!("👛 Fee payer: {}", transaction.fee_payer);
+    println!("📍 Slot: {}", invocation.slot);
+    println!("⏰ Block time: {}", invocation.block_time.map(display_time).unwrap_or_else(|| "unknown".to_string()));
+    match &transaction.err {
+        None => println!("🚦 Status: succeeded"),
+        Some(err) => {
+            print!("🚦 Status: ");
+            std::println!("failed ({})", err);
+        }
+    }
+    if options.verbose {
+        println!("🌐 Transaction: https://explorer.solana.com/tx/{}", transaction.signature);
+    }
+    
+    Ok(())
+}
+
+async fn run_feature(finder: &BlockFinder, feature_id: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    println!("🔍 Looking up the activation of feature {}...", feature_id);
+    let Some(activation) = finder.feature_activation(feature_id).await? else {
+        let epoch_info = finder.get_epoch_info().await?;
+        println!("\n⏳ Feature {} is not activated yet (current epoch: {}).", feature_id, epoch_info.epoch);
+        return Ok(());
+    };
+    
+    println!("\n✅ Feature {} activated:", feature_id);
+    println!("📍 Slot: {}", activation.slot);
+    println!("⏰ Block time: {}", activation.block_time.map(display_time).unwrap_or_else(|| "unknown".to_string()));
+    println!("🗓️  Epoch: {}", activation.epoch);
+    let schedule = finder.get_epoch_schedule().await?;
+    print_epoch_boundaries(finder, &schedule, activation.epoch, options).await
+}
+
+async fn run_epoch_at(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let target_timestamp = options.target_timestamp.ok_or("Missing required timestamp")?;
+    
+    println!("🔍 Resolving epoch at timestamp {}...", target_timestamp);
+    let slot = finder.get_slot_by_timestamp_optimized(target_timestamp).await?;
+    let schedule = finder.get_epoch_schedule().await?;
+    let (epoch, slot_index) = schedule.get_epoch_and_slot_index(slot);
+    
+    println!("\n✅ Found epoch:");
+    println!("📍 Slot: {}", slot);
+    println!("🗓️  Epoch: {}", epoch);
+    println!("🔢 Slot index: {} / {}", slot_index, schedule.get_slots_in_epoch(epoch));
+    print_epoch_boundaries(finder, &schedule, epoch, options).await
+}
+
+async fn run_epoch(finder: &BlockFinder, epoch: u64, options: &Options) -> Result<(), Box<dyn Error>> {
+    println!("🔍 Resolving boundaries of epoch {}...", epoch);
+    let schedule = finder.get_epoch_schedule().await?;
+    
+    println!("\n✅ Epoch {}:", epoch);
+    print_epoch_boundaries(finder, &schedule, epoch, options).await
+}
+
+async fn run_dump_epoch(finder: &BlockFinder, epoch: u64, options: &Options) -> Result<(), Box<dyn Error>> {
+    let schedule = finder.get_epoch_schedule().await?;
+    let (first_slot, last_slot) = epoch_slots(&schedule, epoch)?;
+    let current_slot = finder.get_current_slot().await?;
+    if first_slot > current_slot {
+        return Err(format!("❌ Error: Epoch {} has not started yet", epoch).into());
+    }
+    // The current epoch is dumped up to the current slot
+    let last_slot = last_slot.min(current_slot);
+    eprintln!("🔍 Dumping every produced block of epoch {} (slots {} - {})...", epoch, first_slot, last_slot);
+    
+    // Text and CSV rows are written as they arrive, the other formats need all rows first
+    let streaming = matches!(options.output, OutputFormat::Text | OutputFormat::Csv);
+    if streaming {
+        std::println!("slot,block_time");
+    }
+    
+    let mut rows: Vec<(Slot, UnixTs)> = Vec::new();
+    let mut count: u64 = 0;
+    let mut errors: u64 = 0;
+    let mut blocks = std::pin::pin!(finder.stream_blocks(first_slot..=last_slot));
+    while let Some(item) = blocks.next().await {
+        match item {
+            Ok((slot, block_time)) => {
+                count += 1;
+                if streaming {
+                    std::println!("{},{}", slot, block_time);
+                } else {
+                    rows.push((slot, block_time));
+                }
+                if count.is_multiple_of(10_000) {
+                    eprintln!("⏳ {} blocks so far, at slot {}", count, slot);
+                }
+            }
+            Err(e) => {
+                errors += 1;
+                eprintln!("⚠️  {}", e);
+            }
+        }
+    }
+    
+    match options.output {
+        OutputFormat::Text | OutputFormat::Csv => {}
+        OutputFormat::Json | OutputFormat::Yaml => {
+            let rows: Vec<SlotTimeRecord> = rows.iter().map(|&(slot, block_time)| SlotTimeRecord::new(slot, block_time)).collect();
+            if options.output == OutputFormat::Json {
+                std::println!("{}", serde_json::to_string_pretty(&rows)?);
+            } else {
+                std::print!("{}", serde_yaml::to_string(&rows)?);
+            }
+        }
+        OutputFormat::Arrow | OutputFormat::Parquet => write_slot_times(&rows, options)?,
+    }
+    
+    eprintln!("✅ Dumped {} blocks of {} slots", count, last_slot - first_slot + 1);
+    if errors > 0 {
+        return Err(format!("❌ {} block time lookups failed, the dump is incomplete", errors).into());
+    }
+    Ok(())
+}
+
+/// Crawls into the --index file, saving it after every batch so Ctrl+C loses at most one batch
+async fn run_index_crawl(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = options.index_file.as_deref().ok_or("--index is required")?;
+    let mut index = SlotIndex::open(path)?;
+    let stride = options.stride.or(index.crawl.as_ref().map(|crawl| crawl.stride)).unwrap_or(DEFAULT_CRAWL_STRIDE);
+    eprintln!("🔍 Crawling block times every {} slots into {} ({} entries so far)...", stride, path, index.len());
+    
+    finder
+        .crawl_index(&mut index, stride, |index| {
+            if let Some(slot) = index.crawl.as_ref().and_then(|crawl| crawl.next_slot) {
+                eprintln!("⏳ {} entries, next at slot {}", index.len(), slot);
+            }
+            index.save(path)
+        })
+        .await?;
+    index.save(path)?;
+    
+    match (index.entries.first_key_value(), index.entries.last_key_value()) {
+        (Some((first, _)), Some((last, _))) => println!("✅ Index complete: {} entries from slot {} to {}", index.len(), first, last),
+        _ => println!("✅ Index complete, no blocks found"),
+    }
+    Ok(())
+}
+
+/// Writes the --index file in the portable export format, to FILE or '-' for stdout
+fn run_index_export(options: &Options, file: &str) -> Result<(), Box<dyn Error>> {
+    let path = options.index_file.as_deref().ok_or("--index is required")?;
+    let index = SlotIndex::read(path)?;
+    if file == "-" {
+        index.export(std::io::stdout().lock())?;
+    } else {
+        let output = fs::File::create(file).map_err(|e| format!("❌ Cannot create {}: {}", file, e))?;
+        index.export(std::io::BufWriter::new(output))?;
+    }
+    eprintln!("✅ Exported {} entries to {}", index.len(), file);
+    Ok(())
+}
+
+/// Merges an export from FILE, or '-' for stdin, into the --index file
+fn run_index_import(options: &Options, file: &str) -> Result<(), Box<dyn Error>> {
+    let path = options.index_file.as_deref().ok_or("--index is required")?;
+    let mut index = SlotIndex::open(path)?;
+    let before = index.len();
+    let imported = if file == "-" {
+        index.import(std::io::stdin().lock())
+    } else {
+        let input = fs::File::open(file).map_err(|e| format!("❌ Cannot read {}: {}", file, e))?;
+        index.import(std::io::BufReader::new(input))
+    }
+    .map_err(|e| format!("❌ Cannot import {}: {}", file, e))?;
+    index.save(path)?;
+    println!("✅ Imported {} entries from {}, {} new; {} now has {} entries", imported, file, index.len() - before, path, index.len());
+    Ok(())
+}
+
+/// Thins the --index file to --resolution and drops entries older than --horizon
+fn run_index_compact(options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = options.index_file.as_deref().ok_or("--index is required")?;
+    let mut index = SlotIndex::read(path)?;
+    let now = UnixTs(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64);
+    let oldest = options.horizon_seconds.map(|horizon| now - horizon);
+    let removed = index.compact(options.resolution_seconds.unwrap_or(0), oldest);
+    index.save(path)?;
+    println!("✅ Removed {} entries, {} has {} left", removed, path, index.len());
+    Ok(())
+}
+
+/// `calibration/<chain>.toml` next to the default config file
+fn default_calibration_path(chain: &str) -> Option<PathBuf> {
+    Some(default_config_path()?.with_file_name("calibration").join(format!("{}.toml", chain)))
+}
+
+/// Where `calibration` reads and writes the era table: --calibration, `calibration` in the
+/// config file, or the default path of the chain
+fn calibration_path(options: &Options) -> Result<PathBuf, Box<dyn Error>> {
+    options.calibration_file.as_ref().map(PathBuf::from)
+        .or_else(|| default_calibration_path(&options.chain_profile.name))
+        .ok_or_else(|| "❌ Error: No default calibration path (HOME is not set), pass --calibration <FILE>".into())
+}
+
+/// The era table searches use, `None` for the built-in one. A missing file is only an error
+/// when it was configured and is not about to be written (`creating`).
+fn load_calibration(options: &Options, creating: bool) -> Result<Option<Calibration>, Box<dyn Error>> {
+    let path = calibration_path(options)?;
+    if (options.calibration_file.is_none() || creating) && !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(Calibration::load(path)?))
+}
+
+fn print_calibration(calibration: &Calibration) {
+    for era in &calibration.eras {
+        println!("  from slot {:>11} ({}): {:.1} ms per slot", era.start_slot, display_time(era.start_time), era.slot_duration_ms);
+    }
+    if let Some(measured) = &calibration.measured_rate {
+        println!("⌛ Measured at {} over {}: {:.1} ms per slot, standard deviation {:.1} ms", display_time(measured.measured_at), human_duration(measured.window_secs), measured.mean_ms, measured.stddev_ms);
+    }
+}
+
+fn run_calibration_show(options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = calibration_path(options)?;
+    match load_calibration(options, false)? {
+        Some(calibration) => {
+            println!("📐 {} eras in {}:", calibration.eras.len(), path.display());
+            print_calibration(&calibration);
+        }
+        None => {
+            let builtin = Calibration::nominal(&options.chain_profile);
+            println!("📐 No calibration at {}, using the built-in table of {}:", path.display(), options.chain_profile.name);
+            if builtin.is_empty() {
+                println!("  no eras, run 'calibration refresh' to measure them");
+            }
+            print_calibration(&builtin);
+        }
+    }
+    Ok(())
+}
+
+async fn run_calibration_refresh(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = calibration_path(options)?;
+    let samples = options.samples.unwrap_or(DEFAULT_CALIBRATION_SAMPLES);
+    println!("🔍 Sampling {} blocks across the history of {}...", samples, finder.chain().name);
+    let mut calibration = finder.measure_calibration(samples).await?;
+    // Keep the rate measured by `calibrate`
+    calibration.measured_rate = load_calibration(options, true)?.and_then(|existing| existing.measured_rate);
+    calibration.save(&path)?;
+    println!("\n✅ Saved {} eras to {}:", calibration.eras.len(), path.display());
+    print_calibration(&calibration);
+    Ok(())
+}
+
+// Window `calibrate` measures without --window
+const DEFAULT_CALIBRATE_WINDOW: i64 = 3_600;
+
+async fn run_calibrate(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = calibration_path(options)?;
+    let window = options.window_seconds.unwrap_or(DEFAULT_CALIBRATE_WINDOW);
+    println!("🔍 Measuring the slot rate of the last {}...", human_duration(window));
+    let rate = finder.measure_slot_rate(window).await?;
+    
+    println!("\n⌛ Average slot time: {:.1} ms, {:.2} slots per second", rate.mean_ms, 1000.0 / rate.mean_ms);
+    println!("📊 Standard deviation: {:.1} ms (variance {:.1} ms²) over {} samples", rate.stddev_ms, rate.stddev_ms.powi(2), rate.samples);
+    println!("📐 Range: {:.1} - {:.1} ms", rate.fastest_ms, rate.slowest_ms);
+    
+    // Eras measured by `calibration refresh` stay, a new file starts from the built-in table
+    let mut calibration = load_calibration(options, true)?.unwrap_or_else(|| Calibration::nominal(&options.chain_profile));
+    calibration.measured_rate = Some(MeasuredRate::new(finder.now()?, window, rate));
+    calibration.save(&path)?;
+    println!("✅ Saved to {}, estimates use it for the next {}", path.display(), human_duration(CALIBRATED_RATE_MAX_AGE_SECS));
+    Ok(())
+}
+
+/// Whether --index names a server started with `index serve` rather than a file
+fn is_remote_index(index: &str) -> bool {
+    index.starts_with("http://") || index.starts_with("https://")
+}
+
+/// Address `index serve` listens on without --listen
+const DEFAULT_INDEX_LISTEN: SocketAddr = SocketAddr::new(IpAddr::V4(std::net::Ipv4Addr::LOCALHOST), 8900);
+
+/// The file behind `index serve`, read again whenever it changes, e.g. during a crawl
+struct ServedIndex {
+    path: PathBuf,
+    state: Mutex<ServedState>,
+}
+
+struct ServedState {
+    modified: Option<SystemTime>,
+    index: Arc<SlotIndex>,
+    // The entries ordered by time, for nearest-anchor queries
+    anchors: Arc<DiscoveryMap>,
+}
+
+impl ServedState {
+    fn read(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+        let index = SlotIndex::read(path)?;
+        let anchors = DiscoveryMap::with_capacity(index.len());
+        index.seed(&anchors);
+        Ok(ServedState { modified, index: Arc::new(index), anchors: Arc::new(anchors) })
+    }
+}
+
+impl ServedIndex {
+    fn open(path: &str) -> Result<Self, Box<dyn Error>> {
+        let path = PathBuf::from(path);
+        let state = ServedState::read(&path)?;
+        Ok(ServedIndex { path, state: Mutex::new(state) })
+    }
+    
+    fn current(&self) -> (Arc<SlotIndex>, Arc<DiscoveryMap>) {
+        let modified = fs::metadata(&self.path).and_then(|metadata| metadata.modified()).ok();
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if modified != state.modified {
+            match ServedState::read(&self.path) {
+                Ok(reloaded) => *state = reloaded,
+                Err(e) => {
+                    eprintln!("⚠️  Keeping the previous index: {}", e);
+                    state.modified = modified;
+                }
+            }
+        }
+        (Arc::clone(&state.index), Arc::clone(&state.anchors))
+    }
+    
+    /// Answers `GET /nearest?timestamp=T` and `GET /range?from_slot=A&to_slot=B`
+    fn respond(&self, request: &hyper::Request<hyper::Body>) -> hyper::Response<hyper::Body> {
+        use hyper::StatusCode;
+        
+        if request.method() != hyper::Method::GET {
+            return json_response(StatusCode::METHOD_NOT_ALLOWED, &serde_json::json!({ "error": "Only GET is supported" }));
+        }
+        let query: HashMap<String, String> = request
+            .uri()
+            .query()
+            .map(|query| reqwest::Url::parse(&format!("http://index/?{}", query)))
+            .and_then(Result::ok)
+            .map(|url| url.query_pairs().into_owned().collect())
+            .unwrap_or_default();
+        let param = |name: &str| query.get(name).and_then(|value| value.parse::<i64>().ok());
+        let (index, anchors) = self.current();
+        
+        match request.uri().path() {
+            "/nearest" => match param("timestamp") {
+                Some(timestamp) => json_response(StatusCode::OK, &NearestAnchors::of(&anchors, UnixTs(timestamp))),
+                None => json_response(StatusCode::BAD_REQUEST, &serde_json::json!({ "error": "timestamp must be a Unix timestamp" })),
+            },
+            "/range" => match (param("from_slot"), param("to_slot")) {
+                (Some(from), Some(to)) if from >= 0 && to >= 0 => {
+                    json_response(StatusCode::OK, &index.range(Slot(from as u64), Slot(to as u64)))
+                }
+                _ => json_response(StatusCode::BAD_REQUEST, &serde_json::json!({ "error": "from_slot and to_slot must be slot numbers" })),
+            },
+            _ => json_response(StatusCode::NOT_FOUND, &serde_json::json!({ "error": "Unknown path, use /nearest or /range" })),
+        }
+    }
+}
+
+fn json_response(status: hyper::StatusCode, body: &impl Serialize) -> hyper::Response<hyper::Body> {
+    let body = serde_json::to_string(body).unwrap_or_default();
+    let mut response = hyper::Response::new(hyper::Body::from(body));
+    *response.status_mut() = status;
+    response.headers_mut().insert(hyper::header::CONTENT_TYPE, hyper::header::HeaderValue::from_static("application/json"));
+    response
+}
+
+/// Serves the --index file so other machines can pass its URL as --index
+async fn run_index_serve(options: &Options) -> Result<(), Box<dyn Error>> {
+    use hyper::service::{make_service_fn, service_fn};
+    
+    let path = options.index_file.as_deref().ok_or("--index is required")?;
+    let served = Arc::new(ServedIndex::open(path)?);
+    let entries = served.current().0.len();
+    let addr = options.listen.unwrap_or(DEFAULT_INDEX_LISTEN);
+    
+    let make_service = make_service_fn(move |_| {
+        let served = Arc::clone(&served);
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let response = served.respond(&request);
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+    let server = hyper::Server::try_bind(&addr)
+        .map_err(|e| format!("❌ Cannot listen on {}: {}", addr, e))?
+        .serve(make_service);
+    println!("🌐 Serving {} ({} entries) on http://{}", path, entries, server.local_addr());
+    server
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+/// Prints the first and last slot of an epoch together with the times of the blocks at its boundaries.
+async fn run_eta(finder: &BlockFinder, slot: Slot) -> Result<(), Box<dyn Error>> {
+    println!("🔍 Estimating when slot {} will be produced...", slot);
+    let (tip_slot, _) = finder.latest_block_time().await?;
+    if slot <= tip_slot {
+        // Nothing to estimate, the block time (if any) is known
+        match finder.get_block_time(slot).await? {
+            Some(block_time) => println!("\n✅ Slot {} has already been produced at {}", slot, display_time(block_time)),
+            None => println!("\n✅ Slot {} has already passed without a block (latest block {})", slot, tip_slot),
+        }
+        return Ok(());
+    }
+    
+    let estimate = finder.estimate_slot_time(slot).await?;
+    let now = finder.now()?;
+    println!("\n🔮 Slot {} is {} slots ahead of the latest block {} ({})", slot, slot - estimate.tip_slot, estimate.tip_slot, display_time(estimate.tip_time));
+    if estimate.estimated_time > now {
+        println!("⏰ Estimated time: {} (in {})", display_time(estimate.estimated_time), human_duration(estimate.estimated_time - now));
+    } else {
+        // The latest block lags behind the wall clock, so very close slots can be due already
+        println!("⏰ Estimated time: {} (due now)", display_time(estimate.estimated_time));
+    }
+    println!("📐 Range: {} - {}", display_time(estimate.earliest_time), display_time(estimate.latest_time));
+    println!("⌛ Recent slot time: {:.1} ms on average, {:.1} - {:.1} ms over {} samples", estimate.rate.mean_ms, estimate.rate.fastest_ms, estimate.rate.slowest_ms, estimate.rate.samples);
+    
+    Ok(())
+}
+
+// Seconds between countdown updates without --every
+const DEFAULT_COUNTDOWN_INTERVAL: i64 = 10;
+
+async fn run_countdown(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let target_timestamp = options.target_timestamp.ok_or("Missing required parameter --timestamp")?;
+    let every = options.every_seconds.unwrap_or(DEFAULT_COUNTDOWN_INTERVAL);
+    if every <= 0 {
+        return Err("❌ Error: --every must be a positive duration".into());
+    }
+    println!("⏳ Counting down to {}, updating every {}...", display_time(target_timestamp), human_duration(every));
+    
+    // Performance samples cover a minute each, so the slot rate is only refreshed once a minute
+    let mut rate = None;
+    let mut rate_age = 0;
+    loop {
+        let (tip_slot, tip_time) = match finder.latest_block_time().await {
+            Ok(tip) => tip,
+            Err(e) => {
+                eprintln!("⚠️  {}", finder.redact(&e.to_string()));
+                sleep(Duration::from_secs(every as u64)).await;
+                continue;
+            }
+        };
+        if tip_time >= target_timestamp {
+            break;
+        }
+        if rate.is_none() || rate_age >= 60 {
+            match finder.estimation_slot_rate().await {
+                Ok(fresh) => {
+                    rate = Some(fresh);
+                    rate_age = 0;
+                }
+                Err(e) => eprintln!("⚠️  {}", finder.redact(&e.to_string())),
+            }
+        }
+        
+        let now = finder.now()?;
+        let left = human_duration((target_timestamp - now).max(0));
+        match rate {
+            Some(rate) => {
+                let estimate = SlotEstimate::project(target_timestamp, tip_slot, tip_time, rate);
+                println!("⏳ {} left: slot ~{} (range {} - {}), the chain is at slot {}, {} slots to go", left, estimate.estimated_slot, estimate.lowest_slot, estimate.highest_slot, tip_slot, estimate.slots_to_go());
+            }
+            None => println!("⏳ {} left, the chain is at slot {}", left, tip_slot),
+        }
+        
+        // Wake up right at the target instead of up to a whole interval later
+        let wait = every.min((target_timestamp - now).max(1));
+        sleep(Duration::from_secs(wait as u64)).await;
+        rate_age += wait;
+    }
+    
+    println!("\n🎯 {} has been reached, resolving its block...", display_time(target_timestamp));
+    let slot = finder.get_slot_by_timestamp_optimized(target_timestamp).await?;
+    print_found_block(finder, slot, Some(target_timestamp), options).await
+}
+
+async fn run_between(finder: &BlockFinder, from_slot: Slot, to_slot: Slot) -> Result<(), Box<dyn Error>> {
+    let (from_slot, to_slot) = (from_slot.min(to_slot), from_slot.max(to_slot));
+    println!("🔍 Measuring the time between slots {} and {}...", from_slot, to_slot);
+    
+    // Skipped slots have no block time, so measure from the first produced block at or after
+    // the lower slot to the last one at or before the upper slot
+    let first_block = finder.get_blocks_with_limit(from_slot, 1).await?
+        .into_iter()
+        .next()
+        .filter(|slot| *slot <= to_slot)
+        .ok_or_else(|| format!("❌ Error: No block was produced between slots {} and {}", from_slot, to_slot))?;
+    let last_block = finder.find_last_block_at_or_before(to_slot, first_block).await?.unwrap_or(first_block);
+    for (requested, block) in [(from_slot, first_block), (to_slot, last_block)] {
+        if requested != block {
+            println!("⚠️  Slot {} was skipped, using block {}", requested, block);
+        }
+    }
+    
+    let first_time = finder.get_block_time(first_block).await?.ok_or_else(|| format!("❌ Error: No block time for slot {}", first_block))?;
+    let last_time = finder.get_block_time(last_block).await?.ok_or_else(|| format!("❌ Error: No block time for slot {}", last_block))?;
+    let elapsed = last_time - first_time;
+    let slot_delta = last_block - first_block;
+    
+    println!("\n✅ Between blocks {} and {}:", first_block, last_block);
+    println!("🟢 From: block {} at {}", first_block, display_time(first_time));
+    println!("🔴 To: block {} at {}", last_block, display_time(last_time));
+    println!("⏱️  Elapsed: {} seconds ({})", elapsed, human_duration(elapsed));
+    println!("📍 Slot delta: {} slots", slot_delta);
+    if slot_delta > 0 {
+        println!("⌛ Average slot time: {:.1} ms (nominal {} ms)", elapsed as f64 * 1000.0 / slot_delta as f64, finder.chain().slot_duration_ms);
+    }
+    
+    Ok(())
+}
+
+async fn run_offset(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let slot = options.slot.ok_or("Missing required parameter --slot")?;
+    let offset = options.offset_seconds.ok_or("Missing required parameter --by")?;
+    let direction = if offset < 0 { "before" } else { "after" };
+    println!("🔍 Resolving the block {} {} slot {}...", human_duration(offset.abs()), direction, slot);
+    
+    // A skipped slot has no block time, so start from the last block at or before it
+    let reference = finder.find_last_block_at_or_before(slot, finder.chain().first_slot()).await?
+        .ok_or_else(|| format!("❌ Error: No block was produced at or before slot {}", slot))?;
+    let reference_time = finder.get_block_time(reference).await?
+        .ok_or_else(|| format!("❌ Error: No block time for slot {}", reference))?;
+    let target_timestamp = reference_time + offset;
+    let current_time = finder.now()?;
+    if target_timestamp > current_time {
+        return Err(format!("❌ Error: {} is {} seconds in the future", target_timestamp, target_timestamp - current_time).into());
+    }
+    
+    // Assume the slot timing of the reference block's era for the estimate, then widen a window around it until the
+    // block times at its edges bracket the target. The reference block bounds one side.
+    let current_slot = finder.get_current_slot().await?;
+    if offset > 0 {
+        if let Some(tip_time) = finder.get_block_time(current_slot).await? {
+            if tip_time < target_timestamp {
+                return Err(format!("❌ Error: The chain has not reached {} yet (latest block time {})", display_time(target_timestamp), display_time(tip_time)).into());
+            }
+        }
+    }
+    let (low, high) = if offset < 0 { (Slot(0), reference) } else { (reference, current_slot) };
+    let offset_slots = finder.calibration().slots_in(reference, offset, finder.chain());
+    let estimate = if offset < 0 { reference.saturating_sub(offset_slots) } else { reference.saturating_add(offset_slots).min(current_slot) };
+    if options.verbose {
+        match finder.calibration().era(reference) {
+            Some(era) => println!("📐 Estimated slot {} from the {:.1} ms slots of the era since slot {}", estimate, era.slot_duration_ms, era.start_slot),
+            None => println!("📐 Estimated slot {} from nominal {} ms slots", estimate, finder.chain().slot_duration_ms),
+        }
+    }
+    let mut margin = (offset_slots / 100).max(64);
+    let (lower, upper) = loop {
+        let lower = estimate.saturating_sub(margin).max(low);
+        let upper = estimate.saturating_add(margin).min(high);
+        let lower_ok = lower == low || finder.get_block_time(lower).await?.is_some_and(|t| t <= target_timestamp);
+        let upper_ok = upper == high || finder.get_block_time(upper).await?.is_some_and(|t| t > target_timestamp);
+        if lower_ok && upper_ok {
+            break (lower, upper);
+        }
+        margin = margin.saturating_mul(4);
+    };
+    
+    let found = finder.get_slot_by_timestamp_in_range(target_timestamp, lower, upper).await?;
+    if options.verbose {
+        println!("📏 The estimate was {} slots off", found.0.abs_diff(estimate.0));
+    }
+    print_found_block(finder, found, Some(target_timestamp), options).await?;
+    println!("⏱️  {} slots {} block {} (block time {})", found.0.abs_diff(reference.0), direction, reference, display_time(reference_time));
+    
+    Ok(())
+}
+
+/// Formats seconds like `2d 3h 4m 5s`, leaving out zero units
+fn human_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.unsigned_abs();
+    let units = [(seconds / 86_400, "d"), (seconds / 3_600 % 24, "h"), (seconds / 60 % 60, "m"), (seconds % 60, "s")];
+    let parts: Vec<String> = units
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+    if parts.is_empty() {
+        return "0s".to_string();
+    }
+    format!("{}{}", sign, parts.join(" "))
+}
+
+/// The first and last slot of `epoch`
+fn epoch_slots(schedule: &EpochSchedule, epoch: u64) -> Result<(Slot, Slot), Box<dyn Error>> {
+    schedule.get_first_slot_in_epoch(epoch)
+        .zip(schedule.get_last_slot_in_epoch(epoch))
+        .ok_or_else(|| format!("❌ Error: Epoch {} is beyond the last possible slot", epoch).into())
+}
+
+async fn print_epoch_boundaries(
+    finder: &BlockFinder,
+    schedule: &EpochSchedule,
+    epoch: u64,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    let (first_slot, last_slot) = epoch_slots(schedule, epoch)?;
+    let epoch_info = finder.get_epoch_info().await?;
+    
+    println!("⏮️  First slot: {}", first_slot);
+    println!("⏭️  Last slot: {}", last_slot);
+    if options.verbose {
+        println!("📐 Slots in epoch: {}", schedule.get_slots_in_epoch(epoch));
+        println!("👑 Leader schedule slot offset: {}", schedule.leader_schedule_slot_offset);
+        println!("🌱 Warmup epochs: {}", if schedule.warmup { "enabled" } else { "disabled" });
+    }
+    
+    if epoch > epoch_info.epoch {
+        println!("⏳ This epoch has not started yet (current epoch: {}).", epoch_info.epoch);
+        return Ok(());
+    }
+    
+    // The boundary slots may have been skipped, so use the first/last produced block inside the epoch
+    let start_block = finder.get_blocks_with_limit(first_slot, 1).await?
+        .into_iter()
+        .next()
+        .filter(|slot| *slot <= last_slot);
+    if let Some(start_block) = start_block {
+        let block_time = finder.get_block_time(start_block).await?;
+        println!("🟢 Start: block {} at {}", start_block, block_time.map(display_time).unwrap_or_else(|| "unknown".to_string()));
+    }
+    
+    if epoch == epoch_info.epoch {
+        println!("⏳ Epoch in progress: slot {} of {} (current slot {}).", epoch_info.slot_index, epoch_info.slots_in_epoch, epoch_info.absolute_slot);
+        return Ok(());
+    }
+    
+    let end_block = finder.find_last_block_at_or_before(last_slot, first_slot).await?;
+    if let Some(end_block) = end_block {
+        let block_time = finder.get_block_time(end_block).await?;
+        println!("🔴 End: block {} at {}", end_block, block_time.map(display_time).unwrap_or_else(|| "unknown".to_string()));
+    }
+    
+    Ok(())
+}
+
+/// Furthest ahead of the current time that schedule projects slots with --estimate-future.
+/// The slot rate drifts too much for anything later to be useful, and a long range with a
+/// short --every would otherwise collect millions of entries.
+const MAX_PROJECTION_SECS: i64 = 366 * 86_400;
+
+async fn run_schedule(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let from_timestamp = options.from_timestamp.ok_or("Missing required parameter --from")?;
+    let to_timestamp = options.to_timestamp.ok_or("Missing required parameter --to")?;
+    let every = options.every_seconds.ok_or("Missing required parameter --every")?;
+    if every <= 0 {
+        return Err("❌ Error: --every must be a positive duration".into());
+    }
+    if from_timestamp > to_timestamp {
+        return Err("❌ Error: --from must not be after --to".into());
+    }
+    
+    let current_time = finder.now()?;
+    let current_slot = finder.get_current_slot().await?;
+    eprintln!("🔍 Resolving every {} seconds between {} and {}...", every, from_timestamp, to_timestamp);
+    
+    let mut entries = Vec::new();
+    let mut low_slot = Slot(0);
+    let mut previous_slot: Option<Slot> = None;
+    let mut step_hint: Option<u64> = None;
+    // With --estimate-future, the latest block and slot rate that future targets are projected from
+    let mut projection = None;
+    let mut target_timestamp = from_timestamp;
+    while target_timestamp <= to_timestamp {
+        if target_timestamp > current_time {
+            if !options.estimate_future {
+                eprintln!("⚠️  Stopping at {}: timestamps in the future cannot be resolved (use --estimate-future to project them)", target_timestamp);
+                break;
+            }
+            if target_timestamp - current_time > MAX_PROJECTION_SECS {
+                eprintln!("⚠️  Stopping at {}: slots are not projected more than {} ahead", target_timestamp, human_duration(MAX_PROJECTION_SECS));
+                break;
+            }
+            let (tip_slot, tip_time, rate) = match projection {
+                Some(projection) => projection,
+                None => {
+                    let (tip_slot, tip_time) = finder.latest_block_time().await?;
+                    let rate = finder.estimation_slot_rate().await?;
+                    *projection.insert((tip_slot, tip_time, rate))
+                }
+            };
+            let estimate = SlotEstimate::project(target_timestamp, tip_slot, tip_time, rate);
+            let entry = BlockMatch::projected(&estimate);
+            if let Some(template) = &options.format {
+                std::println!("{}", template.render(&entry));
+            } else if options.output == OutputFormat::Text {
+                println!("{} → slot ~{} (estimated, range {} - {})", target_timestamp, estimate.estimated_slot, estimate.lowest_slot, estimate.highest_slot);
+            }
+            entries.push(entry);
+            let Some(next_timestamp) = target_timestamp.checked_add(every) else {
+                break;
+            };
+            target_timestamp = next_timestamp;
+            continue;
+        }
+        
+        // Targets are increasing, so the previous answer is a lower bound for this one, and
+        // twice the previous step is usually a valid upper bound
+        let mut high_slot = current_slot;
+        if let Some(step) = step_hint {
+            let guess = low_slot.saturating_add(step.saturating_mul(2)).min(current_slot);
+            if let Ok(Some(block_time)) = finder.get_block_time(guess).await {
+                if block_time > target_timestamp {
+                    high_slot = guess;
+                }
+            }
+        }
+        
+        let slot = finder.get_slot_by_timestamp_in_range(target_timestamp, low_slot, high_slot).await?;
+        let block_info = finder.get_block_info(slot, false).await?;
+        
+        let entry = BlockMatch::new(slot, &block_info, Some(target_timestamp));
+        if let Some(template) = &options.format {
+            std::println!("{}", template.render(&entry));
+        } else if options.output == OutputFormat::Text {
+            println!("{} → slot {} (block time {}) {}", target_timestamp, slot, display_time(block_info.block_time.unwrap_or_default()), block_info.blockhash);
+        }
+        entries.push(entry);
+        
+        step_hint = previous_slot.filter(|previous| slot > *previous).map(|previous| slot - previous);
+        previous_slot = Some(slot);
+        low_slot = slot;
+        // Past the last representable timestamp there are no more targets
+        let Some(next_timestamp) = target_timestamp.checked_add(every) else {
+            break;
+        };
+        target_timestamp = next_timestamp;
+    }
+    
+    match options.output {
+        OutputFormat::Text if options.format.is_some() => eprintln!("✅ Resolved {} targets", entries.len()),
+        OutputFormat::Text => println!("\n✅ Resolved {} targets", entries.len()),
+        OutputFormat::Csv => print_csv(&entries),
+        OutputFormat::Json => std::println!("{}", serde_json::to_string_pretty(&records(&entries, options))?),
+        OutputFormat::Yaml => std::print!("{}", serde_yaml::to_string(&records(&entries, options))?),
+        OutputFormat::Arrow | OutputFormat::Parquet => write_columnar(&entries, options)?,
+    }
+    
+    Ok(())
+}
+
+/// Resolves each timestamp read from stdin as soon as its line arrives and writes one line
+/// per timestamp: the slot, the `--format` line, or with `--output json` the match as a JSON object. Lines that
+/// fail are reported on stderr, so stdout only ever holds answers.
+async fn run_stdin(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    use std::io::Write;
+    use tokio::io::AsyncBufReadExt;
+    
+    let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = std::io::stdout();
+    let mut failed = 0;
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        
+        let result = match parse_timestamp(line) {
+            Ok(target_timestamp) if options.estimate_future => finder.find_or_estimate_block(target_timestamp).await,
+            Ok(target_timestamp) if finder.is_in_future(target_timestamp)? => {
+                Err("Timestamp is in the future".into())
+            }
+            Ok(target_timestamp) => finder.find_block_by_timestamp(target_timestamp).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(entry) if options.output == OutputFormat::Json => writeln!(stdout, "{}", serde_json::to_string(&record(&entry, options))?)?,
+            Ok(entry) => match &options.format {
+                Some(template) => writeln!(stdout, "{}", template.render(&entry))?,
+                None => writeln!(stdout, "{}", entry.slot)?,
+            },
+            Err(e) => {
+                eprintln!("❌ {}: {}", line, finder.redact(&e.to_string()));
+                failed += 1;
+            }
+        }
+        // Consumers such as `jq` or `xargs` act on each line as it arrives
+        stdout.flush()?;
+    }
+    
+    if failed > 0 {
+        return Err(format!("❌ {} timestamps from stdin failed", failed).into());
+    }
+    Ok(())
+}
+
+/// Reads one timestamp per line, skipping blank lines and `#` comments
+fn read_timestamps(path: &str) -> Result<Vec<UnixTs>, Box<dyn Error>> {
+    let input = if path == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("❌ Cannot read {}: {}", path, e))?
+    };
+    
+    let mut timestamps = Vec::new();
+    for (number, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let timestamp = parse_timestamp(line).map_err(|e| format!("{}:{}: {}", path, number + 1, e))?;
+        timestamps.push(timestamp);
+    }
+    Ok(timestamps)
+}
+
+async fn run_batch(finder: &BlockFinder, path: &str, options: &Options) -> Result<(), Box<dyn Error>> {
+    let timestamps = read_timestamps(path)?;
+    let total = timestamps.len();
+    let concurrency = options.batch_concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+    eprintln!("🔍 Resolving {} timestamps, {} at a time...", total, concurrency);
+    
+    // All searches share the finder, so their probes go through one rate limiter and pool. In
+    // time order, each answer bounds the next search.
+    let mut results = pin!(finder.find_blocks_by_sorted_timestamps(timestamps, concurrency));
+    let mut entries = Vec::new();
+    let mut failed = 0;
+    while let Some((target_timestamp, result)) = results.next().await {
+        match result {
+            Ok(entry) => {
+                if let Some(template) = &options.format {
+                    std::println!("{}", template.render(&entry));
+                } else if options.output == OutputFormat::Text {
+                    let marker = if entry.approximate { " (approximate)" } else { "" };
+                    println!("{} → slot {} (block time {}) {}{}", target_timestamp, entry.slot, display_time(entry.block_time.unwrap_or_default()), entry.blockhash, marker);
+                }
+                entries.push(entry);
+            }
+            Err(e) => {
+                eprintln!("❌ {}: {}", target_timestamp, finder.redact(&e.to_string()));
+                failed += 1;
+            }
+        }
+    }
+    
+    match options.output {
+        OutputFormat::Text if options.format.is_some() => eprintln!("✅ Resolved {} of {} timestamps with {} RPC calls", entries.len(), total, finder.rpc_calls()),
+        OutputFormat::Text => println!("\n✅ Resolved {} of {} timestamps with {} RPC calls", entries.len(), total, finder.rpc_calls()),
+        OutputFormat::Csv => print_csv(&entries),
+        OutputFormat::Json => std::println!("{}", serde_json::to_string_pretty(&records(&entries, options))?),
+        OutputFormat::Yaml => std::print!("{}", serde_yaml::to_string(&records(&entries, options))?),
+        OutputFormat::Arrow | OutputFormat::Parquet => write_columnar(&entries, options)?,
+    }
+    
+    if failed > 0 {
+        return Err(format!("❌ {} of {} timestamps failed", failed, total).into());
+    }
+    Ok(())
+}
+
+/// Versioned record for `--output json` and `--output yaml`, with diagnostics only if requested
+fn record(entry: &BlockMatch, options: &Options) -> BlockMatchRecord {
+    let mut entry = entry.clone();
+    if !options.diagnostics {
+        entry.diagnostics = None;
+    }
+    BlockMatchRecord::from(entry)
+}
+
+fn records(entries: &[BlockMatch], options: &Options) -> Vec<BlockMatchRecord> {
+    entries.iter().map(|entry| record(entry, options)).collect()
+}
+
+/// A field that `--format` templates can refer to as `{name}`
+#[derive(Debug, Clone, Copy)]
+enum TemplateField {
+    Slot,
+    Blockhash,
+    BlockTime,
+    BlockTimeIso,
+    BlockHeight,
+    TargetTimestamp,
+    TimeDiff,
+    Exact,
+    Approximate,
+    Finalized,
+    Estimated,
+    EstimateLow,
+    EstimateHigh,
+}
+
+const TEMPLATE_FIELDS: &[(&str, TemplateField)] = &[
+    ("slot", TemplateField::Slot),
+    ("blockhash", TemplateField::Blockhash),
+    ("block_time", TemplateField::BlockTime),
+    ("block_time_iso", TemplateField::BlockTimeIso),
+    ("block_height", TemplateField::BlockHeight),
+    ("target_timestamp", TemplateField::TargetTimestamp),
+    ("time_diff", TemplateField::TimeDiff),
+    ("exact", TemplateField::Exact),
+    ("approximate", TemplateField::Approximate),
+    ("finalized", TemplateField::Finalized),
+    ("estimated", TemplateField::Estimated),
+    ("estimate_low", TemplateField::EstimateLow),
+    ("estimate_high", TemplateField::EstimateHigh),
+];
+
+#[derive(Debug, Clone)]
+enum TemplatePart {
+    Text(String),
+    Field(TemplateField),
+}
+
+/// A `--format` line template such as `{slot},{blockhash},{block_time_iso}`. `{{` and `}}` are literal braces.
+#[derive(Debug, Clone)]
+struct Template(Vec<TemplatePart>);
+
+impl Template {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = input.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.next_if_eq(&'{').is_some() => text.push('{'),
+                '}' if chars.next_if_eq(&'}').is_some() => text.push('}'),
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => return Err(format!("❌ Invalid --format template: unclosed '{{' in '{}' (use '{{{{' for a literal brace)", input).into()),
+                        }
+                    }
+                    let field = TEMPLATE_FIELDS
+                        .iter()
+                        .find(|(field_name, _)| *field_name == name)
+                        .map(|(_, field)| *field)
+                        .ok_or_else(|| {
+                            let names: Vec<&str> = TEMPLATE_FIELDS.iter().map(|(name, _)| *name).collect();
+                            format!("❌ Unknown --format field '{{{}}}' (available: {})", name, names.join(", "))
+                        })?;
+                    if !text.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(TemplatePart::Field(field));
+                }
+                '}' => return Err(format!("❌ Invalid --format template: unmatched '}}' in '{}' (use '}}}}' for a literal brace)", input).into()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(TemplatePart::Text(text));
+        }
+        Ok(Template(parts))
+    }
+    
+    /// Renders one result. Unknown values are empty.
+    fn render(&self, entry: &BlockMatch) -> String {
+        let mut line = String::new();
+        for part in &self.0 {
+            match part {
+                TemplatePart::Text(text) => line.push_str(text),
+                TemplatePart::Field(field) => line.push_str(&match field {
+                    TemplateField::Slot => entry.slot.to_string(),
+                    TemplateField::Blockhash => entry.blockhash.clone(),
+                    TemplateField::BlockTime => entry.block_time.map(|t| t.to_string()).unwrap_or_default(),
+                    TemplateField::BlockTimeIso => entry.block_time
+                        .and_then(|t| DateTime::from_timestamp(t.into(), 0))
+                        .map(|utc| utc.to_rfc3339_opts(SecondsFormat::Secs, true))
+                        .unwrap_or_default(),
+                    TemplateField::BlockHeight => entry.block_height.map(|h| h.to_string()).unwrap_or_default(),
+                    TemplateField::TargetTimestamp => entry.target_timestamp.map(|t| t.to_string()).unwrap_or_default(),
+                    TemplateField::TimeDiff => entry.time_diff.map(|d| d.to_string()).unwrap_or_default(),
+                    TemplateField::Exact => entry.exact.to_string(),
+                    TemplateField::Approximate => entry.approximate.to_string(),
+                    TemplateField::Finalized => entry.finalized.map(|f| f.to_string()).unwrap_or_default(),
+                    TemplateField::Estimated => entry.estimated.to_string(),
+                    TemplateField::EstimateLow => entry.estimate_range.map(|(low, _)| low.to_string()).unwrap_or_default(),
+                    TemplateField::EstimateHigh => entry.estimate_range.map(|(_, high)| high.to_string()).unwrap_or_default(),
+                }),
+            }
+        }
+        line
+    }
+}
+
+// Column order of --output csv. Append new columns at the end, scripts depend on the order.
+const CSV_COLUMNS: &[&str] = &["input_ts", "slot", "block_time", "blockhash", "height", "diff_seconds", "approximate", "finalized", "estimated", "estimate_low", "estimate_high"];
+
+/// Prints results as RFC 4180 CSV. Unknown values are empty fields.
+fn print_csv(entries: &[BlockMatch]) {
+    std::println!("{}", CSV_COLUMNS.join(","));
+    for entry in entries {
+        let fields = [
+            entry.target_timestamp.map(|t| t.to_string()).unwrap_or_default(),
+            entry.slot.to_string(),
+            entry.block_time.map(|t| t.to_string()).unwrap_or_default(),
+            entry.blockhash.clone(),
+            entry.block_height.map(|h| h.to_string()).unwrap_or_default(),
+            entry.time_diff.map(|d| d.to_string()).unwrap_or_default(),
+            entry.approximate.to_string(),
+            entry.finalized.map(|f| f.to_string()).unwrap_or_default(),
+            entry.estimated.to_string(),
+            entry.estimate_range.map(|(low, _)| low.to_string()).unwrap_or_default(),
+            entry.estimate_range.map(|(_, high)| high.to_string()).unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        std::println!("{}", row.join(","));
+    }
+}
+
+/// Quotes a CSV field when it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes series results to --output-file as Arrow IPC or Parquet
+#[cfg(feature = "parquet")]
+fn write_columnar(entries: &[BlockMatch], options: &Options) -> Result<(), Box<dyn Error>> {
+    let path = options.output_file.as_deref().ok_or("Missing required parameter --output-file")?;
+    match options.output {
+        OutputFormat::Arrow => solana_block_finder::export::write_arrow(path, entries)?,
+        _ => solana_block_finder::export::write_parquet(path, entries)?,
+    }
+    eprintln!("📦 Wrote {} rows to {}", entries.len(), path);
+    Ok(())
+}
+
+/// Writes `dump-epoch` rows to --output-file as Arrow IPC or Parquet
+#[cfg(feature = "parquet")]
+fn write_slot_times(rows: &[(Slot, UnixTs)], options: &Options) -> Result<(), Box<dyn Error>> {
+    use solana_block_finder::export;
+    
+    let path = options.output_file.as_deref().ok_or("Missing required parameter --output-file")?;
+    let batch = export::slot_times_to_record_batch(rows)?;
+    match options.output {
+        OutputFormat::Arrow => export::write_arrow_batch(path, &batch)?,
+        _ => export::write_parquet_batch(path, &batch)?,
+    }
+    eprintln!("📦 Wrote {} rows to {}", rows.len(), path);
+    Ok(())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_slot_times(_rows: &[(Slot, UnixTs)], _options: &Options) -> Result<(), Box<dyn Error>> {
+    Err("❌ Error: --output arrow/parquet requires building with --features parquet".into())
+}
+
+#[cfg(not(feature = "parquet"))]
+fn write_columnar(_entries: &[BlockMatch], _options: &Options) -> Result<(), Box<dyn Error>> {
+    Err("❌ Error: --output arrow/parquet requires building with --features parquet".into())
+}
+
+async fn run_stats(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let from_timestamp = options.from_timestamp.ok_or("Missing required parameter --from")?;
+    let to_timestamp = options.to_timestamp.ok_or("Missing required parameter --to")?;
+    if from_timestamp > to_timestamp {
+        return Err("❌ Error: --from must not be after --to".into());
+    }
+    
+    println!("🔍 Collecting block production statistics between {} and {}...", from_timestamp, to_timestamp);
+    let (from_slot, to_slot) = finder.resolve_window_slots(from_timestamp, to_timestamp).await?;
+    if from_slot > to_slot {
+        return Err("❌ Error: No slots in the requested window".into());
+    }
+    
+    // getBlocks accepts at most 500,000 slots per request
+    let chunk_size: u64 = 500_000;
+    let mut produced: u64 = 0;
+    let mut first_block: Option<Slot> = None;
+    let mut last_block: Option<Slot> = None;
+    let mut longest_gap: u64 = 0;
+    let mut longest_gap_start = from_slot;
+    let mut chunk_start = from_slot;
+    while chunk_start <= to_slot {
+        let chunk_end = (chunk_start + chunk_size - 1).min(to_slot);
+        for slot in finder.get_blocks(chunk_start, chunk_end).await? {
+            // Count consecutive skipped slots between produced blocks
+            let gap = match last_block {
+                Some(previous) => slot - previous - 1,
+                None => slot - from_slot,
+            };
+            if gap > longest_gap {
+                longest_gap = gap;
+                longest_gap_start = last_block.map(|previous| previous + 1).unwrap_or(from_slot);
+            }
+            first_block.get_or_insert(slot);
+            last_block = Some(slot);
+            produced += 1;
+        }
+        chunk_start = chunk_end + 1;
+    }
+    
+    // Skipped slots after the last produced block also form a gap
+    let trailing_gap = match last_block {
+        Some(last) => to_slot - last,
+        None => to_slot - from_slot + 1,
+    };
+    if trailing_gap > longest_gap {
+        longest_gap = trailing_gap;
+        longest_gap_start = last_block.map(|last| last + 1).unwrap_or(from_slot);
+    }
+    
+    let total_slots = to_slot - from_slot + 1;
+    let skipped = total_slots - produced;
+    
+    println!("\n📊 Block production statistics:");
+    println!("📍 Slots: {} - {} ({} slots)", from_slot, to_slot, total_slots);
+    println!("🧱 Produced blocks: {}", produced);
+    println!("🕳️  Skipped slots: {} ({:.2}%)", skipped, skipped as f64 * 100.0 / total_slots as f64);
+    if longest_gap > 0 {
+        println!("📉 Longest gap: {} slots starting at slot {}", longest_gap, longest_gap_start);
+    }
+    
+    // Rates are based on the actual times of the first and last produced block in the window
+    if let (Some(first_block), Some(last_block)) = (first_block, last_block) {
+        let first_time = finder.get_block_time(first_block).await?;
+        let last_time = finder.get_block_time(last_block).await?;
+        if let (Some(first_time), Some(last_time)) = (first_time, last_time) {
+            let elapsed = last_time - first_time;
+            if elapsed > 0 {
+                let slot_span = last_block - first_block;
+                println!("⏱️  Elapsed: {} seconds", elapsed);
+                println!("🚀 Blocks per second: {:.3}", produced.saturating_sub(1) as f64 / elapsed as f64);
+                println!("⌛ Average slot time: {:.1} ms", elapsed as f64 * 1000.0 / slot_span as f64);
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+async fn run_drift(finder: &BlockFinder, options: &Options) -> Result<(), Box<dyn Error>> {
+    let from_timestamp = options.from_timestamp.ok_or("Missing required parameter --from")?;
+    let to_timestamp = options.to_timestamp.ok_or("Missing required parameter --to")?;
+    let samples = options.samples.unwrap_or(20).max(2);
+    if from_timestamp > to_timestamp {
+        return Err("❌ Error: --from must not be after --to".into());
+    }
+    
+    println!("🔍 Sampling {} blocks between {} and {} for blockTime drift...", samples, from_timestamp, to_timestamp);
+    let (from_slot, to_slot) = finder.resolve_window_slots(from_timestamp, to_timestamp).await?;
+    if from_slot >= to_slot {
+        return Err("❌ Error: The window is too small to analyze".into());
+    }
+    
+    // Sample evenly spaced slots, moving forward to the next produced block when a slot was skipped
+    let mut points: Vec<(Slot, UnixTs)> = Vec::new();
+    let step = ((to_slot - from_slot) / (samples as u64 - 1)).max(1);
+    let mut slot = from_slot;
+    while slot <= to_slot && points.len() < samples {
+        if let Some(block) = finder.get_blocks_with_limit(slot, 1).await?.first().copied() {
+            if block <= to_slot && points.last().is_none_or(|(last, _)| *last < block) {
+                if let Some(block_time) = finder.get_block_time(block).await? {
+                    points.push((block, block_time));
+                }
+            }
+        }
+        slot += step;
+        sleep(Duration::from_millis(10)).await;
+    }
+    
+    if points.len() < 2 {
+        return Err("❌ Error: Not enough blocks with timestamps in the window".into());
+    }
+    
+    // Expected time assumes every slot since the first sample took the nominal slot duration
+    let (first_slot, first_time) = points[0];
+    let drifts: Vec<f64> = points
+        .iter()
+        .map(|&(slot, block_time)| {
+            let expected = first_time.0 as f64 + (slot - first_slot) as f64 * finder.chain().slot_duration_ms as f64 / 1000.0;
+            block_time.0 as f64 - expected
+        })
+        .collect();
+    
+    if options.verbose {
+        println!("\n📋 Samples:");
+        for ((slot, block_time), drift) in points.iter().zip(&drifts) {
+            println!("    slot {} time {} drift {:+.1}s", slot, display_time(*block_time), drift);
+        }
+    }
+    
+    let mean = drifts.iter().sum::<f64>() / drifts.len() as f64;
+    let variance = drifts.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / drifts.len() as f64;
+    let min = drifts.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = drifts.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let (last_slot, last_time) = points[points.len() - 1];
+    let observed_slot_ms = (last_time - first_time) as f64 * 1000.0 / (last_slot - first_slot) as f64;
+    
+    println!("\n📊 BlockTime drift vs {} ms nominal slots ({} samples):", finder.chain().slot_duration_ms, points.len());
+    println!("📉 Min drift: {:+.1} seconds", min);
+    println!("📈 Max drift: {:+.1} seconds", max);
+    println!("➗ Mean drift: {:+.1} seconds", mean);
+    println!("📐 Std deviation: {:.1} seconds", variance.sqrt());
+    println!("⌛ Observed average slot time: {:.1} ms", observed_slot_ms);
+    
+    Ok(())
+}
+
+/// Fetches the found block and prints its summary, plus the optional rewards and raw block dump.
+/// RPC methods exercised by `bench`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BenchMethod {
+    Slot,
+    BlockTime,
+    Block,
+}
+
+impl BenchMethod {
+    fn parse(input: &str) -> Result<Self, Box<dyn Error>> {
+        match input {
+            "getSlot" => Ok(BenchMethod::Slot),
+            "getBlockTime" => Ok(BenchMethod::BlockTime),
+            "getBlock" => Ok(BenchMethod::Block),
+            _ => Err(format!("❌ Invalid bench method: '{}' (expected getSlot, getBlockTime or getBlock)", input).into()),
+        }
+    }
+    
+    fn name(self) -> &'static str {
+        match self {
+            BenchMethod::Slot => "getSlot",
+            BenchMethod::BlockTime => "getBlockTime",
+            BenchMethod::Block => "getBlock",
+        }
+    }
+}
+
+/// Parses a call mix like `getSlot=10,getBlockTime=20,getBlock=5`
+fn parse_bench_mix(input: &str) -> Result<Vec<(BenchMethod, usize)>, Box<dyn Error>> {
+    let mix = input
+        .split(',')
+        .map(|entry| {
+            let (method, count) = entry.trim().split_once('=').ok_or_else(|| format!("❌ Invalid --mix entry: '{}' (expected METHOD=N)", entry))?;
+            Ok((BenchMethod::parse(method)?, parse_count(count)?))
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    if mix.iter().all(|(_, count)| *count == 0) {
+        return Err("❌ Error: --mix does not make any calls".into());
+    }
+    Ok(mix)
+}
+
+/// Nearest-rank percentile of sorted latencies
+fn percentile(sorted_ms: &[u64], percent: usize) -> u64 {
+    if sorted_ms.is_empty() {
+        return 0;
+    }
+    let rank = (sorted_ms.len() * percent).div_ceil(100).max(1);
+    sorted_ms[rank - 1]
+}
+
+async fn run_bench(endpoints: &[String], api_key: Option<&str>, options: &Options) -> Result<(), Box<dyn Error>> {
+    let mix = parse_bench_mix(options.bench_mix.as_deref().unwrap_or(DEFAULT_BENCH_MIX))?;
+    let calls_per_endpoint: usize = mix.iter().map(|(_, count)| count).sum();
+    println!("🏁 Benchmarking {} endpoint(s), {} calls each...", endpoints.len(), calls_per_endpoint);
+    
+    let mut ranking: Vec<(String, u64)> = Vec::new();
+    for endpoint in endpoints {
+        // No retries and no cache, so every call is exactly one request and failures show up
+        let finder = network_builder(options, api_key)
+            .endpoint(endpoint)
+            .retry_policy(RetryPolicy::none())
+            .cache(Arc::new(NoCache))
+            .tip_ttl(Duration::ZERO)
+            .build()?;
+        println!("\n🌐 {}", finder.redact(endpoint));
+        
+        // Recent produced blocks, which every node (archival or not) can serve
+        let sample_slots = match finder.get_current_slot().await {
+            Ok(current_slot) => finder.get_blocks_with_limit(current_slot.saturating_sub(10_000), 500).await.unwrap_or_default(),
+            Err(e) => {
+                println!("    ❌ Unreachable: {}", finder.redact(&e.to_string()));
+                continue;
+            }
+        };
+        if sample_slots.is_empty() {
+            println!("    ❌ getBlocksWithLimit returned no blocks to sample");
+            continue;
+        }
+        
+        let mut all_latencies: Vec<u64> = Vec::new();
+        for (method, count) in &mix {
+            let mut latencies: Vec<u64> = Vec::new();
+            let mut errors = 0;
+            for n in 0..*count {
+                // Spread the calls over the sampled blocks
+                let slot = sample_slots[(n * 37) % sample_slots.len()];
+                let start = std::time::Instant::now();
+                let result = match method {
+                    BenchMethod::Slot => finder.get_current_slot().await.map(|_| ()),
+                    BenchMethod::BlockTime => finder.get_block_time(slot).await.map(|_| ()),
+                    BenchMethod::Block => finder.get_block_info(slot, false).await.map(|_| ()),
+                };
+                match result {
+                    Ok(()) => latencies.push(start.elapsed().as_millis() as u64),
+                    Err(e) => {
+                        errors += 1;
+                        if options.verbose {
+                            eprintln!("⚠️  {} failed on {}: {}", method.name(), finder.redact(endpoint), finder.redact(&e.to_string()));
+                        }
+                    }
+                }
+            }
+            if *count == 0 {
+                continue;
+            }
+            
+            latencies.sort_unstable();
+            println!(
+                "    {:<14} calls: {:>4}  errors: {:>3} ({:>5.1}%)  p50: {:>5} ms  p95: {:>5} ms",
+                method.name(),
+                count,
+                errors,
+                errors as f64 * 100.0 / *count as f64,
+                percentile(&latencies, 50),
+                percentile(&latencies, 95),
+            );
+            all_latencies.extend(latencies);
+        }
+        
+        if !all_latencies.is_empty() {
+            all_latencies.sort_unstable();
+            ranking.push((finder.redact(endpoint), percentile(&all_latencies, 50)));
+        }
+    }
+    
+    if ranking.len() > 1 {
+        ranking.sort_by_key(|(_, p50)| *p50);
+        println!("\n🏆 Ranking by overall p50 latency:");
+        for (position, (endpoint, p50)) in ranking.iter().enumerate() {
+            println!("    {}. {} ({} ms)", position + 1, endpoint, p50);
+        }
+    }
+    
+    Ok(())
+}
+
+async fn run_selftest(endpoints: &[String], api_key: Option<&str>, options: &Options) -> Result<(), Box<dyn Error>> {
+    if options.chain_profile.name != ChainProfile::solana().name {
+        return Err(format!("❌ selftest checks Solana mainnet blocks and cannot run against chain '{}'", options.chain_profile.name).into());
+    }
+    println!("🧪 Running the search against {} mainnet reference vectors...", REFERENCE_VECTORS.len());
+    
+    let mut passed = 0;
+    let mut total_calls = 0;
+    let start_time = std::time::Instant::now();
+    for vector in REFERENCE_VECTORS {
+        // A fresh finder per vector, so no vector benefits from another's cached block times
+        let finder = network_builder(options, api_key).endpoints(endpoints.iter().cloned()).build()?;
+        let search_start = std::time::Instant::now();
+        let outcome = check_reference_vector(&finder, vector).await;
+        let calls = finder.rpc_calls();
+        total_calls += calls;
+        
+        match outcome {
+            Ok(slot) => {
+                passed += 1;
+                println!("✅ {} → slot {} ({} RPC calls, {:.2}s)", vector.timestamp, slot, calls, search_start.elapsed().as_secs_f64());
+            }
+            Err(e) => println!("❌ {}: {} ({} RPC calls)", vector.timestamp, finder.redact(&e.to_string()), calls),
+        }
+    }
+    
+    println!();
+    println!("📊 Accuracy: {}/{} vectors passed", passed, REFERENCE_VECTORS.len());
+    println!("🔢 RPC calls: {} total, {:.1} per search", total_calls, total_calls as f64 / REFERENCE_VECTORS.len() as f64);
+    println!("⚡ Completed in {:.2} seconds", start_time.elapsed().as_secs_f64());
+    
+    if passed < REFERENCE_VECTORS.len() {
+        return Err(format!("❌ {} of {} reference vectors failed", REFERENCE_VECTORS.len() - passed, REFERENCE_VECTORS.len()).into());
+    }
+    Ok(())
+}
+
+/// Searches one vector's timestamp and checks the answer, returning the slot found
+async fn check_reference_vector(finder: &BlockFinder, vector: &ReferenceVector) -> Result<Slot, Box<dyn Error>> {
+    let block = finder.find_block_by_timestamp(vector.timestamp).await?;
+    match vector.blockhash {
+        Some(blockhash) => {
+            if block.slot != vector.slot {
+                return Err(format!("expected slot {}, got {}", vector.slot, block.slot).into());
+            }
+            if block.blockhash != blockhash {
+                return Err(format!("expected blockhash {}, got {}", blockhash, block.blockhash).into());
+            }
+        }
+        None => {
+            if block.slot < vector.slot {
+                return Err(format!("got slot {}, but slot {} already has this block time", block.slot, vector.slot).into());
+            }
+            if block.block_time != Some(vector.timestamp) {
+                let block_time = block.block_time.map(|t| t.to_string()).unwrap_or_else(|| "none".to_string());
+                return Err(format!("slot {} has block time {}, expected {}", block.slot, block_time, vector.timestamp).into());
+            }
+        }
+    }
+    Ok(block.slot)
+}
+
+async fn run_health(endpoints: &[String], api_key: Option<&str>, options: &Options) -> Result<(), Box<dyn Error>> {
+    println!("🩺 Checking {} endpoint(s)...", endpoints.len());
+    
+    let mut unhealthy = 0;
+    for endpoint in endpoints {
+        // Report each endpoint as it is, without retries or failover hiding problems
+        let finder = network_builder(options, api_key)
+            .endpoint(endpoint)
+            .retry_policy(RetryPolicy::none())
+            .cache(Arc::new(NoCache))
+            .tip_ttl(Duration::ZERO)
+            .build()?;
+        println!("\n🌐 {}", finder.redact(endpoint));
+        if !check_endpoint_health(&finder, options.cluster).await {
+            unhealthy += 1;
+        }
+    }
+    
+    println!();
+    if unhealthy > 0 {
+        return Err(format!("❌ {} of {} endpoint(s) failed the health check", unhealthy, endpoints.len()).into());
+    }
+    println!("✅ All endpoints are healthy");
+    Ok(())
+}
+
+/// Prints one line per check and returns whether the endpoint passed all of them
+async fn check_endpoint_health(finder: &BlockFinder, expected_cluster: Option<Cluster>) -> bool {
+    let start = std::time::Instant::now();
+    match finder.get_version().await {
+        Ok(version) => {
+            println!("    ✅ Reachable ({} ms)", start.elapsed().as_millis());
+            println!("    ✅ Version: solana-core {}", version.solana_core);
+        }
+        Err(e) => {
+            println!("    ❌ Unreachable: {}", finder.redact(&e.to_string()));
+            return false;
         }
     }
     
-    // Check if timestamp was provided
-    let target_timestamp = match target_timestamp {
-        Some(ts) => ts,
-        None => {
-            eprintln!("❌ Error: Missing required parameter --timestamp");
-            eprintln!("");
-            print_usage();
-            return Ok(());
+    // getVersion needs no authentication on most providers, getSlot does
+    let current_slot = match finder.get_current_slot().await {
+        Ok(slot) => {
+            println!("    ✅ Current slot: {}", slot);
+            slot
+        }
+        Err(e) => {
+            println!("    ❌ getSlot failed (check the API key): {}", finder.redact(&e.to_string()));
+            return false;
         }
     };
     
-    // Get API key from parameter or environment
-    let api_key = match api_key {
-        Some(key) => key,
-        None => {
-            match env::var("HELIUS_API_KEY") {
-                Ok(key) => key,
-                Err(_) => {
-                    eprintln!("❌ Error: No API key provided!");
-                    eprintln!("");
-                    eprintln!("Please provide an API key by either:");
-                    eprintln!("  1. Setting the HELIUS_API_KEY environment variable:");
-                    eprintln!("     export HELIUS_API_KEY=your-api-key-here");
-                    eprintln!("");
-                    eprintln!("  2. Or using the --api-key parameter:");
-                    eprintln!("     {} --timestamp <timestamp> --api-key <your-key>", env::args().next().unwrap_or_else(|| "solana-block-finder".to_string()));
-                    eprintln!("");
-                    eprintln!("You can get a free API key from: https://helius.xyz");
-                    return Err("Missing API key".into());
-                }
+    let mut healthy = true;
+    match (finder.detect_cluster().await, expected_cluster) {
+        (Ok((_, Some(cluster))), Some(expected)) if cluster != expected => {
+            println!("    ❌ Cluster: {}, expected {}", cluster.as_str(), expected.as_str());
+            healthy = false;
+        }
+        (Ok((_, Some(cluster))), _) => println!("    ✅ Cluster: {}", cluster.as_str()),
+        (Ok((hash, None)), Some(expected)) => {
+            println!("    ❌ Cluster: unknown (genesis hash {}), expected {}", hash, expected.as_str());
+            healthy = false;
+        }
+        (Ok((hash, None)), None) => println!("    ℹ️  Cluster: unknown (genesis hash {})", hash),
+        (Err(e), _) => {
+            println!("    ❌ getGenesisHash failed: {}", finder.redact(&e.to_string()));
+            healthy = false;
+        }
+    }
+    
+    match finder.get_first_available_block().await {
+        Ok(Slot(0)) => println!("    ✅ First available block: 0 (full history)"),
+        Ok(first_block) => {
+            let since = match finder.get_block_time(first_block).await {
+                Ok(Some(block_time)) => format!(" at {}", block_time),
+                _ => String::new(),
+            };
+            println!("    ⚠️  First available block: {}{} (not an archival node, older timestamps cannot be searched)", first_block, since);
+        }
+        Err(e) => {
+            println!("    ❌ getFirstAvailableBlock failed: {}", finder.redact(&e.to_string()));
+            healthy = false;
+        }
+    }
+    
+    // A recent produced block must have a block time, or no search can work
+    let recent_block = finder.get_blocks_with_limit(current_slot.saturating_sub(1_000), 1).await.ok().and_then(|blocks| blocks.first().copied());
+    match recent_block {
+        Some(slot) => match finder.get_block_time(slot).await {
+            Ok(Some(block_time)) => println!("    ✅ getBlockTime works (slot {} → {})", slot, display_time(block_time)),
+            Ok(None) => {
+                println!("    ❌ getBlockTime returned no time for produced slot {}", slot);
+                healthy = false;
+            }
+            Err(e) => {
+                println!("    ❌ getBlockTime failed: {}", finder.redact(&e.to_string()));
+                healthy = false;
             }
+        },
+        None => {
+            println!("    ❌ getBlocksWithLimit found no recent block to check getBlockTime with");
+            healthy = false;
         }
-    };
+    }
     
-    // Current time check
-    let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
-    if target_timestamp > current_time {
-        return Err("❌ Error: Timestamp is in the future".into());
+    healthy
+}
+
+async fn print_found_block(
+    finder: &BlockFinder,
+    slot: Slot,
+    target_timestamp: Option<UnixTs>,
+    options: &Options,
+) -> Result<(), Box<dyn Error>> {
+    // Get block info for the found slot
+    // The fee reward names the leader
+    let block_info = finder.get_block_info(slot, options.show_rewards || options.leader_details).await?;
+    let finalized = finder.is_finalized(slot).await?;
+    let block_match = BlockMatch { finalized: Some(finalized), ..BlockMatch::new(slot, &block_info, target_timestamp) };
+    
+    print_block_match(&block_match);
+    if options.verbose {
+        println!("👪 Parent slot: {}", block_info.parent_slot);
     }
     
-    // Initialize HTTP client with connection pooling and optimized settings
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
-        .connect_timeout(Duration::from_secs(5))
-        .pool_max_idle_per_host(20)
-        .pool_idle_timeout(Duration::from_secs(30))
-        .tcp_keepalive(Duration::from_secs(60))
-        .build()?;
-    let rpc_url = "https://mainnet.helius-rpc.com";
+    // blockTime is a stake-weighted estimate, so show how tightly the neighbors bracket it
+    if let Some(block_time) = block_match.block_time {
+        let uncertainty = finder.get_timestamp_uncertainty(slot, block_time).await?;
+        let previous = uncertainty.previous
+            .map(|(s, t)| format!("{} (slot {})", display_time(t), s))
+            .unwrap_or_else(|| "unknown".to_string());
+        let next = uncertainty.next
+            .map(|(s, t)| format!("{} (slot {})", display_time(t), s))
+            .unwrap_or_else(|| "not produced yet".to_string());
+        println!("📐 Uncertainty window: previous block time {}, next block time {}", previous, next);
+        println!("👥 Blocks sharing this timestamp: {}", uncertainty.same_timestamp_blocks);
+    }
     
-    if verbose {
-        println!("🔍 Searching for block with timestamp {} or right before it...", target_timestamp);
-        println!("📊 Using RPC endpoint: {}", rpc_url);
+    if options.leader_details {
+        print_leader_details(finder, slot, &block_info.rewards).await?;
+    }
+    
+    if options.show_rewards {
+        print_rewards(&block_info.rewards);
+    }
+    
+    // Fetched once for every option that reads the transactions
+    let reads_transactions = options.show_fees || options.show_total_fees || options.show_compute_units || options.program.is_some();
+    let full_block = if reads_transactions || options.full_block_output.is_some() {
+        Some(finder.get_full_block(slot).await?)
     } else {
-        println!("🔍 Searching for block with timestamp {} or right before it...", target_timestamp);
+        None
+    };
+    
+    if let Some(block) = full_block.as_ref().filter(|_| reads_transactions) {
+        // Votes are most of a block, so a raw count says little about its activity
+        let counts = block_stats::transaction_counts(block);
+        println!("\n🧾 Transactions: {} ({} votes, {} non-vote)", counts.total(), counts.votes, counts.non_votes);
+    }
+    if let Some(block) = full_block.as_ref().filter(|_| options.show_fees) {
+        print_priority_fees(block);
+    }
+    if let Some(block) = full_block.as_ref().filter(|_| options.show_total_fees) {
+        let fees = block_stats::total_fees(block);
+        println!("\n🏦 Fees collected: {:.9} SOL ({} lamports)", fees.total as f64 / LAMPORTS_PER_SOL, fees.total);
+        println!("    Base fees: {} lamports, priority fees: {} lamports", fees.base, fees.priority);
+    }
+    if let Some(block) = full_block.as_ref().filter(|_| options.show_compute_units) {
+        print_compute_units(block);
+    }
+    if let (Some(program_id), Some(block)) = (&options.program, &full_block) {
+        print_program_transactions(block, program_id);
     }
     
-    // Use the optimized search function
-    let start_time = std::time::Instant::now();
-    let slot = get_slot_by_timestamp_optimized(&client, rpc_url, &api_key, target_timestamp).await?;
-    let search_duration = start_time.elapsed();
+    if let (Some(output), Some(block)) = (&options.full_block_output, &full_block) {
+        let raw = serde_json::to_string_pretty(block)?;
+        if output == "-" {
+            println!("\n📦 Full block:");
+            std::println!("{}", raw);
+        } else {
+            std::fs::write(output, raw)?;
+            println!("\n📦 Full block written to {}", output);
+        }
+    }
     
-    // Get block info for the found slot
-    let block_info = get_block_info(&client, rpc_url, &api_key, slot).await?;
+    if let Some(target) = options.copy {
+        let text = target.text(slot);
+        match copy_to_clipboard(&text) {
+            Ok(()) => println!("📋 Copied {} to the clipboard", text),
+            Err(e) => eprintln!("⚠️  Could not copy to the clipboard: {}", e),
+        }
+    }
+    
+    Ok(())
+}
+
+/// `key store`: reads the API key from stdin and saves it in the OS keyring
+fn run_key_store() -> Result<(), Box<dyn Error>> {
+    use std::io::BufRead;
     
+    if std::io::stdin().is_terminal() {
+        eprint!("🔑 Helius API key: ");
+    }
+    let mut key = String::new();
+    std::io::stdin().lock().read_line(&mut key)?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("❌ Error: No API key given on stdin".into());
+    }
+    
+    store_keyring_key(key)?;
+    println!("✅ API key saved to the OS keyring. It is used whenever --api-key and HELIUS_API_KEY are not set.");
+    Ok(())
+}
+
+/// `key delete`: removes the API key from the OS keyring
+fn run_key_delete() -> Result<(), Box<dyn Error>> {
+    if delete_keyring_key()? {
+        println!("🗑️  API key removed from the OS keyring.");
+    } else {
+        println!("ℹ️  No API key stored in the OS keyring.");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_entry() -> Result<keyring::Entry, Box<dyn Error>> {
+    Ok(keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)?)
+}
+
+/// The API key saved with `key store`, if any
+#[cfg(feature = "keyring")]
+fn load_keyring_key() -> Result<Option<String>, Box<dyn Error>> {
+    match keyring_entry()?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn store_keyring_key(key: &str) -> Result<(), Box<dyn Error>> {
+    keyring_entry()?.set_password(key).map_err(|e| format!("❌ Could not save the API key to the keyring: {}", e).into())
+}
+
+/// Returns false when there was no key to delete
+#[cfg(feature = "keyring")]
+fn delete_keyring_key() -> Result<bool, Box<dyn Error>> {
+    match keyring_entry()?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(format!("❌ Could not delete the API key from the keyring: {}", e).into()),
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+fn load_keyring_key() -> Result<Option<String>, Box<dyn Error>> {
+    Ok(None)
+}
+
+#[cfg(not(feature = "keyring"))]
+fn store_keyring_key(_key: &str) -> Result<(), Box<dyn Error>> {
+    Err("❌ Error: built without the keyring feature (cargo build --features keyring)".into())
+}
+
+#[cfg(not(feature = "keyring"))]
+fn delete_keyring_key() -> Result<bool, Box<dyn Error>> {
+    Err("❌ Error: built without the keyring feature (cargo build --features keyring)".into())
+}
+
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(text: &str) -> Result<(), Box<dyn Error>> {
+    arboard::Clipboard::new()?.set_text(text)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_text: &str) -> Result<(), Box<dyn Error>> {
+    Err("built without the clipboard feature (cargo build --features clipboard)".into())
+}
+
+fn print_block_match(block_match: &BlockMatch) {
     println!("\n✅ Found block:");
-    println!("📍 Slot: {}", slot);
-    println!("🔗 Block hash: {}", block_info.blockhash);
-    println!("⏰ Block time: {}", block_info.blockTime.unwrap_or_default());
-    if let Some(height) = block_info.blockHeight {
+    println!("📍 Slot: {}", block_match.slot);
+    println!("🔗 Block hash: {}", block_match.blockhash);
+    println!("⏰ Block time: {}", display_time(block_match.block_time.unwrap_or_default()));
+    if let Some(height) = block_match.block_height {
         println!("📏 Block height: {}", height);
     }
     
+    if block_match.approximate {
+        println!("⚠️  Approximate: a limit stopped the search, this is the closest block found before the target.");
+    }
+    if block_match.finalized == Some(false) {
+        println!("⚠️  Not finalized: the block is only confirmed and could still be dropped.");
+    }
+    
     // Calculate time difference
-    if let Some(block_time) = block_info.blockTime {
-        let time_diff = block_time - target_timestamp;
-        if time_diff == 0 {
+    if let Some(time_diff) = block_match.time_diff {
+        if block_match.exact {
             println!("🎯 This block exactly matches the requested timestamp.");
         } else if time_diff < 0 {
             println!("⏪ This block is {} seconds before the requested timestamp.", time_diff.abs());
@@ -492,14 +3733,289 @@ async fn main() -> Result<(), Box<dyn Error>> {
             println!("⚠️  Warning: Found a block after the requested timestamp, which shouldn't happen.");
         }
     }
+}
+
+fn print_rewards(rewards: &[Reward]) {
+    println!("\n💰 Rewards:");
+    if rewards.is_empty() {
+        println!("    No rewards recorded for this block.");
+        return;
+    }
     
-    if verbose {
-        println!("\n⚡ Performance: Search completed in {:.2} seconds", search_duration.as_secs_f64());
-        println!("🌐 Block Explorer: https://explorer.solana.com/block/{}", slot);
+    for reward in rewards {
+        let reward_type = reward.reward_type.as_deref().unwrap_or("Unknown");
+        let mut line = format!(
+            "    {:<8} {} {:+.9} SOL (post balance: {:.9} SOL)",
+            reward_type,
+            reward.pubkey,
+            reward.lamports as f64 / LAMPORTS_PER_SOL,
+            reward.post_balance as f64 / LAMPORTS_PER_SOL,
+        );
+        if let Some(commission) = reward.commission {
+            line.push_str(&format!(" commission: {}%", commission));
+        }
+        println!("{}", line);
+    }
+    
+    // Leader fees are the part validator operators usually care about
+    let leader_fees: i64 = rewards
+        .iter()
+        .filter(|r| r.reward_type.as_deref() == Some("Fee"))
+        .map(|r| r.lamports)
+        .sum();
+    println!("    Leader fees: {:.9} SOL ({} lamports)", leader_fees as f64 / LAMPORTS_PER_SOL, leader_fees);
+}
+
+fn print_priority_fees(block: &Value) {
+    println!("\n💸 Priority fees of non-vote transactions:");
+    let Some(fees) = block_stats::priority_fees(block) else {
+        println!("    No transactions besides votes in this block.");
+        return;
+    };
+    println!("    Min: {} lamports", fees.min);
+    println!("    Median: {} lamports", fees.median);
+    println!("    Max: {} lamports", fees.max);
+    println!("    Total: {:.9} SOL ({} lamports)", fees.total as f64 / LAMPORTS_PER_SOL, fees.total);
+}
+
+fn print_compute_units(block: &Value) {
+    println!("\n⚙️  Compute units:");
+    let Some(units) = block_stats::compute_units(block) else {
+        println!("    Not recorded for this block.");
+        return;
+    };
+    println!("    Total: {} CU over {} transactions", units.total, units.transactions);
+    println!("    Per transaction: min {}, median {}, max {} CU", units.min, units.median, units.max);
+}
+
+fn print_program_transactions(block: &Value, program_id: &str) {
+    let transactions = block_stats::transactions_invoking(block, program_id);
+    println!("\n🧩 Transactions invoking {}: {}", program_id, transactions.len());
+    for transaction in transactions {
+        match &transaction.err {
+            None => println!("    ✅ {} fee payer {}", transaction.signature, transaction.fee_payer),
+            Some(err) => {
+                print!("    ❌ ");
+                std::println!("{} fee payer {} error: {}", transaction.signature, transaction.fee_payer, err);
+            }
+        }
+    }
+}
+
+/// Prints the leader of `slot` with its vote account and activated stake. `getVoteAccounts` only
+/// knows the current epoch, so the stake is today's, not the one the leader had at the time.
+async fn print_leader_details(finder: &BlockFinder, slot: Slot, rewards: &[Reward]) -> Result<(), Box<dyn Error>> {
+    println!("\n👑 Leader:");
+    // The leader collects the block's fees, and the leader schedule knows it for recent epochs
+    let leader = match rewards.iter().find(|r| r.reward_type.as_deref() == Some("Fee")) {
+        Some(reward) => Some(reward.pubkey.clone()),
+        None => finder.get_slot_leaders(slot, 1).await.ok().and_then(|leaders| leaders.into_iter().next()),
+    };
+    let Some(leader) = leader else {
+        println!("    Unknown: the block pays no fees and the node has no leader schedule for its epoch.");
+        return Ok(());
+    };
+    println!("    Identity: {}", leader);
+    
+    let accounts = finder.get_vote_accounts().await?;
+    let total_stake: u64 = accounts.current.iter().chain(&accounts.delinquent).map(|a| a.activated_stake).sum();
+    let Some(account) = accounts.current.iter().chain(&accounts.delinquent)
+        .filter(|a| a.node_pubkey == leader)
+        .max_by_key(|a| a.activated_stake)
+    else {
+        println!("    No vote account: the validator does not vote anymore.");
+        return Ok(());
+    };
+    println!("    Vote account: {}", account.vote_pubkey);
+    println!(
+        "    Activated stake: {:.9} SOL ({:.2}% of the cluster, current epoch)",
+        account.activated_stake as f64 / LAMPORTS_PER_SOL,
+        account.activated_stake as f64 * 100.0 / total_stake.max(1) as f64,
+    );
+    println!("    Commission: {}%", account.commission);
+    if accounts.delinquent.iter().any(|a| a.vote_pubkey == account.vote_pubkey) {
+        println!("    ⚠️  Delinquent: its last vote was for slot {}.", account.last_vote);
+    }
+    Ok(())
+}
+
+/// A command in the COMMANDS section of `--help` and the man page
+struct CommandDoc {
+    name: &'static str,
+    argument: Option<&'static str>,
+    help: &'static str,
+}
+
+/// An option in the OPTIONS section of `--help` and the man page
+struct OptionDoc {
+    short: Option<char>,
+    long: &'static str,
+    value: Option<&'static str>,
+    help: &'static str,
+}
+
+const COMMAND_DOCS: &[CommandDoc] = &[
+    CommandDoc { name: "find-tx", argument: Some("<SIGNATURE>"), help: "Find the block containing a transaction" },
+    CommandDoc { name: "activity", argument: Some("<ADDRESS>"), help: "List an address's transactions between --from and --to" },
+    CommandDoc { name: "created-at", argument: Some("<ADDRESS>"), help: "Show the slot and time of an account's earliest (creating) transaction" },
+    CommandDoc { name: "mint-created", argument: Some("<MINT>"), help: "Find the block and time a token mint was created" },
+    CommandDoc { name: "deployments", argument: Some("<PROGRAM_ID>"), help: "List the slots and times a program was deployed and upgraded" },
+    CommandDoc { name: "first-call", argument: Some("<PROGRAM_ID>"), help: "Find the first transaction calling a program after --from (until --to, default: now)" },
+    CommandDoc { name: "feature", argument: Some("<FEATURE_ID>"), help: "Show the slot, epoch and time a feature gate activated" },
+    CommandDoc { name: "last-activity", argument: Some("<ADDRESS>"), help: "Show an address's most recent transaction at or before --timestamp" },
+    CommandDoc { name: "epoch-at", argument: Some("<TIMESTAMP>"), help: "Show the epoch (and its boundaries) at a timestamp" },
+    CommandDoc { name: "epoch", argument: Some("<N>"), help: "Show the first/last slot and start/end time of epoch N" },
+    CommandDoc { name: "countdown", argument: None, help: "Estimate the slot of a future --timestamp until it is reached (updates every --every, default 10s)" },
+    CommandDoc { name: "eta", argument: Some("<SLOT>"), help: "Estimate when a future slot will be produced, with a range" },
+    CommandDoc { name: "offset", argument: None, help: "Find the block about --by (e.g. 2h30m, -1h) after/before --slot" },
+    CommandDoc { name: "between", argument: Some("<SLOT> <SLOT>"), help: "Wall-clock time, slot delta and average slot time between two slots" },
+    CommandDoc { name: "dump-epoch", argument: Some("<N>"), help: "Write every produced slot of epoch N with its block time (csv, json, yaml, arrow, parquet)" },
+    CommandDoc { name: "schedule", argument: None, help: "Resolve a series of timestamps (--every, --from, --to)" },
+    CommandDoc { name: "batch", argument: Some("<FILE>"), help: "Resolve the timestamps in FILE (one per line, '-' for stdin) concurrently" },
+    CommandDoc { name: "stats", argument: None, help: "Block production statistics between --from and --to" },
+    CommandDoc { name: "drift", argument: None, help: "Drift of blockTime vs slot-derived time between --from and --to" },
+    CommandDoc { name: "selftest", argument: None, help: "Run the search against embedded mainnet reference vectors" },
+    CommandDoc { name: "health", argument: None, help: "Check that each --endpoint is reachable, current and usable for searches" },
+    CommandDoc { name: "bench", argument: None, help: "Measure latency and error rate of each --endpoint" },
+    CommandDoc { name: "index", argument: Some("<crawl|serve|export|import|compact>"), help: "Sample block times going back from the tip into --index every --stride slots, resuming where it stopped; serve --index over HTTP; export/import it as a portable file; or thin it out" },
+    CommandDoc { name: "calibration", argument: Some("<refresh|show>"), help: "Measure the slot timing per era of the chain's history from --samples blocks (default: 64) into --calibration, or print the table in use" },
+    CommandDoc { name: "calibrate", argument: None, help: "Measure the average slot time and its variance over the last --window (default: 1h) and save it into --calibration for estimates" },
+    CommandDoc { name: "key", argument: Some("<store|delete>"), help: "Save the API key read from stdin to the OS keyring, or remove it" },
+];
+
+const OPTION_DOCS: &[OptionDoc] = &[
+    OptionDoc { short: Some('k'), long: "api-key", value: Some("<API_KEY>"), help: "Helius API key (or set HELIUS_API_KEY, or use key store)" },
+    OptionDoc { short: Some('e'), long: "endpoint", value: Some("<URL>"), help: "RPC endpoint, repeatable (default: https://mainnet.helius-rpc.com)" },
+    OptionDoc { short: Some('v'), long: "verbose", value: None, help: "Show detailed output including performance metrics" },
+    OptionDoc { short: None, long: "rewards", value: None, help: "Show rewards (leader fees, rent, ...) paid in the found block" },
+    OptionDoc { short: None, long: "leader-details", value: None, help: "Show the found block's leader with its vote account and stake" },
+    OptionDoc { short: None, long: "fees", value: None, help: "Show min/median/max priority fees paid in the found block" },
+    OptionDoc { short: None, long: "total-fees", value: None, help: "Show the total fees paid by the found block's transactions" },
+    OptionDoc { short: None, long: "compute-units", value: None, help: "Show the compute units consumed in the found block, in total and per transaction" },
+    OptionDoc { short: None, long: "program", value: Some("<PUBKEY>"), help: "List the found block's transactions invoking this program" },
+    OptionDoc { short: None, long: "full-block", value: Some("<FILE>"), help: "Write the full block JSON (with transactions) to FILE, or '-' for stdout" },
+    OptionDoc { short: None, long: "copy", value: Some("<slot|url>"), help: "Copy the found slot or its explorer URL to the clipboard (needs the clipboard feature)" },
+    OptionDoc { short: None, long: "from", value: Some("<TIMESTAMP>"), help: "Start of a time window (Unix or ISO 8601)" },
+    OptionDoc { short: None, long: "to", value: Some("<TIMESTAMP>"), help: "End of a time window (Unix or ISO 8601)" },
+    OptionDoc { short: None, long: "slot", value: Some("<SLOT>"), help: "Reference slot of offset" },
+    OptionDoc { short: None, long: "by", value: Some("<DURATION>"), help: "Offset from --slot, negative for earlier (e.g. 2h30m, -1h)" },
+    OptionDoc { short: None, long: "every", value: Some("<DURATION>"), help: "Interval between schedule targets or countdown updates (e.g. 1h, 1d, 2h30m)" },
+    OptionDoc { short: None, long: "checkpoint", value: Some("<FILE>"), help: "Save search progress to FILE after every probe" },
+    OptionDoc { short: None, long: "resume", value: None, help: "Continue the search saved in --checkpoint" },
+    OptionDoc { short: None, long: "estimate-future", value: None, help: "Project the slot of future timestamps from the recent slot rate instead of failing" },
+    OptionDoc { short: None, long: "record", value: Some("<FILE>"), help: "Record every RPC request and response to the cassette FILE" },
+    OptionDoc { short: None, long: "replay", value: Some("<FILE>"), help: "Answer RPC requests from the cassette FILE instead of the network" },
+    OptionDoc { short: None, long: "stdin", value: None, help: "Resolve each timestamp read from stdin, printing one slot (or --output json line) each" },
+    OptionDoc { short: None, long: "deadline", value: Some("<DURATION>"), help: "Stop each search after this long, e.g. 30s or 2m" },
+    OptionDoc { short: None, long: "max-iterations", value: Some("<N>"), help: "Give up after N binary search steps (default: 200)" },
+    OptionDoc { short: None, long: "max-rpc-calls", value: Some("<N>"), help: "Stop searching after N RPC calls in total" },
+    OptionDoc { short: None, long: "diagnostics", value: None, help: "Add probes, cache hits, retries and the final window of each search to JSON/YAML output" },
+    OptionDoc { short: None, long: "best-effort", value: None, help: "When a limit stops a search, report the closest block found so far as approximate" },
+    OptionDoc { short: None, long: "lowest", value: None, help: "Return the first block sharing the found block time instead of the last" },
+    OptionDoc { short: None, long: "all-matching", value: None, help: "List every block sharing the found block time (slot and blockhash)" },
+    OptionDoc { short: None, long: "samples", value: Some("<N>"), help: "Number of blocks sampled by drift (default: 20) or calibration refresh (default: 64)" },
+    OptionDoc { short: None, long: "batch-concurrency", value: Some("<N>"), help: "Searches batch runs at once (default: 4)" },
+    OptionDoc { short: None, long: "mix", value: Some("<METHOD=N,...>"), help: "Calls made by bench per endpoint (default: getSlot=10,getBlockTime=10,getBlock=5)" },
+    OptionDoc { short: None, long: "proxy", value: Some("<URL>"), help: "Send RPC requests through this proxy (default: HTTPS_PROXY/HTTP_PROXY)" },
+    OptionDoc { short: None, long: "resolve", value: Some("<HOST:IP>"), help: "Connect to IP for HOST instead of resolving it (repeatable)" },
+    OptionDoc { short: None, long: "user-agent", value: Some("<VALUE>"), help: "User-Agent sent to the RPC (default: solana-block-finder/<version>)" },
+    OptionDoc { short: None, long: "timeout", value: Some("<DURATION>"), help: "Time limit per RPC request (default: 10s)" },
+    OptionDoc { short: None, long: "connect-timeout", value: Some("<DURATION>"), help: "Time limit for connecting to the RPC (default: 5s)" },
+    OptionDoc { short: None, long: "pool-max-idle", value: Some("<N>"), help: "Idle connections kept per host, 0 disables reuse (default: 20)" },
+    OptionDoc { short: None, long: "pool-idle-timeout", value: Some("<DURATION>"), help: "Close idle connections after this long (default: 30s)" },
+    OptionDoc { short: None, long: "tcp-keepalive", value: Some("<DURATION>"), help: "TCP keepalive interval (default: 60s)" },
+    OptionDoc { short: None, long: "probe-window", value: Some("<N>"), help: "Initial slots checked around a skipped slot (default: 20)" },
+    OptionDoc { short: None, long: "probe-delay", value: Some("<MS>"), help: "Pause between sequential probes in ms (default: 10, scan: 5)" },
+    OptionDoc { short: None, long: "otlp-endpoint", value: Some("<URL>"), help: "Export traces to this OTLP gRPC collector (needs the otlp feature)" },
+    OptionDoc { short: None, long: "chain", value: Some("<NAME>"), help: "SVM chain from the config file's [chains.<NAME>] tables (default: solana)" },
+    OptionDoc { short: None, long: "commitment", value: Some("<finalized|confirmed>"), help: "Commitment of the searched blocks (default: finalized); confirmed reaches newer blocks" },
+    OptionDoc { short: None, long: "cluster", value: Some("<mainnet|devnet|testnet>"), help: "Warn if the endpoints' genesis hash belongs to another cluster" },
+    OptionDoc { short: None, long: "strict", value: None, help: "Fail instead of warning when the endpoints serve another cluster than --cluster" },
+    OptionDoc { short: None, long: "ledger", value: Some("<DIR>"), help: "Search a validator's local ledger instead of RPC (needs the ledger feature)" },
+    OptionDoc { short: None, long: "index", value: Some("<FILE|URL>"), help: "Index of known block times that narrows searches, built by index crawl, or the URL of an index serve" },
+    OptionDoc { short: None, long: "calibration", value: Some("<FILE>"), help: "Era table of slot timing used by searches and estimates (default: calibration/<chain>.toml next to the config file)" },
+    OptionDoc { short: None, long: "resolution", value: Some("<DURATION>"), help: "Minimum time between the entries kept by index compact, e.g. 10m" },
+    OptionDoc { short: None, long: "window", value: Some("<DURATION>"), help: "Recent history calibrate measures, e.g. 6h (default: 1h)" },
+    OptionDoc { short: None, long: "horizon", value: Some("<DURATION>"), help: "Age beyond which index compact drops entries, e.g. 365d" },
+    OptionDoc { short: None, long: "listen", value: Some("<IP:PORT>"), help: "Address of index serve (default: 127.0.0.1:8900)" },
+    OptionDoc { short: None, long: "stride", value: Some("<SLOTS>"), help: "Slots between the samples of index crawl (default: 1000)" },
+    OptionDoc { short: None, long: "genesis", value: Some("<FILE>"), help: "TOML with genesis_timestamp, slot_duration_ms and first_available_slot of a private cluster" },
+    OptionDoc { short: None, long: "config", value: Some("<FILE>"), help: "Config file (default: ~/.config/solana-block-finder/config.toml)" },
+    OptionDoc { short: Some('o'), long: "output", value: Some("<FORMAT>"), help: "Output format for series: text, csv, json, yaml, arrow or parquet (default: text)" },
+    OptionDoc { short: None, long: "output-file", value: Some("<FILE>"), help: "File written by --output arrow/parquet (needs the parquet feature)" },
+    OptionDoc { short: None, long: "format", value: Some("<TEMPLATE>"), help: "Print each result as this line, e.g. '{slot},{blockhash},{block_time_iso}'" },
+    OptionDoc { short: None, long: "time-format", value: Some("<TEMPLATE>"), help: "Show block times in UTC with this strftime template (default: Unix seconds)" },
+    OptionDoc { short: None, long: "no-emoji", value: None, help: "Plain ASCII labels instead of emoji (default when not a terminal or TERM=dumb)" },
+    OptionDoc { short: Some('h'), long: "help", value: None, help: "Show this help message" },
+];
+
+// Width of the flag column in --help
+const HELP_COLUMN: usize = 31;
+
+/// Pads `left` to the help column, or leaves two spaces when it does not fit
+fn help_columns(left: String, help: &str) -> String {
+    if left.len() + 2 <= HELP_COLUMN {
+        format!("{:<width$}{}", left, help, width = HELP_COLUMN)
     } else {
-        println!("\n⚡ Search completed in {:.2} seconds", search_duration.as_secs_f64());
+        format!("{}  {}", left, help)
+    }
+}
+
+impl CommandDoc {
+    fn help_line(&self) -> String {
+        let usage = match self.argument {
+            Some(argument) => format!("{} {}", self.name, argument),
+            None => self.name.to_string(),
+        };
+        help_columns(usage, self.help)
+    }
+}
+
+impl OptionDoc {
+    fn help_line(&self) -> String {
+        let short = match self.short {
+            Some(short) => format!("-{}, ", short),
+            None => "    ".to_string(),
+        };
+        let value = self.value.map(|value| format!(" {}", value)).unwrap_or_default();
+        help_columns(format!("{}--{}{}", short, self.long, value), self.help)
+    }
+}
+
+/// Writes a roff man page describing the CLI to stdout (`mangen`, not listed in --help)
+fn print_man_page() -> Result<(), Box<dyn Error>> {
+    let timestamp = Arg::new("timestamp")
+        .short('t')
+        .long("timestamp")
+        .value_name("TIMESTAMP")
+        .help("Unix timestamp in seconds (e.g., 1750921805) or ISO 8601 format (e.g., 2025-06-26T10:21:08Z)");
+    let mut cli = clap::Command::new("solana-block-finder")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Find the latest Solana block that matches a given timestamp")
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .arg(timestamp);
+    for option in OPTION_DOCS {
+        let mut arg = Arg::new(option.long).long(option.long).help(option.help);
+        if let Some(short) = option.short {
+            arg = arg.short(short);
+        }
+        arg = match option.value {
+            Some(value) => arg.value_name(value.trim_start_matches('<').trim_end_matches('>')).action(ArgAction::Set),
+            None => arg.action(ArgAction::SetTrue),
+        };
+        cli = cli.arg(arg);
+    }
+    for command in COMMAND_DOCS {
+        let mut subcommand = clap::Command::new(command.name).about(command.help);
+        if let Some(argument) = command.argument {
+            let name = argument.trim_start_matches('<').trim_end_matches('>');
+            subcommand = subcommand.arg(Arg::new(name).value_name(name).required(true));
+        }
+        cli = cli.subcommand(subcommand);
     }
     
+    clap_mangen::Man::new(cli).render(&mut std::io::stdout())?;
     Ok(())
 }
 
@@ -507,69 +4023,353 @@ fn print_help() {
     let program_name = env::args().next().unwrap_or_else(|| "solana-block-finder".to_string());
     println!("🚀 Solana Block Finder v1.0");
     println!("Find the latest Solana block that matches a given timestamp");
-    println!("");
+    println!();
     println!("📖 USAGE:");
     println!("    {} --timestamp <TIMESTAMP> [OPTIONS]", program_name);
-    println!("");
+    println!("    {} <COMMAND> [ARGS] [OPTIONS]", program_name);
+    println!();
+    println!("🧭 COMMANDS:");
+    for command in COMMAND_DOCS {
+        println!("    {}", command.help_line());
+    }
+    println!();
     println!("📋 REQUIRED PARAMETERS:");
     println!("    -t, --timestamp <TIMESTAMP>    Unix timestamp in seconds (e.g., 1750921805)");
     println!("                                   Or ISO 8601 format (e.g., 2025-06-26T10:21:08Z)");
-    println!("");
+    println!();
     println!("🔧 OPTIONS:");
-    println!("    -k, --api-key <API_KEY>        Helius API key (or set HELIUS_API_KEY env var)");
-    println!("    -v, --verbose                  Show detailed output including performance metrics");
-    println!("    -h, --help                     Show this help message");
-    println!("");
+    for option in OPTION_DOCS {
+        println!("    {}", option.help_line());
+    }
+    println!();
     println!("💡 EXAMPLES:");
     println!("    # Basic usage with Unix timestamp");
     println!("    {} --timestamp 1750921805", program_name);
-    println!("");
+    println!();
     println!("    # With custom API key");
     println!("    {} --timestamp 1750921805 --api-key your-api-key-here", program_name);
-    println!("");
+    println!();
     println!("    # With verbose output");
     println!("    {} --timestamp 1750921805 --verbose", program_name);
-    println!("");
+    println!();
+    println!("    # Show leader fees/rewards of the found block");
+    println!("    {} --timestamp 1750921805 --rewards", program_name);
+    println!();
+    println!("    # Show who produced the found block, with its vote account and stake");
+    println!("    {} --timestamp 1750921805 --leader-details", program_name);
+    println!();
+    println!("    # Show the priority fees paid in the found block");
+    println!("    {} --timestamp 1750921805 --fees", program_name);
+    println!();
+    println!("    # Show the fees collected in the found block");
+    println!("    {} --timestamp 1750921805 --total-fees", program_name);
+    println!();
+    println!("    # Show how busy the found block was");
+    println!("    {} --timestamp 1750921805 --compute-units", program_name);
+    println!();
+    println!("    # What did the Token program do at that time?");
+    println!("    {} --timestamp 1750921805 --program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", program_name);
+    println!();
+    println!("    # Dump the whole block JSON to a file");
+    println!("    {} --timestamp 1750921805 --full-block block.json", program_name);
+    println!();
+    println!("    # Copy the explorer link of the found block");
+    println!("    {} --timestamp 1750921805 --copy url", program_name);
+    println!();
     println!("    # Using ISO 8601 format");
     println!("    {} --timestamp 2025-06-26T10:21:08Z", program_name);
-    println!("");
+    println!();
+    println!("    # Find the block containing a transaction");
+    println!("    {} find-tx <SIGNATURE>", program_name);
+    println!();
+    println!("    # List an address's transactions within a time window");
+    println!("    {} activity <ADDRESS> --from 2025-06-26 --to 2025-06-27", program_name);
+    println!();
+    println!("    # When was a program first called after a time?");
+    println!("    {} first-call <PROGRAM_ID> --from 2025-06-26T10:00:00Z", program_name);
+    println!();
+    println!("    # When did a feature gate go live?");
+    println!("    {} feature <FEATURE_ID>", program_name);
+    println!();
+    println!("    # Epoch information at a timestamp");
+    println!("    {} epoch-at 1750921805", program_name);
+    println!();
+    println!("    # When did epoch 700 start and end");
+    println!("    {} epoch 700", program_name);
+    println!();
+    println!("    # Every block time of epoch 700 as Parquet, for your own index");
+    println!("    {} dump-epoch 700 --output parquet --output-file epoch-700.parquet", program_name);
+    println!();
+    println!("    # Last block before midnight UTC every day in June, as CSV");
+    println!("    {} schedule --every 1d --from 2025-06-01 --to 2025-06-30 --output csv", program_name);
+    println!();
+    println!("    # Hourly slots for a month as Parquet, for DuckDB or Spark");
+    println!("    {} schedule --every 1h --from 2025-06-01 --to 2025-06-30 --output parquet --output-file june.parquet", program_name);
+    println!();
+    println!("    # Slots for a list of timestamps, 8 searches at a time");
+    println!("    {} batch timestamps.txt --batch-concurrency 8 --output json", program_name);
+    println!();
+    println!("    # Pipe mode: one slot per input line, printed as soon as it is found");
+    println!("    tail -f events.log | cut -d' ' -f1 | {} --stdin --output json | jq .slot", program_name);
+    println!();
+    println!("    # Exactly the line format a downstream script expects");
+    println!("    {} batch timestamps.txt --format '{{slot}},{{blockhash}},{{block_time_iso}}'", program_name);
+    println!();
+    println!("    # Produced vs skipped slots during one hour");
+    println!("    {} stats --from 2025-06-26T10:00:00Z --to 2025-06-26T11:00:00Z", program_name);
+    println!();
+    println!("    # How far blockTime drifted from nominal slot timing in a day");
+    println!("    {} drift --from 2025-06-26 --to 2025-06-27 --samples 50", program_name);
+    println!();
+    println!("    # Validate a new RPC provider");
+    println!("    {} selftest -e https://my-provider.example.com", program_name);
+    println!();
+    println!("    # Catch bad API keys and non-archival nodes before a long search");
+    println!("    {} health -e https://mainnet.helius-rpc.com", program_name);
+    println!();
+    println!("    # Which endpoint is fastest");
+    println!("    {} bench -e https://mainnet.helius-rpc.com -e https://api.mainnet-beta.solana.com", program_name);
+    println!();
+    println!("    # Resumable search for tight rate limits");
+    println!("    {} --timestamp 1750921805 --checkpoint search.json", program_name);
+    println!("    {} --checkpoint search.json --resume", program_name);
+    println!();
+    println!("    # Through a corporate proxy");
+    println!("    {} --timestamp 1750921805 --proxy http://proxy.example.com:3128", program_name);
+    println!();
+    println!("    # Pin the RPC host to a specific IP");
+    println!("    {} --timestamp 1750921805 --resolve mainnet.helius-rpc.com:203.0.113.10", program_name);
+    println!();
+    println!("    # Slow archival provider");
+    println!("    {} --timestamp 1650000000 --timeout 60s --connect-timeout 10s", program_name);
+    println!();
+    println!("    # Keep the API key in the OS keyring instead of passing --api-key");
+    println!("    {} key store", program_name);
+    println!();
     println!("    # Short form parameters");
     println!("    {} -t 1750921805 -k your-key -v", program_name);
-    println!("");
+    println!();
     println!("🌟 FEATURES:");
     println!("    • 🎯 100% accuracy verified against Solana Explorer");
     println!("    • 🚀 Fast binary search algorithm (7-10 second searches)");
-    println!("    • ⚡ Always finds the highest slot when multiple blocks share timestamp");
+    println!("    • ⚡ Always finds the highest slot when multiple blocks share timestamp (or the lowest with --lowest)");
     println!("    • 🔄 Parallel processing for optimal performance");
     println!("    • 🌐 Production-ready with error handling and connection pooling");
-    println!("");
+    println!();
     println!("📊 OUTPUT:");
     println!("    The tool will display the found block's slot number, blockhash,");
     println!("    timestamp, block height, and a link to Solana Explorer.");
-    println!("");
+    println!();
     println!("🔑 API KEY:");
     println!("    Get a free Helius API key at: https://helius.xyz");
     println!("    Set it as environment variable: export HELIUS_API_KEY=your-key");
-    println!("");
+    println!("    Or keep it out of shell history in the OS keyring: {} key store", program_name);
+    println!();
 }
 
 fn print_usage() {
     let program_name = env::args().next().unwrap_or_else(|| "solana-block-finder".to_string());
     println!("📖 USAGE:");
     println!("    {} --timestamp <TIMESTAMP> [OPTIONS]", program_name);
-    println!("");
+    println!();
     println!("💡 EXAMPLES:");
     println!("    {} --timestamp 1750921805                    # Unix timestamp", program_name);
     println!("    {} --timestamp 2025-06-26T10:21:08Z          # ISO 8601 format", program_name);
     println!("    {} -t 1750921805 -v                          # With verbose output", program_name);
     println!("    {} -t 1750921805 -k your-key                 # With API key", program_name);
-    println!("");
+    println!("    {} find-tx <SIGNATURE>                       # Block of a transaction", program_name);
+    println!("    {} activity <ADDRESS> --from <TS> --to <TS>  # Address activity in a window", program_name);
+    println!("    {} last-activity <ADDRESS> -t <TS>           # Last transaction before a time", program_name);
+    println!("    {} created-at <ADDRESS>                      # When an account was created", program_name);
+    println!("    {} mint-created <MINT>                       # When a token was created", program_name);
+    println!("    {} deployments <PROGRAM_ID>                  # Program deployment history", program_name);
+    println!("    {} epoch-at 1750921805                       # Epoch at a timestamp", program_name);
+    println!("    {} epoch 700                                 # Epoch boundaries", program_name);
+    println!("    {} eta 400000000                             # When a future slot lands", program_name);
+    println!("    {} countdown -t 2030-01-01T00:00:00Z         # Slot of a future time, live", program_name);
+    println!("    {} between 250000000 250432000               # Time between two slots", program_name);
+    println!("    {} offset --slot 250000000 --by -1h          # The block an hour earlier", program_name);
+    println!("    {} schedule --every 1d --from <TS> --to <TS> # Daily snapshot slots", program_name);
+    println!("    {} batch <FILE> --batch-concurrency 8        # Many timestamps concurrently", program_name);
+    println!("    {} stats --from <TS> --to <TS>               # Block production stats", program_name);
+    println!("    {} drift --from <TS> --to <TS>               # BlockTime drift analysis", program_name);
+    println!("    {} health                                    # Check the RPC endpoints", program_name);
+    println!("    {} bench -e <URL> -e <URL>                   # Compare endpoint latency", program_name);
+    println!();
     println!("Use --help for full documentation");
 }
 
-fn parse_timestamp(input: &str) -> Result<i64, Box<dyn Error>> {
+/// Parses durations like `30s`, `15m`, `1h`, `1d` or `2h30m` into seconds.
+fn parse_count(input: &str) -> Result<usize, Box<dyn Error>> {
+    input.parse().map_err(|_| format!("❌ Invalid number: '{}'", input).into())
+}
+
+/// Parses a non-zero number of slots for `--probe-window`
+fn parse_probe_window(input: &str) -> Result<u64, Box<dyn Error>> {
+    match input.parse() {
+        Ok(0) | Err(_) => Err(format!("❌ Invalid probe window: '{}' (must be at least 1 slot)", input).into()),
+        Ok(slots) => Ok(slots),
+    }
+}
+
+fn parse_stride(input: &str) -> Result<u64, Box<dyn Error>> {
+    match input.parse() {
+        Ok(0) | Err(_) => Err(format!("❌ Invalid stride: '{}' (must be at least 1 slot)", input).into()),
+        Ok(slots) => Ok(slots),
+    }
+}
+
+/// Checks a strftime-style template up front, since formatting with an invalid one panics
+fn parse_time_format(input: &str) -> Result<String, Box<dyn Error>> {
+    if StrftimeItems::new(input).any(|item| item == Item::Error) {
+        return Err(format!("❌ Invalid time format: '{}' (expected strftime specifiers such as %Y-%m-%d %H:%M:%S%z)", input).into());
+    }
+    Ok(input.to_string())
+}
+
+/// Parses the cap on binary search steps
+fn parse_max_iterations(input: &str) -> Result<u32, Box<dyn Error>> {
+    match input.parse() {
+        Ok(0) | Err(_) => Err(format!("❌ Invalid max iterations: '{}' (must be at least 1)", input).into()),
+        Ok(iterations) => Ok(iterations),
+    }
+}
+
+/// Parses the number of searches batch runs at once
+fn parse_batch_concurrency(input: &str) -> Result<usize, Box<dyn Error>> {
+    match input.parse() {
+        Ok(0) | Err(_) => Err(format!("❌ Invalid batch concurrency: '{}' (must be at least 1)", input).into()),
+        Ok(concurrency) => Ok(concurrency),
+    }
+}
+
+/// Parses a delay given in whole milliseconds, zero disables it
+fn parse_millis(input: &str) -> Result<Duration, Box<dyn Error>> {
+    input.parse().map(Duration::from_millis).map_err(|_| format!("❌ Invalid delay: '{}' (expected milliseconds, e.g. 50)", input).into())
+}
+
+fn parse_commitment(input: &str) -> Result<Commitment, Box<dyn Error>> {
+    match input {
+        "finalized" => Ok(Commitment::Finalized),
+        "confirmed" => Ok(Commitment::Confirmed),
+        _ => Err(format!("❌ Invalid commitment: '{}' (expected finalized or confirmed)", input).into()),
+    }
+}
+
+fn parse_cluster(input: &str) -> Result<Cluster, Box<dyn Error>> {
+    match input {
+        "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+        "devnet" => Ok(Cluster::Devnet),
+        "testnet" => Ok(Cluster::Testnet),
+        _ => Err(format!("❌ Invalid cluster: '{}' (expected mainnet, devnet or testnet)", input).into()),
+    }
+}
+
+/// Parses a non-zero timeout in the same format as `--every`
+fn parse_timeout(input: &str) -> Result<Duration, Box<dyn Error>> {
+    match parse_duration(input)? {
+        0 => Err(format!("❌ Invalid timeout: '{}' (must be at least 1s)", input).into()),
+        seconds => Ok(Duration::from_secs(seconds as u64)),
+    }
+}
+
+/// Reads the config file given with `--config`, or the default one if it exists.
+fn load_config(path: Option<&str>) -> Result<Config, Box<dyn Error>> {
+    let (path, explicit) = match path {
+        Some(path) => (PathBuf::from(path), true),
+        None => match default_config_path() {
+            Some(path) => (path, false),
+            None => return Ok(Config::default()),
+        },
+    };
+    
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if !explicit && e.kind() == std::io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(e) => return Err(format!("❌ Cannot read config file {}: {}", path.display(), e).into()),
+    };
+    toml::from_str(&contents).map_err(|e| format!("❌ Invalid config file {}: {}", path.display(), e).into())
+}
+
+fn load_genesis(path: &str) -> Result<GenesisConfig, Box<dyn Error>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("❌ Cannot read genesis file {}: {}", path, e))?;
+    toml::from_str(&contents).map_err(|e| format!("❌ Invalid genesis file {}: {}", path, e).into())
+}
+
+/// `$XDG_CONFIG_HOME/solana-block-finder/config.toml`, falling back to `~/.config`
+fn default_config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("solana-block-finder").join("config.toml"))
+}
+
+/// Parses a curl style `HOST:IP` override. IPv6 addresses may be wrapped in brackets.
+fn parse_resolve(input: &str) -> Result<(String, IpAddr), Box<dyn Error>> {
+    let invalid = || format!("❌ Invalid --resolve value: '{}' (expected HOST:IP, e.g. mainnet.helius-rpc.com:203.0.113.10)", input);
+    let (host, ip) = input.split_once(':').ok_or_else(invalid)?;
+    let ip = ip.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err(invalid().into());
+    }
+    Ok((host.to_string(), ip.parse().map_err(|_| invalid())?))
+}
+
+fn parse_positive_duration(input: &str) -> Result<i64, Box<dyn Error>> {
+    match parse_duration(input)? {
+        seconds if seconds > 0 => Ok(seconds),
+        _ => Err(format!("❌ Invalid duration: '{}' (must be positive)", input).into()),
+    }
+}
+
+fn parse_duration(input: &str) -> Result<i64, Box<dyn Error>> {
+    let invalid = || format!("❌ Invalid duration: '{}' (expected e.g. 30s, 15m, 1h, 1d, 2h30m)", input);
+    
+    let mut total: i64 = 0;
+    let mut number = String::new();
+    for c in input.trim().chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3_600,
+            'd' => 86_400,
+            'w' => 604_800,
+            _ => return Err(invalid().into()),
+        };
+        let value: i64 = number.parse().map_err(|_| invalid())?;
+        total = value.checked_mul(unit).and_then(|seconds| total.checked_add(seconds)).ok_or_else(invalid)?;
+        number.clear();
+    }
+    
+    // A bare number is taken as seconds
+    if !number.is_empty() {
+        let value: i64 = number.parse().map_err(|_| invalid())?;
+        total = total.checked_add(value).ok_or_else(invalid)?;
+    }
+    if total == 0 && input.trim().is_empty() {
+        return Err(invalid().into());
+    }
+    
+    Ok(total)
+}
+
+/// Parses a duration with an optional sign, e.g. `2h30m`, `+1h` or `-1h`, into seconds
+fn parse_offset(input: &str) -> Result<i64, Box<dyn Error>> {
+    let input = input.trim();
+    match input.strip_prefix('-') {
+        Some(duration) => Ok(-parse_duration(duration)?),
+        None => parse_duration(input.strip_prefix('+').unwrap_or(input)),
+    }
+}
+
+fn parse_timestamp(input: &str) -> Result<UnixTs, Box<dyn Error>> {
     // Try to parse as Unix timestamp first
-    if let Ok(timestamp) = input.parse::<i64>() {
+    if let Ok(timestamp) = input.parse::<UnixTs>() {
         return Ok(timestamp);
     }
     
@@ -595,7 +4395,7 @@ fn parse_timestamp(input: &str) -> Result<i64, Box<dyn Error>> {
             {
                 if output.status.success() {
                     let timestamp_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    if let Ok(timestamp) = timestamp_str.parse::<i64>() {
+                    if let Ok(timestamp) = timestamp_str.parse::<UnixTs>() {
                         return Ok(timestamp);
                     }
                 }
@@ -605,3 +4405,166 @@ fn parse_timestamp(input: &str) -> Result<i64, Box<dyn Error>> {
     
     Err(format!("❌ Invalid timestamp format: '{}'\n\nSupported formats:\n  • Unix timestamp: 1750921805\n  • ISO 8601: 2025-06-26T10:21:08Z\n  • Date only: 2025-06-26", input).into())
 } 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn parses_duration_units() {
+        assert_eq!(parse_duration("30s").unwrap(), 30);
+        assert_eq!(parse_duration("15m").unwrap(), 900);
+        assert_eq!(parse_duration("1h").unwrap(), 3_600);
+        assert_eq!(parse_duration("1d").unwrap(), 86_400);
+        assert_eq!(parse_duration("2w").unwrap(), 1_209_600);
+        // A bare number is taken as seconds
+        assert_eq!(parse_duration("45").unwrap(), 45);
+        assert_eq!(parse_duration(" 0s ").unwrap(), 0);
+    }
+    
+    #[test]
+    fn parses_combined_durations() {
+        assert_eq!(parse_duration("2h30m").unwrap(), 9_000);
+        assert_eq!(parse_duration("1d12h").unwrap(), 129_600);
+        assert_eq!(parse_duration("1m30").unwrap(), 90);
+    }
+    
+    #[test]
+    fn rejects_empty_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("   ").is_err());
+    }
+    
+    #[test]
+    fn rejects_junk_durations() {
+        for input in ["h", "1x", "1.5h", "-1h", "1 h", "2h30mm", "abc"] {
+            assert!(parse_duration(input).is_err(), "{}", input);
+        }
+    }
+    
+    #[test]
+    fn rejects_overflowing_durations() {
+        assert!(parse_duration("99999999999999999999").is_err());
+        assert!(parse_duration("9999999999999999w").is_err());
+        assert!(parse_duration("9223372036854775807s1s").is_err());
+        assert_eq!(parse_duration("9223372036854775807").unwrap(), i64::MAX);
+    }
+    
+    #[test]
+    fn labels_status_emoji() {
+        assert_eq!(ascii_labels("✅ Found block"), "[ok] Found block");
+        assert_eq!(ascii_labels("❌ Error: no block"), "[error] Error: no block");
+        // The warning sign is followed by a variation selector and two spaces
+        assert_eq!(ascii_labels("⚠️  Stopping"), "[warning] Stopping");
+        assert_eq!(ascii_labels("\n🛑 Interrupted!"), "\n[stopped] Interrupted!");
+        assert_eq!(ascii_labels("1600000000 → slot 5"), "1600000000 -> slot 5");
+        assert_eq!(ascii_labels("  • Unix timestamp"), "  - Unix timestamp");
+    }
+    
+    #[test]
+    fn drops_decorative_emoji() {
+        assert_eq!(ascii_labels("📍 Slot: 5"), "Slot: 5");
+        assert_eq!(ascii_labels("⏮️  First slot: 0"), "First slot: 0");
+        assert_eq!(ascii_labels("🗓️  Epoch: 600"), "Epoch: 600");
+        // Joined emoji are dropped as a whole
+        assert_eq!(ascii_labels("👩\u{200D}💻 dev"), "dev");
+    }
+    
+    #[test]
+    fn keeps_other_multi_byte_characters() {
+        assert_eq!(ascii_labels("Memo: grüße, 日本語, ©"), "Memo: grüße, 日本語, ©");
+        assert_eq!(ascii_labels("✅ Ünïcödé"), "[ok] Ünïcödé");
+    }
+    
+    #[test]
+    fn leaves_ascii_unchanged() {
+        for text in ["", "Slot 5 has timestamp 1600000000", "[ok] already plain", "  indented  "] {
+            assert_eq!(ascii_labels(text), text);
+        }
+    }
+    
+    #[test]
+    fn plain_text_off_a_terminal() {
+        assert_eq!(plain_text(false, "✅ Done".to_string()), "[ok] Done");
+        assert_eq!(plain_text(false, "plain".to_string()), "plain");
+    }
+    
+    #[test]
+    fn csv_fields_without_special_characters_are_bare() {
+        assert_eq!(csv_field("hash250002"), "hash250002");
+        assert_eq!(csv_field("with space"), "with space");
+        assert_eq!(csv_field(""), "");
+    }
+    
+    #[test]
+    fn quotes_csv_fields_with_separators() {
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field("carriage\rreturn"), "\"carriage\rreturn\"");
+    }
+    
+    #[test]
+    fn doubles_quotes_in_csv_fields() {
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("\""), "\"\"\"\"");
+    }
+    
+    #[test]
+    fn accepts_strftime_time_formats() {
+        for format in ["%Y-%m-%d %H:%M:%S%z", "%+", "%s", "%d/%m/%Y", "block at %H:%M", "%%", ""] {
+            assert_eq!(parse_time_format(format).unwrap(), format);
+        }
+        // Accepted formats render without panicking
+        let time = DateTime::from_timestamp(1_600_000_000, 0).unwrap();
+        assert_eq!(time.format(&parse_time_format("%Y-%m-%d %H:%M:%S").unwrap()).to_string(), "2020-09-13 12:26:40");
+    }
+    
+    #[test]
+    fn rejects_invalid_time_formats() {
+        // Unknown specifiers, a trailing lone '%' and padding without a specifier
+        for format in ["%Q", "%Y-%m-%", "%", "%-", "%Ez"] {
+            assert!(parse_time_format(format).is_err(), "{}", format);
+        }
+    }
+    
+    fn found_block() -> BlockMatch {
+        let block_info = solana_block_finder::rpc::BlockInfo {
+            blockhash: "hash250002".to_string(),
+            parent_slot: Slot(250001),
+            block_time: Some(UnixTs(1600100000)),
+            block_height: Some(240000),
+            rewards: Vec::new(),
+        };
+        BlockMatch::new(Slot(250002), &block_info, Some(UnixTs(1600099999)))
+    }
+    
+    #[test]
+    fn renders_template_fields() {
+        let template = Template::parse("{slot},{blockhash},{block_time_iso},{time_diff},{exact},{finalized}").unwrap();
+        assert_eq!(template.render(&found_block()), "250002,hash250002,2020-09-14T16:13:20Z,1,false,");
+    }
+    
+    #[test]
+    fn renders_literal_text_and_braces() {
+        let template = Template::parse("slot={slot} {{raw}} }}").unwrap();
+        assert_eq!(template.render(&found_block()), "slot=250002 {raw} }");
+        assert_eq!(Template::parse("").unwrap().render(&found_block()), "");
+        // Templates have no time specifiers, strftime syntax is plain text
+        assert_eq!(Template::parse("%Y-%m-%d %Q {block_time}").unwrap().render(&found_block()), "%Y-%m-%d %Q 1600100000");
+    }
+    
+    #[test]
+    fn rejects_unknown_template_fields() {
+        let error = Template::parse("{slot},{hash}").unwrap_err().to_string();
+        assert!(error.contains("Unknown --format field '{hash}'"), "{}", error);
+        assert!(Template::parse("{}").is_err());
+        assert!(Template::parse("{ slot }").is_err());
+    }
+    
+    #[test]
+    fn rejects_unbalanced_template_braces() {
+        for template in ["{slot", "slot}", "{slot}}", "{{slot}", "}{slot}"] {
+            assert!(Template::parse(template).is_err(), "{}", template);
+        }
+    }
+}
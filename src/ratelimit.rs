@@ -0,0 +1,122 @@
+//! Token buckets enforcing per-endpoint request rates
+//!
+//! Each endpoint with a [`RateLimit`] gets its own bucket. The bucket holds
+//! up to `burst` tokens and refills at `max_rps` tokens per second; every
+//! request takes one.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::finder::RateLimit;
+
+pub(crate) struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    refilled_at_ms: u64,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        TokenBucket {
+            limit,
+            state: Mutex::new(BucketState { tokens: limit.burst.max(1) as f64, refilled_at_ms: 0 }),
+        }
+    }
+    
+    /// Takes a token if one is available right now
+    pub(crate) fn try_acquire(&self, clock: &dyn Clock) -> bool {
+        self.take(clock).is_none()
+    }
+    
+    /// Waits until a token is available and takes it
+    pub(crate) async fn acquire(&self, clock: &dyn Clock) {
+        while let Some(wait) = self.take(clock) {
+            clock.sleep(wait).await;
+        }
+    }
+    
+    /// Takes a token, or returns how long until the next one is refilled
+    fn take(&self, clock: &dyn Clock) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now_ms = clock.elapsed_ms();
+        let capacity = self.limit.burst.max(1) as f64;
+        let elapsed = now_ms.saturating_sub(state.refilled_at_ms) as f64 / 1000.0;
+        state.tokens = (state.tokens + elapsed * self.limit.max_rps).min(capacity);
+        state.refilled_at_ms = now_ms;
+        
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            let missing = 1.0 - state.tokens;
+            Some(Duration::from_secs_f64(missing / self.limit.max_rps).max(Duration::from_millis(1)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FakeClock;
+    use crate::types::UnixTs;
+    use tokio::time::Instant;
+    
+    fn bucket(max_rps: f64, burst: u32) -> TokenBucket {
+        TokenBucket::new(RateLimit { max_rps, burst })
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn hands_out_the_burst_without_waiting() {
+        let clock = FakeClock::new(UnixTs(1_000));
+        let bucket = bucket(10.0, 3);
+        let started = Instant::now();
+        for _ in 0..3 {
+            bucket.acquire(&clock).await;
+        }
+        assert_eq!(started.elapsed(), Duration::ZERO);
+        assert!(!bucket.try_acquire(&clock));
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_the_refill_once_empty() {
+        let clock = FakeClock::new(UnixTs(1_000));
+        let bucket = bucket(10.0, 1);
+        let started = Instant::now();
+        bucket.acquire(&clock).await;
+        bucket.acquire(&clock).await;
+        bucket.acquire(&clock).await;
+        // Two refills at 10 per second
+        let waited = started.elapsed();
+        assert!(waited >= Duration::from_millis(200) && waited < Duration::from_millis(210), "{:?}", waited);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn refills_up_to_the_burst() {
+        let clock = FakeClock::new(UnixTs(1_000));
+        let bucket = bucket(2.0, 2);
+        assert!(bucket.try_acquire(&clock));
+        assert!(bucket.try_acquire(&clock));
+        assert!(!bucket.try_acquire(&clock));
+        
+        // A long pause refills the bucket, but only to its burst
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert!(bucket.try_acquire(&clock));
+        assert!(bucket.try_acquire(&clock));
+        assert!(!bucket.try_acquire(&clock));
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn follows_the_advanced_clock() {
+        let clock = FakeClock::new(UnixTs(1_000));
+        let bucket = bucket(1.0, 1);
+        assert!(bucket.try_acquire(&clock));
+        assert!(!bucket.try_acquire(&clock));
+        clock.advance(Duration::from_secs(1));
+        assert!(bucket.try_acquire(&clock));
+    }
+}
@@ -0,0 +1,962 @@
+//! Thin JSON-RPC client for the Solana methods used by the finder
+//!
+//! [`HttpBackend`] is the default [`RpcBackend`]. The `BlockFinder` methods at
+//! the bottom add caching and the concurrency limit on top of whichever
+//! backend is configured, and wrap every call in an `rpc` tracing span.
+
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use reqwest::Client;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tracing::field::{display, Empty};
+use tracing::Instrument;
+
+use crate::backend::{BackendFuture, RpcBackend};
+use crate::cassette::{Cassette, MissingRecording};
+use crate::clock::{Clock, SystemClock};
+use crate::epoch::EpochSchedule;
+use crate::finder::{Auth, BlockFinder, Commitment, RateLimit, RetryPolicy};
+use crate::ratelimit::TokenBucket;
+use crate::redact::Redactor;
+use crate::singleflight::{self, Flight};
+use crate::runtime::Stopwatch;
+use crate::types::{EndpointStats, Slot, UnixTs};
+
+// RPC response structures
+#[derive(Debug, Deserialize)]
+pub struct RpcResponse<T> {
+    /// Echo of the request id, `null` when the node could not read the request
+    #[serde(default)]
+    pub id: Value,
+    pub result: Option<T>,
+    pub error: Option<RpcError>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (code {})", self.message, self.code)
+    }
+}
+
+impl Error for RpcError {}
+
+impl RpcError {
+    pub fn kind(&self) -> RpcErrorKind {
+        match RpcErrorKind::from_code(self.code) {
+            // Providers report bad keys with their own codes, but always say so in the message
+            RpcErrorKind::Other if mentions_bad_credentials(&self.message) => RpcErrorKind::Unauthorized,
+            kind => kind,
+        }
+    }
+}
+
+fn mentions_bad_credentials(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("api key") || message.contains("api-key") || message.contains("unauthorized")
+}
+
+/// What a JSON-RPC error code means for the search.
+///
+/// | code               | kind                  | search behavior                              |
+/// |--------------------|-----------------------|----------------------------------------------|
+/// | -32007, -32009     | [`SlotSkipped`]       | no block in the slot, probe its neighbors    |
+/// | -32004, -32014     | [`BlockNotAvailable`] | try the next endpoint, then treat as skipped |
+/// | -32001, -32011     | [`Purged`]            | below this node's ledger, search higher      |
+/// | -32005, -32016     | [`NodeBehind`]        | try the next endpoint, then search lower     |
+/// | -32602             | [`InvalidParams`]     | abort, retrying cannot help                  |
+/// | -32401, -32403     | [`Unauthorized`]      | abort, the API key is missing or invalid     |
+///
+/// [`SlotSkipped`]: RpcErrorKind::SlotSkipped
+/// [`BlockNotAvailable`]: RpcErrorKind::BlockNotAvailable
+/// [`Purged`]: RpcErrorKind::Purged
+/// [`NodeBehind`]: RpcErrorKind::NodeBehind
+/// [`InvalidParams`]: RpcErrorKind::InvalidParams
+/// [`Unauthorized`]: RpcErrorKind::Unauthorized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorKind {
+    /// The slot was skipped (or is missing from long-term storage), so it has no block
+    SlotSkipped,
+    /// The block exists but this node cannot serve it, e.g. it is not confirmed there yet
+    BlockNotAvailable,
+    /// The block was cleaned up from this node's ledger, or its history is not kept
+    Purged,
+    /// The node is unhealthy or has not reached the requested slot
+    NodeBehind,
+    /// The request itself is malformed
+    InvalidParams,
+    /// The provider rejected the credentials
+    Unauthorized,
+    Other,
+}
+
+impl RpcErrorKind {
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            -32007 | -32009 => RpcErrorKind::SlotSkipped,
+            -32004 | -32014 => RpcErrorKind::BlockNotAvailable,
+            -32001 | -32011 => RpcErrorKind::Purged,
+            -32005 | -32016 => RpcErrorKind::NodeBehind,
+            -32602 => RpcErrorKind::InvalidParams,
+            -32401 | -32403 => RpcErrorKind::Unauthorized,
+            _ => RpcErrorKind::Other,
+        }
+    }
+    
+    /// Errors that depend on the node that answered, so another endpoint may succeed
+    pub fn is_node_specific(self) -> bool {
+        matches!(self, RpcErrorKind::BlockNotAvailable | RpcErrorKind::NodeBehind)
+    }
+    
+    /// Classifies an error returned by a [`BlockFinder`] or [`RpcBackend`] call.
+    /// Errors that are not JSON-RPC errors are [`RpcErrorKind::Other`].
+    pub fn of(error: &(dyn Error + 'static)) -> Self {
+        error.downcast_ref::<RpcError>().map_or(RpcErrorKind::Other, RpcError::kind)
+    }
+}
+
+/// Non-success HTTP status returned by an endpoint
+#[derive(Debug, Clone)]
+pub struct HttpError {
+    pub status: StatusCode,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => write!(f, "HTTP {} (check the API key)", self.status),
+            status => write!(f, "HTTP {}", status),
+        }
+    }
+}
+
+impl Error for HttpError {}
+
+/// Response whose `id` does not belong to the request it answers, e.g. from a misbehaving
+/// proxy or load balancer mixing up connections
+#[derive(Debug)]
+pub struct ResponseIdMismatch {
+    pub expected: u64,
+    pub received: Value,
+}
+
+impl fmt::Display for ResponseIdMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Response id {} does not match request id {}", self.received, self.expected)
+    }
+}
+
+impl Error for ResponseIdMismatch {}
+
+/// Copy of a failed call's error handed to every caller coalesced onto it.
+/// Keeps the types [`classify_error`] and [`RpcErrorKind::of`] look at.
+#[derive(Debug, Clone)]
+pub(crate) enum SharedError {
+    Rpc(RpcError),
+    Http(HttpError),
+    Other(String),
+}
+
+impl SharedError {
+    fn of(error: &(dyn Error + 'static)) -> Self {
+        if let Some(error) = error.downcast_ref::<RpcError>() {
+            SharedError::Rpc(error.clone())
+        } else if let Some(error) = error.downcast_ref::<HttpError>() {
+            SharedError::Http(error.clone())
+        } else {
+            SharedError::Other(error.to_string())
+        }
+    }
+    
+    fn into_error(self) -> Box<dyn Error> {
+        match self {
+            SharedError::Rpc(error) => error.into(),
+            SharedError::Http(error) => error.into(),
+            SharedError::Other(message) => message.into(),
+        }
+    }
+}
+
+/// Whether repeating a failed request can succeed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Transient: connection failures, timeouts, HTTP 408/429/5xx, node-specific RPC errors
+    Retryable,
+    /// Permanent: rejected credentials, other HTTP 4xx, invalid params, malformed requests
+    Fatal,
+}
+
+/// Classifies an error from an RPC call for the retry layer and the search.
+/// Errors the classifier does not recognize are treated as retryable.
+pub fn classify_error(error: &(dyn Error + 'static)) -> ErrorClass {
+    if let Some(error) = error.downcast_ref::<HttpError>() {
+        let status = error.status;
+        return if status == StatusCode::REQUEST_TIMEOUT || status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            ErrorClass::Retryable
+        } else {
+            ErrorClass::Fatal
+        };
+    }
+    if let Some(error) = error.downcast_ref::<RpcError>() {
+        return match error.kind() {
+            RpcErrorKind::InvalidParams | RpcErrorKind::Unauthorized => ErrorClass::Fatal,
+            _ => ErrorClass::Retryable,
+        };
+    }
+    // Replaying again gives the same answer
+    if error.is::<MissingRecording>() {
+        return ErrorClass::Fatal;
+    }
+    if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+        // Bad URLs and unbuildable requests fail the same way every time
+        return if error.is_builder() { ErrorClass::Fatal } else { ErrorClass::Retryable };
+    }
+    ErrorClass::Retryable
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockInfo {
+    pub blockhash: String,
+    pub parent_slot: Slot,
+    #[serde(default)]
+    pub block_time: Option<UnixTs>,
+    #[serde(default)]
+    pub block_height: Option<u64>,
+    #[serde(default)]
+    pub rewards: Vec<Reward>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reward {
+    pub pubkey: String,
+    pub lamports: i64,
+    pub post_balance: u64,
+    #[serde(default)]
+    pub reward_type: Option<String>,
+    #[serde(default)]
+    pub commission: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: Slot,
+    #[serde(default)]
+    pub err: Option<Value>,
+    #[serde(default)]
+    pub memo: Option<String>,
+    #[serde(default)]
+    pub block_time: Option<UnixTs>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochInfo {
+    pub epoch: u64,
+    pub slot_index: u64,
+    pub slots_in_epoch: u64,
+    pub absolute_slot: Slot,
+}
+
+/// An account as returned by `getAccountInfo` with `jsonParsed` encoding
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountInfo {
+    pub lamports: u64,
+    pub owner: String,
+    pub executable: bool,
+    /// `{"program": ..., "parsed": ...}` for account types the node can parse, `[data, encoding]` otherwise
+    pub data: Value,
+}
+
+/// One entry of `getRecentPerformanceSamples`, normally covering 60 seconds
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceSample {
+    /// Slot at the end of the sample period
+    pub slot: Slot,
+    pub num_slots: u64,
+    pub num_transactions: u64,
+    pub sample_period_secs: u16,
+}
+
+/// A validator's vote account, as listed by `getVoteAccounts`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteAccount {
+    pub vote_pubkey: String,
+    /// Identity of the validator voting with this account, the pubkey that leads its slots
+    pub node_pubkey: String,
+    /// Stake delegated to the account in the current epoch, in lamports
+    pub activated_stake: u64,
+    pub commission: u8,
+    pub last_vote: Slot,
+}
+
+/// Vote accounts returned by `getVoteAccounts`, split by whether they still vote
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VoteAccounts {
+    pub current: Vec<VoteAccount>,
+    pub delinquent: Vec<VoteAccount>,
+}
+
+/// Software version reported by `getVersion`
+#[derive(Debug, Deserialize)]
+pub struct VersionInfo {
+    #[serde(rename = "solana-core")]
+    pub solana_core: String,
+    #[serde(rename = "feature-set", default)]
+    pub feature_set: Option<u32>,
+}
+
+/// JSON-RPC over HTTP with retries, endpoint failover and per-endpoint rate limits
+pub struct HttpBackend {
+    client: Client,
+    endpoints: Vec<String>,
+    auth: Auth,
+    retry: RetryPolicy,
+    // One slot per endpoint, `None` for endpoints without a limit
+    rate_limits: Vec<Option<TokenBucket>>,
+    redactor: Redactor,
+    next_id: AtomicU64,
+    // Requests and retries, one pair per endpoint
+    counters: Vec<(AtomicU64, AtomicU64)>,
+    cassette: Option<Cassette>,
+    clock: Arc<dyn Clock>,
+}
+
+impl HttpBackend {
+    pub fn new(client: Client, endpoints: Vec<String>, auth: Auth, retry: RetryPolicy) -> Self {
+        let rate_limits = endpoints.iter().map(|_| None).collect();
+        let mut redactor = Redactor::new();
+        if let Some(secret) = auth.secret() {
+            redactor.add(secret);
+        }
+        let counters = endpoints.iter().map(|_| (AtomicU64::new(0), AtomicU64::new(0))).collect();
+        HttpBackend { client, endpoints, auth, retry, rate_limits, redactor, next_id: AtomicU64::new(1), counters, cassette: None, clock: Arc::new(SystemClock::new()) }
+    }
+    
+    /// Replaces the redactor used for retry warnings, which by default masks the configured
+    /// credentials and key-like URL parameters
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+    
+    /// Limits the request rate on `endpoint`. Unknown endpoints are ignored.
+    pub fn with_rate_limit(mut self, endpoint: &str, limit: RateLimit) -> Self {
+        for (url, bucket) in self.endpoints.iter().zip(self.rate_limits.iter_mut()) {
+            if url == endpoint {
+                *bucket = Some(TokenBucket::new(limit));
+            }
+        }
+        self
+    }
+    
+    /// Records every request and response to `cassette`, or answers requests from it
+    /// without sending them when it is replaying
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+    
+    /// Waits for retries and rate limits on `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    fn replay_cassette(&self) -> Option<&Cassette> {
+        self.cassette.as_ref().filter(|cassette| cassette.is_replay())
+    }
+    
+    /// Picks the endpoint for an attempt: the preferred one if it is within its rate limit,
+    /// otherwise the next endpoint with capacity left. When every endpoint is exhausted,
+    /// waits for a token on the preferred one.
+    async fn schedule(&self, preferred: usize) -> usize {
+        let count = self.endpoints.len();
+        for index in (0..count).map(|offset| (preferred + offset) % count) {
+            match &self.rate_limits[index] {
+                None => return index,
+                Some(bucket) if bucket.try_acquire(self.clock.as_ref()) => return index,
+                Some(_) => {}
+            }
+        }
+        if let Some(bucket) = &self.rate_limits[preferred] {
+            bucket.acquire(self.clock.as_ref()).await;
+        }
+        preferred
+    }
+    
+    /// Sends a JSON-RPC request and parses the response envelope.
+    /// Errors that [`classify_error`] considers retryable, e.g. connection resets, HTTP 429 and
+    /// 5xx responses, and node-specific RPC errors (see [`RpcErrorKind::is_node_specific`]), are
+    /// retried according to the retry policy, moving on to the next configured endpoint on every
+    /// attempt. Fatal errors such as a rejected API key are returned immediately.
+    pub async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<RpcResponse<T>, Box<dyn Error>> {
+        let mut attempt: u32 = 0;
+        loop {
+            // Replayed requests never reach an endpoint, so its rate limit does not apply
+            let preferred = attempt as usize % self.endpoints.len();
+            let index = if self.replay_cassette().is_some() { preferred } else { self.schedule(preferred).await };
+            let (requests, retries) = &self.counters[index];
+            requests.fetch_add(1, Ordering::Relaxed);
+            if attempt > 0 {
+                retries.fetch_add(1, Ordering::Relaxed);
+            }
+            let endpoint = &self.endpoints[index];
+            let backoff = match self.send_request::<T>(endpoint, method, &params).await {
+                Ok(RpcResponse { error: Some(error), .. }) if error.kind().is_node_specific() && attempt < self.retry.max_retries => {
+                    let backoff = self.retry.backoff(attempt);
+                    tracing::warn!("{} failed on {}: {} (retrying in {} ms)", method, self.redactor.redact(endpoint), self.redactor.redact(&error.to_string()), backoff.as_millis());
+                    backoff
+                }
+                // Retrying with the same credentials cannot help, so fail every method the same way
+                Ok(RpcResponse { error: Some(error), .. }) if error.kind() == RpcErrorKind::Unauthorized => {
+                    return Err(error.into());
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if attempt < self.retry.max_retries && classify_error(e.as_ref()) == ErrorClass::Retryable => {
+                    let backoff = self.retry.backoff(attempt);
+                    tracing::warn!("{} failed on {}: {} (retrying in {} ms)", method, self.redactor.redact(endpoint), self.redactor.redact(&e.to_string()), backoff.as_millis());
+                    backoff
+                }
+                Err(e) => return Err(e),
+            };
+            self.clock.sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+    
+    async fn send_request<T: DeserializeOwned>(&self, endpoint: &str, method: &str, params: &Value) -> Result<RpcResponse<T>, Box<dyn Error>> {
+        if let Some(cassette) = self.replay_cassette() {
+            return Ok(serde_json::from_value(cassette.play(method, params)?)?);
+        }
+        
+        let mut request = self.client
+            .post(endpoint)
+            .header("Content-Type", "application/json");
+        request = match &self.auth {
+            Auth::None => request,
+            Auth::ApiKey(key) => request.header("x-api-key", key),
+            Auth::Bearer(token) => request.bearer_auth(token),
+        };
+        
+        // Unique per backend, so a response can always be matched to its request
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let response = request
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params
+            }))
+            .send()
+            .await
+            // The URL can carry the API key, and the endpoint is reported by the retry warning anyway
+            .map_err(reqwest::Error::without_url)?;
+        
+        let status = response.status();
+        let response_text = response.text().await.map_err(reqwest::Error::without_url)?;
+        if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() || status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(HttpError { status }.into());
+        }
+        if let Some(cassette) = &self.cassette {
+            if let Ok(recorded) = serde_json::from_str::<Value>(&response_text) {
+                cassette.save(method, params, &recorded)?;
+            }
+        }
+        // Some nodes answer JSON-RPC errors with a 4xx status, so only fall back to the status
+        // when the body is not a JSON-RPC response
+        match serde_json::from_str::<RpcResponse<T>>(&response_text) {
+            Ok(parsed) if parsed.id == json!(id) => Ok(parsed),
+            // Nodes that cannot parse a request answer with an error and a null id
+            Ok(parsed) if parsed.id.is_null() && parsed.error.is_some() => Ok(parsed),
+            Ok(parsed) => Err(ResponseIdMismatch { expected: id, received: parsed.id }.into()),
+            Err(_) if !status.is_success() => Err(HttpError { status }.into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl RpcBackend for HttpBackend {
+    fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+    
+    fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        self.endpoints
+            .iter()
+            .zip(&self.counters)
+            .map(|(url, (requests, retries))| EndpointStats {
+                url: self.redactor.redact(url),
+                requests: requests.load(Ordering::Relaxed),
+                retries: retries.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+    
+    fn get_slot(&self, commitment: Commitment) -> BackendFuture<'_, Slot> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Slot> = self.request("getSlot", json!([{"commitment": commitment.as_str()}])).await?;
+            
+            match parsed.result {
+                Some(slot) => Ok(slot),
+                None => Err(format!("Failed to get current slot: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_block_time(&self, slot: Slot) -> BackendFuture<'_, Option<UnixTs>> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Option<UnixTs>> = self.request("getBlockTime", json!([slot])).await?;
+            
+            match parsed.result {
+                Some(time) => Ok(time),
+                None => match parsed.error {
+                    Some(error) if error.kind() == RpcErrorKind::SlotSkipped => Ok(None),
+                    Some(error) => Err(error.into()),
+                    None => Ok(None),
+                },
+            }
+        })
+    }
+    
+    fn get_block_info(&self, slot: Slot, include_rewards: bool, commitment: Commitment) -> BackendFuture<'_, BlockInfo> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Value> = self.request("getBlock", json!([
+                slot,
+                {
+                    "commitment": commitment.as_str(),
+                    "encoding": "json",
+                    "maxSupportedTransactionVersion": 0,
+                    "transactionDetails": "none",
+                    "rewards": include_rewards
+                }
+            ])).await?;
+            
+            match parsed.result {
+                Some(block_data) => {
+                    Ok(BlockInfo {
+                        blockhash: block_data.get("blockhash")
+                            .and_then(|h| h.as_str())
+                            .map(String::from)
+                            .unwrap_or_default(),
+                        parent_slot: block_data.get("parentSlot")
+                            .and_then(|s| s.as_u64())
+                            .map(Slot)
+                            .unwrap_or_default(),
+                        block_time: block_data.get("blockTime")
+                            .and_then(|t| t.as_i64())
+                            .map(UnixTs),
+                        block_height: block_data.get("blockHeight")
+                            .and_then(|h| h.as_u64()),
+                        rewards: block_data.get("rewards")
+                            .and_then(|r| serde_json::from_value(r.clone()).ok())
+                            .unwrap_or_default(),
+                    })
+                },
+                None => match parsed.error {
+                    Some(error) => Err(error.into()),
+                    None => Err("Failed to get block info: empty response".into()),
+                },
+            }
+        })
+    }
+    
+    fn get_full_block(&self, slot: Slot, commitment: Commitment) -> BackendFuture<'_, Value> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Value> = self.request("getBlock", json!([
+                slot,
+                {
+                    "commitment": commitment.as_str(),
+                    "encoding": "json",
+                    "maxSupportedTransactionVersion": 0,
+                    "transactionDetails": "full",
+                    "rewards": true
+                }
+            ])).await?;
+            
+            match parsed.result {
+                Some(block_data) => Ok(block_data),
+                None => Err(format!("Failed to get full block: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_epoch_schedule(&self) -> BackendFuture<'_, EpochSchedule> {
+        Box::pin(async move {
+            let parsed: RpcResponse<EpochSchedule> = self.request("getEpochSchedule", json!([])).await?;
+            match parsed.result {
+                Some(schedule) => Ok(schedule),
+                None => Err(format!("Failed to get epoch schedule: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_epoch_info(&self, commitment: Commitment) -> BackendFuture<'_, EpochInfo> {
+        Box::pin(async move {
+            let parsed: RpcResponse<EpochInfo> = self.request("getEpochInfo", json!([{"commitment": commitment.as_str()}])).await?;
+            match parsed.result {
+                Some(info) => Ok(info),
+                None => Err(format!("Failed to get epoch info: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_version(&self) -> BackendFuture<'_, VersionInfo> {
+        Box::pin(async move {
+            let parsed: RpcResponse<VersionInfo> = self.request("getVersion", json!([])).await?;
+            match parsed.result {
+                Some(version) => Ok(version),
+                None => Err(format!("Failed to get version: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_genesis_hash(&self) -> BackendFuture<'_, String> {
+        Box::pin(async move {
+            let parsed: RpcResponse<String> = self.request("getGenesisHash", json!([])).await?;
+            match parsed.result {
+                Some(hash) => Ok(hash),
+                None => Err(format!("Failed to get genesis hash: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_first_available_block(&self) -> BackendFuture<'_, Slot> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Slot> = self.request("getFirstAvailableBlock", json!([])).await?;
+            match parsed.result {
+                Some(slot) => Ok(slot),
+                None => Err(format!("Failed to get first available block: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_blocks_with_limit(&self, start_slot: Slot, limit: u64, commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Vec<Slot>> = self.request("getBlocksWithLimit", json!([start_slot, limit, {"commitment": commitment.as_str()}])).await?;
+            match parsed.result {
+                Some(slots) => Ok(slots),
+                None => Err(format!("Failed to get blocks: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot, commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Vec<Slot>> = self.request("getBlocks", json!([start_slot, end_slot, {"commitment": commitment.as_str()}])).await?;
+            match parsed.result {
+                Some(slots) => Ok(slots),
+                None => Err(format!("Failed to get blocks: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_slot_leaders(&self, start_slot: Slot, limit: u64) -> BackendFuture<'_, Vec<String>> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Vec<String>> = self.request("getSlotLeaders", json!([start_slot, limit])).await?;
+            match parsed.result {
+                Some(leaders) => Ok(leaders),
+                None => Err(format!("Failed to get slot leaders: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_transaction_slot<'a>(&'a self, signature: &'a str, commitment: Commitment) -> BackendFuture<'a, (Slot, Option<UnixTs>)> {
+        Box::pin(async move {
+            // getSignatureStatuses is cheap, but only knows the slot
+            let parsed: RpcResponse<Value> = self.request("getSignatureStatuses", json!([[signature], {"searchTransactionHistory": true}])).await?;
+            if let Some(error) = parsed.error {
+                return Err(format!("Failed to get signature status: {}", error).into());
+            }
+            let status_slot = parsed.result
+                .as_ref()
+                .and_then(|r| r.get("value"))
+                .and_then(|v| v.get(0))
+                .and_then(|s| s.get("slot"))
+                .and_then(|s| s.as_u64())
+                .map(Slot);
+            
+            // getTransaction also gives us the block time, and works when the status cache misses
+            let parsed: RpcResponse<Value> = self.request("getTransaction", json!([
+                signature,
+                {"commitment": commitment.as_str(), "encoding": "json", "maxSupportedTransactionVersion": 0}
+            ])).await?;
+            let transaction = parsed.result.filter(|t| !t.is_null());
+            let block_time = transaction.as_ref()
+                .and_then(|t| t.get("blockTime"))
+                .and_then(|t| t.as_i64())
+                .map(UnixTs);
+            let transaction_slot = transaction.as_ref()
+                .and_then(|t| t.get("slot"))
+                .and_then(|s| s.as_u64())
+                .map(Slot);
+            
+            match status_slot.or(transaction_slot) {
+                Some(slot) => Ok((slot, block_time)),
+                None => Err(format!("Transaction {} not found", signature).into()),
+            }
+        })
+    }
+    
+    fn get_signatures_for_address<'a>(
+        &'a self,
+        address: &'a str,
+        before: Option<&'a str>,
+        min_context_slot: Option<Slot>,
+        limit: usize,
+        commitment: Commitment,
+    ) -> BackendFuture<'a, Vec<SignatureInfo>> {
+        Box::pin(async move {
+            let mut config = json!({ "limit": limit, "commitment": commitment.as_str() });
+            if let Some(before) = before {
+                config["before"] = json!(before);
+            }
+            if let Some(min_context_slot) = min_context_slot {
+                config["minContextSlot"] = json!(min_context_slot);
+            }
+            
+            let parsed: RpcResponse<Vec<SignatureInfo>> = self.request("getSignaturesForAddress", json!([address, config])).await?;
+            
+            match parsed.result {
+                Some(signatures) => Ok(signatures),
+                None => Err(format!("Failed to get signatures for address: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_account_info<'a>(&'a self, address: &'a str, commitment: Commitment) -> BackendFuture<'a, Option<AccountInfo>> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Value> = self.request("getAccountInfo", json!([address, {"encoding": "jsonParsed", "commitment": commitment.as_str()}])).await?;
+            match parsed.result {
+                Some(response) => Ok(serde_json::from_value(response.get("value").cloned().unwrap_or(Value::Null))?),
+                None => Err(format!("Failed to get account info: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_recent_performance_samples(&self, limit: usize) -> BackendFuture<'_, Vec<PerformanceSample>> {
+        Box::pin(async move {
+            let parsed: RpcResponse<Vec<PerformanceSample>> = self.request("getRecentPerformanceSamples", json!([limit])).await?;
+            match parsed.result {
+                Some(samples) => Ok(samples),
+                None => Err(format!("Failed to get performance samples: {:?}", parsed.error).into()),
+            }
+        })
+    }
+    
+    fn get_vote_accounts(&self, commitment: Commitment) -> BackendFuture<'_, VoteAccounts> {
+        Box::pin(async move {
+            let parsed: RpcResponse<VoteAccounts> = self.request("getVoteAccounts", json!([{"commitment": commitment.as_str()}])).await?;
+            match parsed.result {
+                Some(accounts) => Ok(accounts),
+                None => Err(format!("Failed to get vote accounts: {:?}", parsed.error).into()),
+            }
+        })
+    }
+}
+
+impl BlockFinder {
+    /// Runs one backend call inside an `rpc` span carrying the method, slot and latency, and counts it
+    async fn traced<T>(&self, method: &'static str, slot: Option<Slot>, call: BackendFuture<'_, T>) -> Result<T, Box<dyn Error>> {
+        self.rpc_calls.fetch_add(1, Ordering::Relaxed);
+        let span = tracing::info_span!(
+            "rpc",
+            rpc.method = method,
+            slot = slot.map(u64::from),
+            latency_ms = Empty,
+            error = Empty,
+            otel.status_code = Empty,
+        );
+        let stopwatch = Stopwatch::start();
+        let result = call.instrument(span.clone()).await;
+        span.record("latency_ms", stopwatch.elapsed_ms());
+        if let Err(e) = &result {
+            span.record("error", display(self.redact(&e.to_string())));
+            span.record("otel.status_code", "ERROR");
+        }
+        result
+    }
+    
+    /// Current slot at the finder's commitment. Reused for [`BlockFinderBuilder::tip_ttl`](crate::BlockFinderBuilder::tip_ttl)
+    /// after each fetch, so repeated searches do not refetch it.
+    pub async fn get_current_slot(&self) -> Result<Slot, Box<dyn Error>> {
+        let ttl_ms = self.tip_ttl.as_millis() as u64;
+        if let Some((slot, fetched_at_ms)) = self.tip.lock().ok().and_then(|tip| *tip) {
+            if self.clock.elapsed_ms() < fetched_at_ms + ttl_ms {
+                return Ok(slot);
+            }
+        }
+        
+        let _permit = self.limiter.acquire().await?;
+        let fetched_at_ms = self.clock.elapsed_ms();
+        let slot = self.traced("getSlot", None, self.backend.get_slot(self.commitment)).await?;
+        if let Ok(mut tip) = self.tip.lock() {
+            *tip = Some((slot, fetched_at_ms));
+        }
+        self.cache.observe_tip(slot);
+        Ok(slot)
+    }
+    
+    /// Whether `slot` is at or below the latest finalized slot. Finders with finalized
+    /// commitment only ever see finalized blocks, so this makes no RPC call for them.
+    pub async fn is_finalized(&self, slot: Slot) -> Result<bool, Box<dyn Error>> {
+        if self.commitment == Commitment::Finalized {
+            return Ok(true);
+        }
+        let _permit = self.limiter.acquire().await?;
+        let finalized_slot = self.traced("getSlot", None, self.backend.get_slot(Commitment::Finalized)).await?;
+        Ok(slot <= finalized_slot)
+    }
+    
+    /// Block time of `slot`, from the cache if possible. Concurrent calls for the same slot,
+    /// e.g. from parallel searches sharing this finder, are coalesced into one request.
+    pub async fn get_block_time(&self, slot: Slot) -> Result<Option<UnixTs>, Box<dyn Error>> {
+        self.block_time_lookups.fetch_add(1, Ordering::Relaxed);
+        if let Some(cached) = self.cache.get(slot) {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            if let Some(block_time) = cached {
+                self.discovery.record(slot, block_time);
+            }
+            return Ok(cached);
+        }
+        
+        let leader = match self.block_time_flights.join(slot) {
+            Flight::Leader(leader) => Some(leader),
+            Flight::Follower(receiver) => match singleflight::wait(receiver).await {
+                Some(result) => return result.map_err(SharedError::into_error),
+                // The caller making the request was cancelled, so make it ourselves
+                None => None,
+            },
+        };
+        
+        let result = self.fetch_block_time(slot).await;
+        if let Some(leader) = leader {
+            leader.finish(result.as_ref().copied().map_err(|e| SharedError::of(e.as_ref())));
+        }
+        result
+    }
+    
+    async fn fetch_block_time(&self, slot: Slot) -> Result<Option<UnixTs>, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        let block_time = if self.chain.block_time_from_get_block {
+            match self.traced("getBlock", Some(slot), self.backend.get_block_info(slot, false, self.commitment)).await {
+                Ok(block) => block.block_time,
+                Err(e) if RpcErrorKind::of(e.as_ref()) == RpcErrorKind::SlotSkipped => None,
+                Err(e) => return Err(e),
+            }
+        } else {
+            self.traced("getBlockTime", Some(slot), self.backend.get_block_time(slot)).await?
+        };
+        self.cache.insert(slot, block_time);
+        if let Some(block_time) = block_time {
+            self.discovery.record(slot, block_time);
+        }
+        Ok(block_time)
+    }
+    
+    pub async fn get_block_info(&self, slot: Slot, include_rewards: bool) -> Result<BlockInfo, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getBlock", Some(slot), self.backend.get_block_info(slot, include_rewards, self.commitment)).await
+    }
+    
+    pub async fn get_full_block(&self, slot: Slot) -> Result<Value, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getBlock", Some(slot), self.backend.get_full_block(slot, self.commitment)).await
+    }
+    
+    pub async fn get_epoch_schedule(&self) -> Result<EpochSchedule, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getEpochSchedule", None, self.backend.get_epoch_schedule()).await
+    }
+    
+    pub async fn get_epoch_info(&self) -> Result<EpochInfo, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getEpochInfo", None, self.backend.get_epoch_info(self.commitment)).await
+    }
+    
+    pub async fn get_version(&self) -> Result<VersionInfo, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getVersion", None, self.backend.get_version()).await
+    }
+    
+    pub async fn get_genesis_hash(&self) -> Result<String, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getGenesisHash", None, self.backend.get_genesis_hash()).await
+    }
+    
+    /// Oldest block the endpoint still has, 0 on archival nodes
+    pub async fn get_first_available_block(&self) -> Result<Slot, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getFirstAvailableBlock", None, self.backend.get_first_available_block()).await
+    }
+    
+    /// Returns up to `limit` produced (non-skipped) slots starting at `start_slot`.
+    pub async fn get_blocks_with_limit(&self, start_slot: Slot, limit: u64) -> Result<Vec<Slot>, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getBlocksWithLimit", Some(start_slot), self.backend.get_blocks_with_limit(start_slot, limit, self.commitment)).await
+    }
+    
+    /// Returns the produced (non-skipped) slots between `start_slot` and `end_slot`, inclusive.
+    pub async fn get_blocks(&self, start_slot: Slot, end_slot: Slot) -> Result<Vec<Slot>, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getBlocks", Some(start_slot), self.backend.get_blocks(start_slot, end_slot, self.commitment)).await
+    }
+    
+    /// Returns the leader identity of each of the `limit` slots starting at `start_slot`. Nodes
+    /// only know the leader schedules of recent epochs.
+    pub async fn get_slot_leaders(&self, start_slot: Slot, limit: u64) -> Result<Vec<String>, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getSlotLeaders", Some(start_slot), self.backend.get_slot_leaders(start_slot, limit)).await
+    }
+    
+    /// Resolves a transaction signature to the slot it landed in and, when known, its block time.
+    pub async fn get_transaction_slot(&self, signature: &str) -> Result<(Slot, Option<UnixTs>), Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getTransaction", None, self.backend.get_transaction_slot(signature, self.commitment)).await
+    }
+    
+    /// Fetches one page of signatures for an address, newest first, starting before `before` when given.
+    pub async fn get_signatures_for_address(
+        &self,
+        address: &str,
+        before: Option<&str>,
+        min_context_slot: Option<Slot>,
+        limit: usize,
+    ) -> Result<Vec<SignatureInfo>, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getSignaturesForAddress", min_context_slot, self.backend.get_signatures_for_address(address, before, min_context_slot, limit, self.commitment)).await
+    }
+    
+    /// Returns `None` when the account does not exist.
+    pub async fn get_account_info(&self, address: &str) -> Result<Option<AccountInfo>, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getAccountInfo", None, self.backend.get_account_info(address, self.commitment)).await
+    }
+    
+    /// Returns up to `limit` recent performance samples, newest first.
+    pub async fn get_recent_performance_samples(&self, limit: usize) -> Result<Vec<PerformanceSample>, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getRecentPerformanceSamples", None, self.backend.get_recent_performance_samples(limit)).await
+    }
+    
+    /// Vote accounts of the cluster with their stake in the current epoch
+    pub async fn get_vote_accounts(&self) -> Result<VoteAccounts, Box<dyn Error>> {
+        let _permit = self.limiter.acquire().await?;
+        self.traced("getVoteAccounts", None, self.backend.get_vote_accounts(self.commitment)).await
+    }
+}
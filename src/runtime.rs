@@ -0,0 +1,64 @@
+//! Platform shims for timers and the wall clock
+//!
+//! Native builds use Tokio and `SystemTime`. On `wasm32` neither is
+//! available, so sleeping goes through browser timers and the current time
+//! comes from `Date.now()`.
+
+use std::error::Error;
+use std::time::Duration;
+
+use crate::types::UnixTs;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
+
+/// Current Unix time in seconds
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn unix_now() -> Result<UnixTs, Box<dyn Error>> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    Ok(UnixTs(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64))
+}
+
+/// Current Unix time in seconds
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn unix_now() -> Result<UnixTs, Box<dyn Error>> {
+    Ok(UnixTs((js_sys::Date::now() / 1000.0) as i64))
+}
+
+/// Measures elapsed time, e.g. RPC latency for tracing spans. Follows Tokio's clock, so it
+/// stands still while Tokio's time is paused.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct Stopwatch(tokio::time::Instant);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Stopwatch(tokio::time::Instant::now())
+    }
+    
+    pub(crate) fn elapsed_ms(&self) -> u64 {
+        self.0.elapsed().as_millis() as u64
+    }
+}
+
+/// Measures elapsed wall time (`Instant` is not available in the browser)
+#[cfg(target_arch = "wasm32")]
+pub(crate) struct Stopwatch(f64);
+
+#[cfg(target_arch = "wasm32")]
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Stopwatch(js_sys::Date::now())
+    }
+    
+    pub(crate) fn elapsed_ms(&self) -> u64 {
+        (js_sys::Date::now() - self.0).max(0.0) as u64
+    }
+}
@@ -0,0 +1,42 @@
+//! Serde types of the CLI's JSON and YAML output
+//!
+//! Every record carries a `schema_version`. Within one version fields are
+//! only ever added, so parsers should ignore fields they do not know.
+//! Removing or renaming a field, or changing its type or meaning, bumps
+//! [`SCHEMA_VERSION`]. Consumers can deserialize the output with these types
+//! instead of redefining them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockMatch, Slot, UnixTs};
+
+/// Version of the records written by `--output json` and `--output yaml`
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A search result, as written by `schedule`, `batch` and `--stdin`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockMatchRecord {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub block: BlockMatch,
+}
+
+impl From<BlockMatch> for BlockMatchRecord {
+    fn from(block: BlockMatch) -> Self {
+        BlockMatchRecord { schema_version: SCHEMA_VERSION, block }
+    }
+}
+
+/// A produced block and its block time, as written by `dump-epoch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotTimeRecord {
+    pub schema_version: u32,
+    pub slot: Slot,
+    pub block_time: UnixTs,
+}
+
+impl SlotTimeRecord {
+    pub fn new(slot: Slot, block_time: UnixTs) -> Self {
+        SlotTimeRecord { schema_version: SCHEMA_VERSION, slot, block_time }
+    }
+}
@@ -0,0 +1,973 @@
+//! Timestamp → slot search
+
+use std::error::Error;
+use std::future::Future;
+use std::path::Path;
+use std::pin::pin;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use futures::future::{self, join_all, Either};
+use tracing::field::Empty;
+use tracing::Instrument;
+
+use crate::finder::BlockFinder;
+use crate::rpc::{classify_error, ErrorClass, RpcErrorKind};
+use crate::search_state::{Direction, NextProbe, Outcome, SearchState};
+use crate::types::{BlockMatch, EndpointStats, SearchDiagnostics, Slot, UnixTs};
+
+/// Bounds of the adaptive nearby window. A larger configured probe window raises the upper bound.
+const NEARBY_WINDOW_MIN: u64 = 4;
+const NEARBY_WINDOW_MAX: u64 = 160;
+
+/// Upper bound of the nearby window when the produced slots are listed with `getBlocks`, where
+/// an empty window costs a single call however wide it is
+const LISTED_WINDOW_MAX: u64 = 10_240;
+
+/// Produced slots fetched per `getBlocksWithLimit` call while scanning for a block time
+const SCAN_BATCH: u64 = 16;
+
+/// Targets at most this long ago get their first guess from the live slot rate instead of the
+/// calibration, since the rate of the last hour drifts from the actual
+/// history further back
+const LIVE_RATE_MAX_AGE_SECS: i64 = 6 * 3_600;
+
+/// Fewest slots either side of the live slot rate guess that a search probes first
+const LIVE_RATE_MIN_MARGIN: u64 = 64;
+
+/// Distance either side of the calibrated guess that a search probes first. An era table
+/// measured with [`DEFAULT_CALIBRATION_SAMPLES`](crate::calibration::DEFAULT_CALIBRATION_SAMPLES)
+/// over mainnet is usually closer than that.
+const CALIBRATION_MARGIN_SECS: i64 = 3_600;
+
+/// Snapshot of a running search, kept so an interrupted search can still report something useful
+/// and, when a checkpoint file is configured, be resumed by a later invocation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchProgress {
+    pub target_timestamp: UnixTs,
+    pub low_slot: Slot,
+    pub high_slot: Slot,
+    pub closest_slot: Slot,
+    pub closest_time_diff: i64,
+}
+
+impl SearchProgress {
+    pub fn new(target_timestamp: UnixTs, low_slot: Slot, high_slot: Slot) -> Self {
+        SearchProgress {
+            target_timestamp,
+            low_slot,
+            high_slot,
+            closest_slot: Slot(0),
+            closest_time_diff: i64::MAX,
+        }
+    }
+    
+    /// Best block found so far at or before the target: (slot, block time)
+    pub fn best_candidate(&self) -> Option<(Slot, UnixTs)> {
+        let block_time = self.target_timestamp.checked_add(self.closest_time_diff)?;
+        (self.closest_time_diff <= 0).then_some((self.closest_slot, block_time))
+    }
+}
+
+/// Limit that stopped a search before it converged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StopReason {
+    /// The finder made its [`max_rpc_calls`](crate::BlockFinderBuilder::max_rpc_calls)
+    RpcBudget(u64),
+    /// The search ran for the finder's [`deadline`](crate::BlockFinderBuilder::deadline)
+    Deadline(Duration),
+    /// The search took the finder's [`max_iterations`](crate::BlockFinderBuilder::max_iterations) steps
+    MaxIterations(u32),
+}
+
+/// Error returned when a [`StopReason`] stops a search. The progress holds the best
+/// candidate found so far and can be passed to [`BlockFinder::resume_slot_search`].
+#[derive(Debug, Clone)]
+pub struct SearchStopped {
+    pub reason: StopReason,
+    pub progress: SearchProgress,
+}
+
+impl SearchStopped {
+    /// Returns the error as a `SearchStopped`, if a limit stopped the search
+    pub fn of<'a>(error: &'a (dyn Error + 'static)) -> Option<&'a SearchStopped> {
+        error.downcast_ref::<SearchStopped>()
+    }
+}
+
+impl std::fmt::Display for SearchStopped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            StopReason::RpcBudget(calls) => write!(f, "Search stopped after its budget of {} RPC calls", calls)?,
+            StopReason::Deadline(deadline) => write!(f, "Search stopped at its deadline of {:?}", deadline)?,
+            StopReason::MaxIterations(iterations) => write!(f, "Search stopped after {} iterations without converging (a bug or an unusual chain state?)", iterations)?,
+        }
+        write!(f, "; slots {} - {} were left to search", self.progress.low_slot, self.progress.high_slot)
+    }
+}
+
+impl Error for SearchStopped {}
+
+/// Error returned for a target older than the first block of the chain
+#[derive(Debug, Clone)]
+pub struct BeforeGenesis {
+    pub target_timestamp: UnixTs,
+    pub chain: String,
+    /// Oldest block with a block time: slot 0, unless the node or the chain profile starts
+    /// later or the earliest blocks carry no block time
+    pub first_slot: Slot,
+    pub first_block_time: UnixTs,
+}
+
+impl std::fmt::Display for BeforeGenesis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let earlier = self.first_block_time - self.target_timestamp;
+        if self.first_slot == Slot(0) {
+            write!(f, "Timestamp {} is {} seconds before the genesis of {}: slot 0 was produced at {}", self.target_timestamp, earlier, self.chain, self.first_block_time)
+        } else {
+            write!(f, "Timestamp {} is {} seconds before the oldest block of {} with a block time: slot {} was produced at {}", self.target_timestamp, earlier, self.chain, self.first_slot, self.first_block_time)
+        }
+    }
+}
+
+impl Error for BeforeGenesis {}
+
+/// Writes the checkpoint atomically so an interrupted write never leaves a corrupt file behind.
+fn write_checkpoint(path: &Path, progress: &SearchProgress) -> Result<(), Box<dyn Error>> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    std::fs::write(&temp_path, serde_json::to_string_pretty(progress)?)?;
+    std::fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+pub fn read_checkpoint(path: impl AsRef<Path>) -> Result<SearchProgress, Box<dyn Error>> {
+    let path = path.as_ref();
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("❌ Cannot read checkpoint {}: {}", path.display(), e))?;
+    let progress: SearchProgress = serde_json::from_str(&contents)?;
+    if SearchState::new(&progress).is_none() {
+        return Err(format!("❌ Invalid checkpoint {}: its slot window or closest block time overflows", path.display()).into());
+    }
+    Ok(progress)
+}
+
+/// Of a block before a skipped slot and one after it, the one that narrows a search for `target`
+/// the most: the latest at or before the target, else the earliest after it
+fn closest_to_target(before: Option<(Slot, UnixTs)>, after: Option<(Slot, UnixTs)>, target: UnixTs) -> Option<(Slot, UnixTs)> {
+    match (before, after) {
+        (_, Some(after)) if after.1 <= target => Some(after),
+        (before, after) => before.or(after),
+    }
+}
+
+/// How precisely a block's timestamp pins down wall-clock time
+#[derive(Debug, Default)]
+pub struct TimestampUncertainty {
+    pub previous: Option<(Slot, UnixTs)>,
+    pub next: Option<(Slot, UnixTs)>,
+    pub same_timestamp_blocks: usize,
+}
+
+impl BlockFinder {
+    /// The limit that stops a search started at `started_ms` on the clock after `iterations` steps, if any
+    fn search_limit(&self, started_ms: u64, iterations: u32) -> Option<StopReason> {
+        if iterations >= self.max_iterations {
+            return Some(StopReason::MaxIterations(iterations));
+        }
+        if let Some(deadline) = self.deadline.filter(|deadline| self.clock.elapsed_ms() - started_ms >= deadline.as_millis() as u64) {
+            return Some(StopReason::Deadline(deadline));
+        }
+        self.max_rpc_calls.filter(|&calls| self.rpc_calls() >= calls).map(StopReason::RpcBudget)
+    }
+    
+    /// Runs `probe`, or returns `None` if the deadline of the search passes first
+    async fn before_deadline<T>(&self, started_ms: u64, probe: impl Future<Output = T>) -> Option<T> {
+        let Some(deadline) = self.deadline else {
+            return Some(probe.await);
+        };
+        let remaining = deadline.saturating_sub(Duration::from_millis(self.clock.elapsed_ms() - started_ms));
+        match future::select(pin!(probe), self.clock.sleep(remaining)).await {
+            Either::Left((value, _)) => Some(value),
+            Either::Right(_) => None,
+        }
+    }
+    
+    fn record_progress(&self, progress: &SearchProgress) {
+        if let Ok(mut current) = self.progress.lock() {
+            *current = Some(progress.clone());
+        }
+        
+        if let Some(path) = &self.checkpoint_file {
+            if let Err(e) = write_checkpoint(path, progress) {
+                tracing::warn!("Failed to write checkpoint {}: {}", path.display(), e);
+            }
+        }
+    }
+    
+    /// Finds the latest block at or before `target_timestamp` and fetches its details.
+    ///
+    /// With [`best_effort`](crate::BlockFinderBuilder::best_effort), a search stopped by a limit
+    /// returns its best candidate marked [`approximate`](BlockMatch::approximate) instead of failing.
+    ///
+    /// The match carries [`SearchDiagnostics`] for the search.
+    pub async fn find_block_by_timestamp(&self, target_timestamp: UnixTs) -> Result<BlockMatch, Box<dyn Error>> {
+        self.find_block(target_timestamp, None).await
+    }
+    
+    /// Like [`find_block_by_timestamp`](Self::find_block_by_timestamp), but only searches between
+    /// `low_slot` and `high_slot`. The caller is responsible for the target block actually lying
+    /// inside that range.
+    pub async fn find_block_by_timestamp_in_range(
+        &self,
+        target_timestamp: UnixTs,
+        low_slot: Slot,
+        high_slot: Slot,
+    ) -> Result<BlockMatch, Box<dyn Error>> {
+        self.find_block(target_timestamp, Some((low_slot, high_slot))).await
+    }
+    
+    async fn find_block(&self, target_timestamp: UnixTs, range: Option<(Slot, Slot)>) -> Result<BlockMatch, Box<dyn Error>> {
+        let started_ms = self.clock.elapsed_ms();
+        let start = self.diagnostic_counters();
+        let mut diagnostics = SearchDiagnostics::default();
+        let result = match range {
+            Some((low_slot, high_slot)) => self.search_in_range(target_timestamp, low_slot, high_slot, &mut diagnostics).await,
+            None => self.search_slot(target_timestamp, &mut diagnostics).await,
+        };
+        let (slot, approximate) = match result {
+            Ok(slot) => (slot, false),
+            Err(e) => match self.best_effort_slot(e.as_ref()) {
+                Some(slot) => (slot, true),
+                None => return Err(e),
+            },
+        };
+        let block_info = self.get_block_info(slot, false).await?;
+        let finalized = self.is_finalized(slot).await?;
+        
+        let end = self.diagnostic_counters();
+        diagnostics.probes = end.probes - start.probes;
+        diagnostics.cache_hits = end.cache_hits - start.cache_hits;
+        diagnostics.rpc_calls = end.rpc_calls - start.rpc_calls;
+        diagnostics.retries = end.retries - start.retries;
+        diagnostics.endpoints = end.endpoints
+            .into_iter()
+            .zip(start.endpoints)
+            .map(|(end, start)| EndpointStats { requests: end.requests - start.requests, retries: end.retries - start.retries, ..end })
+            .filter(|endpoint| endpoint.requests > 0)
+            .collect();
+        diagnostics.duration_ms = self.clock.elapsed_ms() - started_ms;
+        
+        Ok(BlockMatch {
+            approximate,
+            finalized: Some(finalized),
+            diagnostics: Some(diagnostics),
+            ..BlockMatch::new(slot, &block_info, Some(target_timestamp))
+        })
+    }
+    
+    /// Current totals of the finder-wide counters that go into [`SearchDiagnostics`]
+    fn diagnostic_counters(&self) -> SearchDiagnostics {
+        let endpoints = self.endpoint_stats();
+        SearchDiagnostics {
+            probes: self.block_time_lookups.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits(),
+            rpc_calls: self.rpc_calls(),
+            retries: endpoints.iter().map(|endpoint| endpoint.retries).sum(),
+            endpoints,
+            ..SearchDiagnostics::default()
+        }
+    }
+    
+    /// The best candidate to report instead of `error`, if best-effort results are enabled
+    /// and a limit stopped the search after finding a block before the target
+    pub fn best_effort_slot(&self, error: &(dyn Error + 'static)) -> Option<Slot> {
+        if !self.best_effort {
+            return None;
+        }
+        SearchStopped::of(error)?.progress.best_candidate().map(|(slot, _)| slot)
+    }
+    
+    pub async fn get_slot_by_timestamp_optimized(&self, target_timestamp: UnixTs) -> Result<Slot, Box<dyn Error>> {
+        self.search_slot(target_timestamp, &mut SearchDiagnostics::default()).await
+    }
+    
+    async fn search_slot(&self, target_timestamp: UnixTs, diagnostics: &mut SearchDiagnostics) -> Result<Slot, Box<dyn Error>> {
+        if let Some(genesis) = self.chain.genesis_timestamp.filter(|genesis| target_timestamp < *genesis) {
+            return Err(self.before_genesis(target_timestamp, Slot(0), genesis).into());
+        }
+        self.consult_remote_index(target_timestamp).await;
+        // Without a known block before the target, make sure there is one at all instead of
+        // letting the search fail somewhere near the first slot
+        if self.discovery.bounds(target_timestamp).0.is_none() {
+            if let Ok(Some((slot, block_time))) = self.first_block().await {
+                if target_timestamp < block_time {
+                    return Err(self.before_genesis(target_timestamp, slot, block_time).into());
+                }
+            }
+        }
+        
+        // Start with current slot
+        let current_slot = self.get_current_slot().await?;
+        tracing::info!("Current slot: {}", current_slot);
+        
+        self.search_in_range(target_timestamp, self.chain.first_slot(), current_slot, diagnostics).await
+    }
+    
+    /// The oldest block searches can reach and its block time, `None` if that block has none
+    async fn first_block(&self) -> Result<Option<(Slot, UnixTs)>, Box<dyn Error>> {
+        let Some(&slot) = self.get_blocks_with_limit(self.chain.first_slot(), 1).await?.first() else {
+            return Ok(None);
+        };
+        Ok(self.get_block_time(slot).await?.map(|block_time| (slot, block_time)))
+    }
+    
+    fn before_genesis(&self, target_timestamp: UnixTs, first_slot: Slot, first_block_time: UnixTs) -> BeforeGenesis {
+        BeforeGenesis { target_timestamp, chain: self.chain.name.clone(), first_slot, first_block_time }
+    }
+    
+    /// Runs the timestamp search between `low_slot` and `high_slot` only.
+    /// The caller is responsible for the target block actually lying inside that range.
+    ///
+    /// Block times already seen by this finder (see [`BlockFinder::discovery`]) narrow the
+    /// range further, so concurrent or repeated searches start from each other's probes.
+    pub async fn get_slot_by_timestamp_in_range(
+        &self,
+        target_timestamp: UnixTs,
+        low_slot: Slot,
+        high_slot: Slot,
+    ) -> Result<Slot, Box<dyn Error>> {
+        self.search_in_range(target_timestamp, low_slot, high_slot, &mut SearchDiagnostics::default()).await
+    }
+    
+    async fn search_in_range(
+        &self,
+        target_timestamp: UnixTs,
+        low_slot: Slot,
+        high_slot: Slot,
+        diagnostics: &mut SearchDiagnostics,
+    ) -> Result<Slot, Box<dyn Error>> {
+        let mut progress = SearchProgress::new(target_timestamp, low_slot, high_slot);
+        self.narrow_to_known_blocks(&mut progress);
+        if self.probe_first_guess(&progress).await {
+            self.narrow_to_known_blocks(&mut progress);
+        }
+        if (progress.low_slot, progress.high_slot) != (low_slot, high_slot) {
+            tracing::info!("Known blocks narrow the search to slots {}..={}", progress.low_slot, progress.high_slot);
+        }
+        self.traced_search(progress, diagnostics).await
+    }
+    
+    /// Shrinks the window of `progress` to the nearest blocks around the target that this
+    /// finder has seen
+    fn narrow_to_known_blocks(&self, progress: &mut SearchProgress) {
+        let (before, after) = self.discovery.bounds(progress.target_timestamp);
+        // The block before the target is the answer unless a later one is found
+        if let Some((slot, block_time)) = before.filter(|(slot, _)| (progress.low_slot..=progress.high_slot).contains(slot)) {
+            progress.low_slot = slot;
+            progress.closest_slot = slot;
+            progress.closest_time_diff = block_time - progress.target_timestamp;
+        }
+        if let Some((slot, _)) = after.filter(|(slot, _)| (progress.low_slot..=progress.high_slot).contains(slot)) {
+            progress.high_slot = slot;
+        }
+    }
+    
+    /// Probes the slots either side of where the target is expected, so that the binary search
+    /// starts from the window between them. Their block times land in the discovery map like
+    /// every probe. Returns whether anything was probed.
+    async fn probe_first_guess(&self, progress: &SearchProgress) -> bool {
+        // Known blocks already narrowed the window more than any guess would
+        if progress.high_slot - progress.low_slot <= LIVE_RATE_MIN_MARGIN.saturating_mul(4) {
+            return false;
+        }
+        let guess = match self.live_rate_guess(progress.target_timestamp).await {
+            Some(guess) => Some(guess),
+            None => self.calibrated_guess(progress.target_timestamp),
+        };
+        let Some((guess, margin, source)) = guess else {
+            return false;
+        };
+        // Not worth two probes when the window is already about that narrow
+        if progress.high_slot - progress.low_slot <= margin.saturating_mul(4) {
+            return false;
+        }
+        let guess = guess.clamp(progress.low_slot, progress.high_slot);
+        let edges = [guess.saturating_sub(margin).max(progress.low_slot), guess.saturating_add(margin).min(progress.high_slot)];
+        tracing::info!("{} expects timestamp {} near slot {}, probing slots {} and {}", source, progress.target_timestamp, guess, edges[0], edges[1]);
+        // Failed or skipped edges just leave the window as it was
+        join_all(edges.map(|slot| self.get_block_time(slot))).await;
+        true
+    }
+    
+    /// Where the [`live_slot_rate`](Self::live_slot_rate) expects a target at most
+    /// [`LIVE_RATE_MAX_AGE_SECS`] ago, counting back from the latest block. The margin
+    /// covers every slot since the target running as slow or as fast as the slowest or fastest
+    /// sampled minute.
+    async fn live_rate_guess(&self, target_timestamp: UnixTs) -> Option<(Slot, u64, &'static str)> {
+        if self.now().ok()? - target_timestamp > LIVE_RATE_MAX_AGE_SECS {
+            return None;
+        }
+        let (tip_slot, tip_time) = self.latest_block_time().await.ok()?;
+        let rate = self.live_slot_rate().await.ok()?;
+        let elapsed_ms = (tip_time - target_timestamp).max(0) as f64 * 1000.0;
+        let slots = |ms_per_slot: f64| (elapsed_ms / ms_per_slot).round() as u64;
+        let margin = (slots(rate.fastest_ms).saturating_sub(slots(rate.slowest_ms)) / 2).max(LIVE_RATE_MIN_MARGIN);
+        Some((tip_slot.saturating_sub(slots(rate.mean_ms)), margin, "Live slot rate"))
+    }
+    
+    /// Where the finder's [`Calibration`](crate::calibration::Calibration) expects the target,
+    /// with a margin of [`CALIBRATION_MARGIN_SECS`]
+    fn calibrated_guess(&self, target_timestamp: UnixTs) -> Option<(Slot, u64, &'static str)> {
+        let guess = self.calibration.slot_at(target_timestamp)?;
+        Some((guess, self.calibration.slots_in(guess, CALIBRATION_MARGIN_SECS, &self.chain), "Calibration"))
+    }
+    
+    /// Continues a timestamp search from a previously recorded state.
+    pub async fn resume_slot_search(&self, progress: SearchProgress) -> Result<Slot, Box<dyn Error>> {
+        self.traced_search(progress, &mut SearchDiagnostics::default()).await
+    }
+    
+    async fn traced_search(&self, progress: SearchProgress, diagnostics: &mut SearchDiagnostics) -> Result<Slot, Box<dyn Error>> {
+        let span = tracing::info_span!(
+            "search",
+            target_timestamp = progress.target_timestamp.0,
+            low_slot = progress.low_slot.0,
+            high_slot = progress.high_slot.0,
+            slot = Empty,
+        );
+        let result = self.binary_search(progress, diagnostics).instrument(span.clone()).await;
+        if let Ok(slot) = &result {
+            span.record("slot", slot.0);
+        }
+        result
+    }
+    
+    async fn binary_search(&self, progress: SearchProgress, diagnostics: &mut SearchDiagnostics) -> Result<Slot, Box<dyn Error>> {
+        let target_timestamp = progress.target_timestamp;
+        let mut state = SearchState::new(&progress).ok_or("❌ Invalid search progress: its slot window or closest block time overflows")?;
+        let mut nearby_window = self.probe_window;
+        let started_ms = self.clock.elapsed_ms();
+        
+        // Binary search to find the slot with timestamp closest to target. `state` decides what
+        // to probe next; this loop only reads block times and enforces the limits.
+        tracing::info!("Starting optimized binary search for timestamp: {}", target_timestamp);
+        
+        let mut next = state.next_probe();
+        let outcome = loop {
+            (diagnostics.final_low_slot, diagnostics.final_high_slot) = state.window();
+            match next {
+                NextProbe::Slot(slot) => {
+                    if let Some(reason) = self.search_limit(started_ms, diagnostics.iterations) {
+                        return Err(Box::new(SearchStopped { reason, progress: state.progress() }));
+                    }
+                    if diagnostics.iterations > 0 {
+                        // Much shorter delay since we're using parallel requests
+                        self.clock.sleep(self.probe_delay).await;
+                    }
+                    diagnostics.iterations += 1;
+                    
+                    let Some(probe) = self.before_deadline(started_ms, self.get_block_time(slot)).await else {
+                        return Err(Box::new(SearchStopped { reason: StopReason::Deadline(self.deadline.unwrap_or_default()), progress: state.progress() }));
+                    };
+                    next = match probe {
+                        Ok(Some(block_time)) => {
+                            tracing::info!("Slot {} has timestamp {}", slot, block_time);
+                            state.observe(slot, Some(block_time))
+                        }
+                        Ok(None) => state.observe(slot, None),
+                        // Still unavailable after trying every endpoint, so probe its neighbors like a skipped slot
+                        Err(e) if RpcErrorKind::of(e.as_ref()) == RpcErrorKind::BlockNotAvailable => state.observe(slot, None),
+                        // Invalid params, rejected API keys and the like fail for every slot
+                        Err(e) if classify_error(e.as_ref()) == ErrorClass::Fatal => return Err(e),
+                        Err(e) => match RpcErrorKind::of(e.as_ref()) {
+                            RpcErrorKind::Purged => {
+                                // Older blocks are gone from this node's ledger, so only later slots can be read
+                                tracing::info!("Slot {} is no longer stored by the node, searching later slots: {}", slot, e);
+                                state.skip(slot, Direction::Later)
+                            }
+                            RpcErrorKind::NodeBehind => {
+                                // Every endpoint is behind this slot, so stay below it
+                                tracing::info!("Node has not reached slot {} yet, searching earlier slots: {}", slot, e);
+                                state.skip(slot, Direction::Earlier)
+                            }
+                            _ => {
+                                tracing::warn!("Error getting block time for slot {}: {}", slot, e);
+                                // Try to continue by skipping this slot
+                                state.skip(slot, Direction::Later)
+                            }
+                        },
+                    };
+                }
+                NextProbe::Nearby(center) => {
+                    // Skip slots with no timestamp and check the nearest produced blocks instead
+                    tracing::info!("No timestamp for slot {}, checking the blocks produced nearby", center);
+                    
+                    let nearby = self.find_nearby_slot_with_timestamp(center, target_timestamp, &mut nearby_window);
+                    let Some(nearby) = self.before_deadline(started_ms, nearby).await else {
+                        return Err(Box::new(SearchStopped { reason: StopReason::Deadline(self.deadline.unwrap_or_default()), progress: state.progress() }));
+                    };
+                    if let Some((found_slot, found_time)) = nearby {
+                        tracing::info!("Found timestamp {} at nearby slot {}", found_time, found_slot);
+                    }
+                    next = state.observe_nearby(center, nearby);
+                }
+                NextProbe::Exact(slot) => {
+                    self.record_progress(&state.progress());
+                    // But first, find the highest slot with this exact timestamp!
+                    return self.resolve_tie(slot, target_timestamp).await;
+                }
+                NextProbe::Done(outcome) => break outcome,
+            }
+            
+            if !matches!(next, NextProbe::Nearby(_)) {
+                self.record_progress(&state.progress());
+            }
+        };
+        
+        match outcome {
+            Outcome::Before(slot, block_time) => self.resolve_lower_tie(slot, block_time).await,
+            Outcome::NotFound => Err("Could not find a suitable block".into()),
+            // The closest block is after the target timestamp, so we need the previous block
+            Outcome::After(closest_slot, closest_time) => {
+                // Find the previous block with a valid timestamp
+                let mut slot = closest_slot;
+                while slot > Slot(0) {
+                    slot -= 1;
+                    if let Ok(Some(found_time)) = self.get_block_time(slot).await {
+                        if found_time == target_timestamp {
+                            return self.resolve_tie(slot, target_timestamp).await;
+                        } else if found_time < target_timestamp {
+                            return self.resolve_lower_tie(slot, found_time).await;
+                        }
+                    }
+                }
+                // Every block up to the closest one is after the target
+                Err(self.before_genesis(target_timestamp, closest_slot, closest_time).into())
+            }
+        }
+    }
+    
+    /// Finds the block closest to the target around `center_slot`, a slot without a block time.
+    ///
+    /// One `getBlocks` call lists the produced slots of the window, so only the nearest produced
+    /// block on either side of `center_slot` has its block time read, however many of the slots
+    /// around it were skipped. Block times never decrease, so those two are the only blocks of
+    /// the window that can bound the search. `window` adapts like in
+    /// [`find_nearby_slot_with_timestamp_parallel`](Self::find_nearby_slot_with_timestamp_parallel),
+    /// but an empty window costs a single call and may grow up to [`LISTED_WINDOW_MAX`]. Nodes
+    /// that cannot list blocks fall back to
+    /// [`find_nearby_slot_across_rotations`](Self::find_nearby_slot_across_rotations), then to
+    /// probing every slot.
+    async fn find_nearby_slot_with_timestamp(
+        &self,
+        center_slot: Slot,
+        target_timestamp: UnixTs,
+        window: &mut u64,
+    ) -> Option<(Slot, UnixTs)> {
+        let min_window = NEARBY_WINDOW_MIN.min(self.probe_window);
+        let max_window = LISTED_WINDOW_MAX.max(self.probe_window);
+        loop {
+            let listed = self.get_blocks(center_slot.saturating_sub(*window), center_slot.saturating_add(*window)).await;
+            let blocks = match listed {
+                Ok(blocks) => blocks,
+                Err(e) => {
+                    tracing::warn!("Cannot list the blocks around slot {}: {}", center_slot, e);
+                    if let Some(found) = self.find_nearby_slot_across_rotations(center_slot, target_timestamp, *window).await {
+                        return Some(found);
+                    }
+                    tracing::debug!("Probing every slot around slot {}", center_slot);
+                    *window = (*window).min(NEARBY_WINDOW_MAX.max(self.probe_window));
+                    return self.find_nearby_slot_with_timestamp_parallel(center_slot, target_timestamp, window).await;
+                }
+            };
+            let Some(nearest) = blocks.iter().map(|slot| slot.0.abs_diff(center_slot.0)).min() else {
+                if *window >= max_window {
+                    return None;
+                }
+                *window = (*window * 2).min(max_window);
+                continue;
+            };
+            *window = (nearest * 2).clamp(min_window, max_window);
+            
+            let (earlier, later) = blocks.split_at(blocks.partition_point(|slot| *slot < center_slot));
+            let (before, after) = future::join(self.first_with_block_time(earlier.iter().rev()), self.first_with_block_time(later.iter())).await;
+            return closest_to_target(before, after, target_timestamp);
+        }
+    }
+    
+    /// Finds a block around `center_slot` by jumping from one leader rotation to the next.
+    ///
+    /// A leader is scheduled for several consecutive slots, and one that is offline skips all
+    /// of them, so the slots next to a skipped one are likely skipped too. Only the slots where
+    /// the leader changes within `window` slots of `center_slot` are probed: the last slot of
+    /// each earlier leader going back, and the first slot of each later leader going forward,
+    /// until one on each side has a block time. `None` when the node does not know the leaders,
+    /// which it only does for recent epochs, or none of those slots has a block time.
+    async fn find_nearby_slot_across_rotations(
+        &self,
+        center_slot: Slot,
+        target_timestamp: UnixTs,
+        window: u64,
+    ) -> Option<(Slot, UnixTs)> {
+        let start = center_slot.saturating_sub(window);
+        let leaders = match self.get_slot_leaders(start, center_slot.saturating_add(window) - start + 1).await {
+            Ok(leaders) => leaders,
+            Err(e) => {
+                tracing::warn!("Cannot get the leaders around slot {}: {}", center_slot, e);
+                return None;
+            }
+        };
+        let center = usize::try_from(center_slot - start).ok().filter(|center| *center < leaders.len())?;
+        let leader_changes = |index: &usize| leaders[*index] != leaders[*index - 1];
+        let earlier: Vec<Slot> = (1..=center).rev().filter(leader_changes).map(|index| start + (index as u64 - 1)).collect();
+        let later: Vec<Slot> = (center + 1..leaders.len()).filter(leader_changes).map(|index| start + index as u64).collect();
+        tracing::debug!("Probing the {} leader rotations around slot {}", earlier.len() + later.len(), center_slot);
+        
+        let (before, after) = future::join(self.first_with_block_time(earlier.iter()), self.first_with_block_time(later.iter())).await;
+        closest_to_target(before, after, target_timestamp)
+    }
+    
+    /// The first of `slots` that has a block time
+    async fn first_with_block_time(&self, slots: impl Iterator<Item = &Slot>) -> Option<(Slot, UnixTs)> {
+        for slot in slots {
+            if let Ok(Some(block_time)) = self.get_block_time(*slot).await {
+                return Some((*slot, block_time));
+            }
+        }
+        None
+    }
+    
+    /// Probes the slots around `center_slot` in parallel for the block closest to the target.
+    ///
+    /// `window` adapts to the observed block density: it doubles (probing only the new
+    /// outer slots) while nothing is found, e.g. in an outage gap, and after a hit it
+    /// shrinks to twice the distance of the nearest block, so dense stretches near the
+    /// tip cost few requests.
+    async fn find_nearby_slot_with_timestamp_parallel(
+        &self,
+        center_slot: Slot,
+        target_timestamp: UnixTs,
+        window: &mut u64,
+    ) -> Option<(Slot, UnixTs)> {
+        let min_window = NEARBY_WINDOW_MIN.min(self.probe_window);
+        let max_window = NEARBY_WINDOW_MAX.max(self.probe_window);
+        let mut probed = 0;
+        loop {
+            let mut requests = Vec::new();
+            let mut slots = Vec::new();
+            
+            for offset in probed + 1..=*window {
+                if let Some(slot) = center_slot.checked_sub(offset) {
+                    slots.push(slot);
+                    requests.push(self.get_block_time(slot));
+                }
+                
+                slots.push(center_slot + offset);
+                requests.push(self.get_block_time(center_slot + offset));
+            }
+            
+            // Execute all requests in parallel
+            let results = join_all(requests).await;
+            
+            // Find the best nearby slot
+            let mut best_slot = None;
+            let mut best_time_diff = i64::MAX;
+            let mut nearest_offset = None;
+            
+            for (i, result) in results.into_iter().enumerate() {
+                if let Ok(Some(block_time)) = result {
+                    let slot = slots[i];
+                    let offset = slot.0.abs_diff(center_slot.0);
+                    nearest_offset = Some(nearest_offset.map_or(offset, |nearest: u64| nearest.min(offset)));
+                    let time_diff = block_time - target_timestamp;
+                    
+                    // Prefer an exact match, then slots before the target timestamp that are closest
+                    if (time_diff <= 0 && time_diff.abs() < best_time_diff.abs())
+                        || (best_time_diff > 0 && time_diff > 0 && time_diff < best_time_diff)
+                    {
+                        best_slot = Some((slot, block_time));
+                        best_time_diff = time_diff;
+                    }
+                }
+            }
+            
+            match nearest_offset {
+                Some(nearest) => {
+                    *window = (nearest * 2).clamp(min_window, max_window);
+                    return best_slot;
+                }
+                None if *window >= max_window => return None,
+                None => {
+                    probed = *window;
+                    *window = (*window * 2).min(max_window);
+                }
+            }
+        }
+    }
+    
+    /// Looks at neighboring blocks to find the nearest ones with a different timestamp and
+    /// counts the blocks that share `block_time` with `slot`.
+    pub async fn get_timestamp_uncertainty(&self, slot: Slot, block_time: UnixTs) -> Result<TimestampUncertainty, Box<dyn Error>> {
+        let batch: u64 = 16;
+        let max_rounds = 8; // Old slots may have no timestamps at all, so don't walk forever
+        let mut uncertainty = TimestampUncertainty { same_timestamp_blocks: 1, ..Default::default() };
+        
+        // Walk backwards in batches until a block with an earlier timestamp shows up
+        let mut end = slot;
+        let mut rounds = 0;
+        while uncertainty.previous.is_none() && end > Slot(0) && rounds < max_rounds {
+            rounds += 1;
+            let start = end.saturating_sub(batch * 4);
+            let mut blocks = self.get_blocks(start, end - 1).await?;
+            blocks.reverse();
+            for chunk in blocks.chunks(batch as usize) {
+                let times = join_all(chunk.iter().map(|s| self.get_block_time(*s))).await;
+                for (neighbor, time) in chunk.iter().zip(times) {
+                    match time? {
+                        Some(time) if time == block_time => uncertainty.same_timestamp_blocks += 1,
+                        Some(time) => {
+                            uncertainty.previous = Some((*neighbor, time));
+                            break;
+                        }
+                        None => {}
+                    }
+                }
+                if uncertainty.previous.is_some() {
+                    break;
+                }
+            }
+            end = start;
+        }
+        
+        // And forwards until a block with a later timestamp shows up
+        let mut start = slot + 1;
+        let mut rounds = 0;
+        while uncertainty.next.is_none() && rounds < max_rounds {
+            rounds += 1;
+            let blocks = self.get_blocks_with_limit(start, batch).await?;
+            if blocks.is_empty() {
+                break;
+            }
+            let times = join_all(blocks.iter().map(|s| self.get_block_time(*s))).await;
+            for (neighbor, time) in blocks.iter().zip(times) {
+                match time? {
+                    Some(time) if time == block_time => uncertainty.same_timestamp_blocks += 1,
+                    Some(time) => {
+                        uncertainty.next = Some((*neighbor, time));
+                        break;
+                    }
+                    None => {}
+                }
+            }
+            start = blocks[blocks.len() - 1] + 1;
+        }
+        
+        Ok(uncertainty)
+    }
+    
+    /// The block that ties with `slot` resolve to: the last block sharing its block time, or
+    /// the first with [`lowest`](crate::BlockFinderBuilder::lowest)
+    async fn resolve_tie(&self, slot: Slot, block_time: UnixTs) -> Result<Slot, Box<dyn Error>> {
+        if self.lowest {
+            self.find_lowest_slot_with_timestamp(slot, block_time).await
+        } else {
+            self.find_highest_slot_with_timestamp(slot, block_time).await
+        }
+    }
+    
+    /// Like [`resolve_tie`](Self::resolve_tie) for a block the search already knows to be the last
+    /// one with its block time
+    async fn resolve_lower_tie(&self, slot: Slot, block_time: UnixTs) -> Result<Slot, Box<dyn Error>> {
+        if self.lowest {
+            self.find_lowest_slot_with_timestamp(slot, block_time).await
+        } else {
+            Ok(slot)
+        }
+    }
+    
+    /// Finds the last block that still has `target_timestamp`, however far after `start_slot`.
+    /// Produced slots come from `getBlocksWithLimit`, so skipped slots and gaps cost no requests.
+    async fn find_highest_slot_with_timestamp(
+        &self,
+        start_slot: Slot, 
+        target_timestamp: UnixTs,
+    ) -> Result<Slot, Box<dyn Error>> {
+        tracing::debug!("Finding highest slot with timestamp {}, starting from slot {}", target_timestamp, start_slot);
+        
+        // Block times never decrease, so the blocks sharing the timestamp form one run. Gallop
+        // forward in doubling steps until a step lands past the run, then binary search that step.
+        let mut highest_slot = start_slot;
+        let mut step = 1;
+        let mut past_slot = loop {
+            let from = highest_slot.saturating_add(step);
+            match self.first_timed_block(from, None).await {
+                Ok(Some((slot, block_time))) if block_time == target_timestamp => {
+                    highest_slot = slot;
+                    tracing::debug!("Found higher slot {} with same timestamp {}", slot, target_timestamp);
+                    step = step.saturating_mul(2);
+                }
+                // Moved past our target timestamp, or reached the tip
+                Ok(_) => break from,
+                Err(e) => {
+                    tracing::warn!("Error getting blocks after slot {}: {}", from, e);
+                    break from;
+                }
+            }
+            
+            // Small delay to avoid overwhelming the RPC
+            self.clock.sleep(self.scan_delay).await;
+        };
+        
+        // Every block from `past_slot` on is after the target timestamp
+        while highest_slot + 1 < past_slot {
+            let mid_slot = highest_slot + 1 + (past_slot - highest_slot - 1) / 2;
+            match self.first_timed_block(mid_slot, Some(past_slot)).await {
+                Ok(Some((slot, block_time))) if block_time == target_timestamp => {
+                    highest_slot = slot;
+                    tracing::debug!("Found higher slot {} with same timestamp {}", slot, target_timestamp);
+                }
+                Ok(_) => past_slot = mid_slot,
+                Err(e) => {
+                    tracing::warn!("Error getting blocks after slot {}: {}", mid_slot, e);
+                    past_slot = mid_slot;
+                }
+            }
+            self.clock.sleep(self.scan_delay).await;
+        }
+        
+        tracing::info!("Highest slot with timestamp {} is {}", target_timestamp, highest_slot);
+        Ok(highest_slot)
+    }
+    
+    /// Finds the first block that already has `target_timestamp`, however far before `start_slot`,
+    /// with the galloping scan of [`find_highest_slot_with_timestamp`](Self::find_highest_slot_with_timestamp)
+    /// run backwards
+    async fn find_lowest_slot_with_timestamp(
+        &self,
+        start_slot: Slot,
+        target_timestamp: UnixTs,
+    ) -> Result<Slot, Box<dyn Error>> {
+        tracing::debug!("Finding lowest slot with timestamp {}, starting from slot {}", target_timestamp, start_slot);
+        
+        let floor = self.chain.first_slot();
+        let mut lowest_slot = start_slot;
+        // No block from `run_start` up to `lowest_slot` is before the target timestamp, and
+        // the first block from `before_run` on is
+        let mut run_start = start_slot;
+        let mut step = 1;
+        let mut before_run = loop {
+            if run_start <= floor {
+                break None;
+            }
+            let from = run_start.saturating_sub(step).max(floor);
+            match self.first_timed_block(from, Some(lowest_slot)).await {
+                Ok(Some((slot, block_time))) if block_time < target_timestamp => break Some(slot),
+                Ok(found) => {
+                    if let Some((slot, _)) = found {
+                        lowest_slot = slot;
+                        tracing::debug!("Found lower slot {} with same timestamp {}", slot, target_timestamp);
+                    }
+                    run_start = from;
+                    step = step.saturating_mul(2);
+                }
+                Err(e) => {
+                    tracing::warn!("Error getting blocks after slot {}: {}", from, e);
+                    break Some(from);
+                }
+            }
+            
+            // Small delay to avoid overwhelming the RPC
+            self.clock.sleep(self.scan_delay).await;
+        };
+        
+        while let Some(before) = before_run.filter(|before| *before + 1 < run_start) {
+            let mid_slot = before + (run_start - before) / 2;
+            match self.first_timed_block(mid_slot, Some(lowest_slot)).await {
+                Ok(Some((slot, block_time))) if block_time < target_timestamp => before_run = Some(slot),
+                Ok(found) => {
+                    if let Some((slot, _)) = found {
+                        lowest_slot = slot;
+                        tracing::debug!("Found lower slot {} with same timestamp {}", slot, target_timestamp);
+                    }
+                    run_start = mid_slot;
+                }
+                Err(e) => {
+                    tracing::warn!("Error getting blocks after slot {}: {}", mid_slot, e);
+                    before_run = Some(mid_slot);
+                }
+            }
+            self.clock.sleep(self.scan_delay).await;
+        }
+        
+        tracing::info!("Lowest slot with timestamp {} is {}", target_timestamp, lowest_slot);
+        Ok(lowest_slot)
+    }
+    
+    /// Every produced slot whose block shares the block time of the one at `slot`, oldest first.
+    /// Multiple slots routinely share one block time; this lists them all instead of picking one.
+    pub async fn blocks_sharing_timestamp(&self, slot: Slot) -> Result<Vec<Slot>, Box<dyn Error>> {
+        let block_time = self.get_block_time(slot).await?.ok_or_else(|| format!("❌ Slot {} has no block time", slot))?;
+        let lowest_slot = self.find_lowest_slot_with_timestamp(slot, block_time).await?;
+        let highest_slot = self.find_highest_slot_with_timestamp(slot, block_time).await?;
+        self.get_blocks(lowest_slot, highest_slot).await
+    }
+    
+    /// First produced block from `slot` on, and before `end` if given, that has a block time
+    async fn first_timed_block(&self, slot: Slot, end: Option<Slot>) -> Result<Option<(Slot, UnixTs)>, Box<dyn Error>> {
+        let mut next_slot = slot;
+        loop {
+            let blocks = self.get_blocks_with_limit(next_slot, SCAN_BATCH).await?;
+            let Some(&last_block) = blocks.last() else {
+                return Ok(None); // Reached the tip
+            };
+            for slot in blocks {
+                if end.is_some_and(|end| slot >= end) {
+                    return Ok(None);
+                }
+                // Skip blocks without a timestamp or with errors
+                if let Ok(Some(block_time)) = self.get_block_time(slot).await {
+                    return Ok(Some((slot, block_time)));
+                }
+            }
+            next_slot = last_block + 1;
+        }
+    }
+    
+    /// Finds the highest produced slot at or before `slot`, without looking below `floor`.
+    pub async fn find_last_block_at_or_before(&self, slot: Slot, floor: Slot) -> Result<Option<Slot>, Box<dyn Error>> {
+        let mut window = 64;
+        loop {
+            let start = slot.saturating_sub(window).max(floor);
+            let blocks = self.get_blocks(start, slot).await?;
+            if let Some(last) = blocks.last() {
+                return Ok(Some(*last));
+            }
+            if start == floor {
+                return Ok(None);
+            }
+            window *= 2;
+        }
+    }
+    
+    /// Resolves a time window to the slots of its first and last blocks.
+    pub async fn resolve_window_slots(&self, from_timestamp: UnixTs, to_timestamp: UnixTs) -> Result<(Slot, Slot), Box<dyn Error>> {
+        let current_time = self.now()?;
+        
+        let from_slot = self.get_slot_by_timestamp_optimized(from_timestamp).await?;
+        // The search returns the block at or before the timestamp; the window starts after it
+        let from_slot = match self.get_block_time(from_slot).await? {
+            Some(block_time) if block_time < from_timestamp => from_slot + 1,
+            _ => from_slot,
+        };
+        
+        let to_slot = if to_timestamp >= current_time {
+            self.get_current_slot().await?
+        } else {
+            self.get_slot_by_timestamp_optimized(to_timestamp).await?
+        };
+        
+        Ok((from_slot, to_slot))
+    }
+}
@@ -0,0 +1,501 @@
+//! Pure core of the timestamp search
+//!
+//! [`SearchState`] holds the slot window of the binary search and the closest
+//! blocks seen on either side of the target, and decides what to probe next
+//! from the block times it is told about. It does no I/O: the async search in
+//! `search.rs` reads the block times, enforces limits and delays, and feeds the
+//! results back in. That keeps the window arithmetic and the closest-block
+//! bookkeeping testable without an RPC node.
+
+use crate::search::SearchProgress;
+use crate::types::{Slot, UnixTs};
+
+/// What the search does after an observation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NextProbe {
+    /// Read the block time of this slot and pass it to [`SearchState::observe`]
+    Slot(Slot),
+    /// This slot has no block time. Probe its neighbours and pass the best one to
+    /// [`SearchState::observe_nearby`].
+    Nearby(Slot),
+    /// This block has exactly the target time; later blocks may share it
+    Exact(Slot),
+    /// The window is empty
+    Done(Outcome),
+}
+
+/// The closest blocks once the window is empty without an exact match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The latest block seen before the target, at this slot and block time, which is the answer
+    Before(Slot, UnixTs),
+    /// Only blocks after the target were seen, the earliest at this slot and block time. The
+    /// answer is the block before it.
+    After(Slot, UnixTs),
+    /// No block with a block time was seen
+    NotFound,
+}
+
+/// Where the search goes on after a slot that cannot be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Only later slots can be read, e.g. older ones are purged
+    Later,
+    /// Only earlier slots can be read, e.g. the node is behind
+    Earlier,
+}
+
+/// Window and closest blocks of a running search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchState {
+    target_timestamp: UnixTs,
+    // Slots still to search, `low..end`. The end is exclusive, so the window can close at slot 0.
+    low: Slot,
+    end: Slot,
+    // Latest block at or before the target, and earliest block after it, by time and then slot
+    before: Option<(Slot, UnixTs)>,
+    after: Option<(Slot, UnixTs)>,
+}
+
+impl SearchState {
+    /// Continues from a snapshot, e.g. a checkpoint. Returns `None` when the snapshot cannot
+    /// come from a search: its window ends at the last slot or its closest block's time overflows.
+    pub fn new(progress: &SearchProgress) -> Option<Self> {
+        let mut state = SearchState {
+            target_timestamp: progress.target_timestamp,
+            low: progress.low_slot,
+            end: if progress.low_slot <= progress.high_slot { progress.high_slot.checked_add(1)? } else { progress.low_slot },
+            before: None,
+            after: None,
+        };
+        if progress.closest_time_diff != i64::MAX {
+            state.record(progress.closest_slot, progress.target_timestamp.checked_add(progress.closest_time_diff)?);
+        }
+        Some(state)
+    }
+    
+    /// Snapshot for checkpoints and interrupted searches. An empty window at slot 0 reads as
+    /// just slot 0, since `high_slot` is inclusive.
+    pub fn progress(&self) -> SearchProgress {
+        let (low_slot, high_slot) = self.window();
+        let mut progress = SearchProgress::new(self.target_timestamp, low_slot, high_slot);
+        if let Some((slot, block_time)) = self.before.or(self.after) {
+            progress.closest_slot = slot;
+            progress.closest_time_diff = block_time - self.target_timestamp;
+        }
+        progress
+    }
+    
+    /// Lowest and highest slot left to search. The highest is below the lowest once the window is empty.
+    pub fn window(&self) -> (Slot, Slot) {
+        let high = if self.end > self.low { self.end - 1 } else { self.low.saturating_sub(1) };
+        (self.low, high)
+    }
+    
+    pub fn next_probe(&self) -> NextProbe {
+        if self.low < self.end {
+            // The lower middle, as in a binary search over `low..=end - 1`
+            return NextProbe::Slot(self.low + (self.end - 1 - self.low) / 2);
+        }
+        NextProbe::Done(match (self.before, self.after) {
+            (Some((slot, block_time)), _) => Outcome::Before(slot, block_time),
+            (None, Some((slot, block_time))) => Outcome::After(slot, block_time),
+            (None, None) => Outcome::NotFound,
+        })
+    }
+    
+    /// Takes the block time of a probed slot, `None` if it has none
+    pub fn observe(&mut self, slot: Slot, block_time: Option<UnixTs>) -> NextProbe {
+        let Some(block_time) = block_time else {
+            return NextProbe::Nearby(slot);
+        };
+        if let Some(exact) = self.observe_block(slot, block_time) {
+            return exact;
+        }
+        if block_time < self.target_timestamp {
+            self.raise_low(slot + 1);
+        } else {
+            self.lower_end(slot);
+        }
+        self.next_probe()
+    }
+    
+    /// Takes the best block found around `center`, a slot without a block time, or `None` if
+    /// none of its neighbours has one either
+    pub fn observe_nearby(&mut self, center: Slot, nearby: Option<(Slot, UnixTs)>) -> NextProbe {
+        let Some((slot, block_time)) = nearby else {
+            // Nothing around, so move on past it
+            self.raise_low(center + 1);
+            return self.next_probe();
+        };
+        if let Some(exact) = self.observe_block(slot, block_time) {
+            return exact;
+        }
+        // A best block outside the window means every slot between the window's edge and
+        // `center` was probed and holds nothing closer, so the window still shrinks past
+        // `center` instead of probing it again
+        if block_time < self.target_timestamp {
+            self.raise_low(if slot < self.low { center + 1 } else { slot + 1 });
+        } else {
+            self.lower_end(if slot >= self.end { center } else { slot });
+        }
+        self.next_probe()
+    }
+    
+    /// Gives up on a slot that cannot be read and goes on in `direction`
+    pub fn skip(&mut self, slot: Slot, direction: Direction) -> NextProbe {
+        match direction {
+            Direction::Later => self.raise_low(slot + 1),
+            Direction::Earlier => self.lower_end(slot),
+        }
+        self.next_probe()
+    }
+    
+    fn observe_block(&mut self, slot: Slot, block_time: UnixTs) -> Option<NextProbe> {
+        self.record(slot, block_time);
+        (block_time == self.target_timestamp).then_some(NextProbe::Exact(slot))
+    }
+    
+    fn record(&mut self, slot: Slot, block_time: UnixTs) {
+        if block_time <= self.target_timestamp {
+            if self.before.is_none_or(|(best_slot, best_time)| (block_time, slot) > (best_time, best_slot)) {
+                self.before = Some((slot, block_time));
+            }
+        } else if self.after.is_none_or(|(best_slot, best_time)| (block_time, slot) < (best_time, best_slot)) {
+            self.after = Some((slot, block_time));
+        }
+    }
+    
+    // The window only ever shrinks
+    fn raise_low(&mut self, slot: Slot) {
+        self.low = self.low.max(slot);
+    }
+    
+    fn lower_end(&mut self, slot: Slot) {
+        self.end = self.end.min(slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const TARGET: UnixTs = UnixTs(1_000);
+    
+    fn state(low: u64, high: u64) -> SearchState {
+        SearchState::new(&SearchProgress::new(TARGET, Slot(low), Slot(high))).unwrap()
+    }
+    
+    /// Block times of a small chain, `None` for skipped slots
+    type Chain = Vec<Option<i64>>;
+    
+    fn dense(len: u64) -> Chain {
+        (0..len).map(|slot| Some(100 + (slot * 2 / 5) as i64)).collect()
+    }
+    
+    fn with_skipped(mut chain: Chain, skipped: impl Fn(u64) -> bool) -> Chain {
+        for (slot, block_time) in chain.iter_mut().enumerate() {
+            if skipped(slot as u64) {
+                *block_time = None;
+            }
+        }
+        chain
+    }
+    
+    fn block_time(chain: &Chain, slot: Slot) -> Option<UnixTs> {
+        chain.get(slot.0 as usize).copied().flatten().map(UnixTs)
+    }
+    
+    /// The finder's nearby probe: a window doubling from 4 to 160 slots either side, returning
+    /// the latest block at or before the target in the first window with any block, else the earliest after it
+    fn nearby(chain: &Chain, center: Slot, target: UnixTs) -> Option<(Slot, UnixTs)> {
+        let mut window = 4;
+        loop {
+            let low = center.saturating_sub(window);
+            let blocks: Vec<(Slot, UnixTs)> = (low.0..=center.0 + window)
+                .map(Slot)
+                .filter_map(|slot| block_time(chain, slot).map(|time| (slot, time)))
+                .collect();
+            if !blocks.is_empty() {
+                let before = blocks.iter().filter(|(_, time)| *time <= target).max_by_key(|(slot, time)| (*time, *slot));
+                let after = blocks.iter().filter(|(_, time)| *time > target).min_by_key(|(slot, time)| (*time, *slot));
+                return before.or(after).copied();
+            }
+            if window >= 160 {
+                return None;
+            }
+            window *= 2;
+        }
+    }
+    
+    /// Latest slot at `target`'s block time, as the finder's forward scan after an exact match
+    fn highest_with_time(chain: &Chain, slot: Slot, target: UnixTs) -> Slot {
+        (slot.0..chain.len() as u64).map(Slot).take_while(|s| block_time(chain, *s).is_none_or(|time| time <= target))
+            .filter(|s| block_time(chain, *s) == Some(target))
+            .last()
+            .unwrap_or(slot)
+    }
+    
+    /// Runs a whole search over `chain` the way the async search drives the state
+    fn run(chain: &Chain, target: UnixTs) -> Option<Slot> {
+        let mut state = state(0, chain.len() as u64 - 1);
+        state.target_timestamp = target;
+        let mut next = state.next_probe();
+        for _ in 0..1_000 {
+            next = match next {
+                NextProbe::Slot(slot) => state.observe(slot, block_time(chain, slot)),
+                NextProbe::Nearby(center) => state.observe_nearby(center, nearby(chain, center, target)),
+                NextProbe::Exact(slot) => return Some(highest_with_time(chain, slot, target)),
+                NextProbe::Done(Outcome::Before(slot, _)) => return Some(slot),
+                NextProbe::Done(Outcome::After(slot, _)) => {
+                    return (0..slot.0).rev().map(Slot).find_map(|earlier| match block_time(chain, earlier) {
+                        Some(time) if time == target => Some(highest_with_time(chain, earlier, target)),
+                        Some(time) if time < target => Some(earlier),
+                        _ => None,
+                    });
+                }
+                NextProbe::Done(Outcome::NotFound) => return None,
+            };
+        }
+        panic!("the search did not converge for target {}", target);
+    }
+    
+    /// The latest produced slot with a block time at or before `target`
+    fn expected(chain: &Chain, target: UnixTs) -> Option<Slot> {
+        (0..chain.len() as u64).rev().map(Slot).find(|slot| block_time(chain, *slot).is_some_and(|time| time <= target))
+    }
+    
+    fn assert_finds_every_target(chain: &Chain) {
+        let times: Vec<i64> = chain.iter().flatten().copied().collect();
+        let (first, last) = (times[0], times[times.len() - 1]);
+        for target in first - 3..=last + 3 {
+            let target = UnixTs(target);
+            assert_eq!(run(chain, target), expected(chain, target), "target {}", target);
+        }
+    }
+    
+    #[test]
+    fn first_probe_is_the_lower_middle() {
+        assert_eq!(state(0, 9).next_probe(), NextProbe::Slot(Slot(4)));
+        assert_eq!(state(5, 5).next_probe(), NextProbe::Slot(Slot(5)));
+    }
+    
+    #[test]
+    fn block_before_the_target_raises_the_window() {
+        let mut state = state(0, 99);
+        assert_eq!(state.observe(Slot(49), Some(UnixTs(900))), NextProbe::Slot(Slot(74)));
+        assert_eq!(state.window(), (Slot(50), Slot(99)));
+    }
+    
+    #[test]
+    fn block_after_the_target_lowers_the_window() {
+        let mut state = state(0, 99);
+        assert_eq!(state.observe(Slot(49), Some(UnixTs(1_100))), NextProbe::Slot(Slot(24)));
+        assert_eq!(state.window(), (Slot(0), Slot(48)));
+    }
+    
+    #[test]
+    fn exact_block_time_stops_the_search() {
+        let mut state = state(0, 99);
+        assert_eq!(state.observe(Slot(49), Some(TARGET)), NextProbe::Exact(Slot(49)));
+        assert_eq!(state.progress().best_candidate(), Some((Slot(49), TARGET)));
+    }
+    
+    #[test]
+    fn block_after_the_target_at_slot_zero_does_not_underflow() {
+        let mut state = state(0, 0);
+        assert_eq!(state.observe(Slot(0), Some(UnixTs(1_100))), NextProbe::Done(Outcome::After(Slot(0), UnixTs(1_100))));
+        assert_eq!(state.window(), (Slot(0), Slot(0)));
+    }
+    
+    #[test]
+    fn nearby_block_after_the_target_at_slot_zero_does_not_underflow() {
+        let mut state = state(0, 1);
+        assert_eq!(state.observe(Slot(0), None), NextProbe::Nearby(Slot(0)));
+        assert_eq!(state.observe_nearby(Slot(0), Some((Slot(0), UnixTs(1_100)))), NextProbe::Done(Outcome::After(Slot(0), UnixTs(1_100))));
+    }
+    
+    #[test]
+    fn skipping_earlier_at_slot_zero_does_not_underflow() {
+        let mut state = state(0, 10);
+        state.observe(Slot(5), Some(UnixTs(1_100)));
+        state.observe(Slot(2), Some(UnixTs(1_100)));
+        assert_eq!(state.skip(Slot(0), Direction::Earlier), NextProbe::Done(Outcome::After(Slot(2), UnixTs(1_100))));
+    }
+    
+    #[test]
+    fn missing_block_time_asks_for_neighbours() {
+        assert_eq!(state(0, 99).observe(Slot(49), None), NextProbe::Nearby(Slot(49)));
+    }
+    
+    #[test]
+    fn no_neighbours_moves_past_the_slot() {
+        let mut state = state(0, 99);
+        assert_eq!(state.observe_nearby(Slot(49), None), NextProbe::Slot(Slot(74)));
+        assert_eq!(state.window(), (Slot(50), Slot(99)));
+    }
+    
+    #[test]
+    fn nearby_block_inside_the_window_bounds_it() {
+        let mut state = state(0, 99);
+        state.observe_nearby(Slot(49), Some((Slot(47), UnixTs(900))));
+        assert_eq!(state.window(), (Slot(48), Slot(99)));
+        state.observe_nearby(Slot(73), Some((Slot(75), UnixTs(1_100))));
+        assert_eq!(state.window(), (Slot(48), Slot(74)));
+    }
+    
+    #[test]
+    fn nearby_block_outside_the_window_still_shrinks_it_past_the_center() {
+        let mut state = state(50, 60);
+        state.observe_nearby(Slot(55), Some((Slot(40), UnixTs(900))));
+        assert_eq!(state.window(), (Slot(56), Slot(60)));
+        let mut state = self::state(50, 60);
+        state.observe_nearby(Slot(55), Some((Slot(70), UnixTs(1_100))));
+        assert_eq!(state.window(), (Slot(50), Slot(54)));
+    }
+    
+    #[test]
+    fn nearby_exact_block_stops_the_search() {
+        assert_eq!(state(0, 99).observe_nearby(Slot(49), Some((Slot(52), TARGET))), NextProbe::Exact(Slot(52)));
+    }
+    
+    #[test]
+    fn observations_outside_the_window_never_widen_it() {
+        let mut state = state(50, 60);
+        state.observe(Slot(10), Some(UnixTs(900)));
+        state.observe(Slot(90), Some(UnixTs(1_100)));
+        state.skip(Slot(5), Direction::Later);
+        state.skip(Slot(95), Direction::Earlier);
+        assert_eq!(state.window(), (Slot(50), Slot(60)));
+    }
+    
+    #[test]
+    fn skipping_moves_the_window_in_the_given_direction() {
+        let mut state = state(0, 99);
+        assert_eq!(state.skip(Slot(49), Direction::Later), NextProbe::Slot(Slot(74)));
+        assert_eq!(state.skip(Slot(74), Direction::Earlier), NextProbe::Slot(Slot(61)));
+        assert_eq!(state.window(), (Slot(50), Slot(73)));
+    }
+    
+    #[test]
+    fn latest_block_before_the_target_wins() {
+        let mut state = state(0, 99);
+        state.observe(Slot(10), Some(UnixTs(800)));
+        state.observe(Slot(30), Some(UnixTs(990)));
+        state.observe(Slot(20), Some(UnixTs(900)));
+        assert_eq!(state.progress().best_candidate(), Some((Slot(30), UnixTs(990))));
+    }
+    
+    #[test]
+    fn later_slot_wins_a_block_time_tie_before_the_target() {
+        let mut state = state(0, 99);
+        state.observe(Slot(30), Some(UnixTs(990)));
+        state.observe(Slot(31), Some(UnixTs(990)));
+        state.observe(Slot(29), Some(UnixTs(990)));
+        assert_eq!(state.progress().best_candidate(), Some((Slot(31), UnixTs(990))));
+    }
+    
+    #[test]
+    fn closer_block_after_the_target_does_not_replace_one_before_it() {
+        let mut state = state(0, 99);
+        state.observe(Slot(10), Some(UnixTs(500)));
+        state.observe(Slot(11), Some(UnixTs(1_001)));
+        assert_eq!(state.next_probe(), NextProbe::Done(Outcome::Before(Slot(10), UnixTs(500))));
+    }
+    
+    #[test]
+    fn earliest_block_after_the_target_is_kept_without_one_before_it() {
+        let mut state = state(0, 99);
+        state.observe(Slot(49), Some(UnixTs(1_500)));
+        state.observe(Slot(24), Some(UnixTs(1_200)));
+        state.observe(Slot(11), Some(UnixTs(1_100)));
+        state.skip(Slot(5), Direction::Earlier);
+        state.skip(Slot(0), Direction::Later);
+        state.skip(Slot(2), Direction::Later);
+        state.skip(Slot(3), Direction::Later);
+        state.skip(Slot(4), Direction::Later);
+        assert_eq!(state.next_probe(), NextProbe::Done(Outcome::After(Slot(11), UnixTs(1_100))));
+        assert_eq!(state.progress().closest_time_diff, 100);
+    }
+    
+    #[test]
+    fn empty_window_without_blocks_is_not_found() {
+        let mut state = state(0, 0);
+        assert_eq!(state.observe_nearby(Slot(0), None), NextProbe::Done(Outcome::NotFound));
+    }
+    
+    #[test]
+    fn progress_round_trips_through_a_checkpoint() {
+        let mut state = state(0, 99);
+        state.observe(Slot(49), Some(UnixTs(990)));
+        state.observe(Slot(74), Some(UnixTs(1_010)));
+        let resumed = SearchState::new(&state.progress()).unwrap();
+        assert_eq!(resumed.window(), state.window());
+        assert_eq!(resumed.next_probe(), state.next_probe());
+        assert_eq!(resumed.progress().best_candidate(), Some((Slot(49), UnixTs(990))));
+    }
+    
+    #[test]
+    fn rejects_snapshots_that_overflow() {
+        let mut progress = SearchProgress::new(TARGET, Slot(0), Slot(u64::MAX));
+        assert_eq!(SearchState::new(&progress), None);
+        
+        progress.high_slot = Slot(99);
+        progress.closest_time_diff = i64::MAX - 1;
+        assert_eq!(SearchState::new(&progress), None);
+        assert_eq!(progress.best_candidate(), None);
+        
+        progress.target_timestamp = UnixTs(i64::MIN);
+        progress.closest_time_diff = i64::MIN;
+        assert_eq!(SearchState::new(&progress), None);
+        assert_eq!(progress.best_candidate(), None);
+    }
+    
+    #[test]
+    fn block_at_slot_zero_is_a_candidate() {
+        let mut state = state(0, 9);
+        state.observe(Slot(0), Some(UnixTs(990)));
+        assert_eq!(SearchState::new(&state.progress()).unwrap().progress().best_candidate(), Some((Slot(0), UnixTs(990))));
+    }
+    
+    #[test]
+    fn finds_every_target_on_a_dense_chain() {
+        assert_finds_every_target(&dense(500));
+    }
+    
+    #[test]
+    fn finds_every_target_with_skipped_slots() {
+        assert_finds_every_target(&with_skipped(dense(500), |slot| slot % 3 == 1 || slot % 7 == 0));
+    }
+    
+    #[test]
+    fn finds_every_target_when_the_first_slots_are_skipped() {
+        assert_finds_every_target(&with_skipped(dense(300), |slot| slot < 25));
+    }
+    
+    #[test]
+    fn finds_every_target_only_slot_zero_produced() {
+        assert_finds_every_target(&with_skipped(dense(50), |slot| slot > 0));
+    }
+    
+    #[test]
+    fn finds_every_target_around_gaps_wider_than_the_nearby_window() {
+        assert_finds_every_target(&with_skipped(dense(1_200), |slot| (40..700).contains(&slot) || (900..1_190).contains(&slot)));
+    }
+    
+    #[test]
+    fn finds_every_target_across_a_cluster_halt() {
+        let chain: Chain = with_skipped(dense(800), |slot| (300..340).contains(&slot))
+            .into_iter()
+            .enumerate()
+            .map(|(slot, time)| time.map(|time| if slot >= 340 { time + 5_000 } else { time }))
+            .collect();
+        assert_finds_every_target(&chain);
+    }
+    
+    #[test]
+    fn finds_nothing_before_the_first_block() {
+        let chain = with_skipped(dense(100), |slot| slot < 10);
+        assert_eq!(run(&chain, UnixTs(50)), None);
+    }
+}
@@ -0,0 +1,141 @@
+//! Coalescing of identical requests that are in flight at the same time
+//!
+//! Concurrent searches sharing a finder often probe the same slot. The first
+//! caller for a key becomes the leader and makes the request; callers that
+//! arrive while it is in flight wait for the leader's result instead of
+//! sending their own.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+pub(crate) struct InFlight<K, V> {
+    calls: Mutex<HashMap<K, watch::Receiver<Option<V>>>>,
+}
+
+impl<K, V> Default for InFlight<K, V> {
+    fn default() -> Self {
+        InFlight { calls: Mutex::new(HashMap::new()) }
+    }
+}
+
+pub(crate) enum Flight<'a, K: Hash + Eq + Clone, V: Clone> {
+    /// No identical request is in flight: make it and [`Leader::finish`] with the result
+    Leader(Leader<'a, K, V>),
+    /// Another caller is making the request
+    Follower(watch::Receiver<Option<V>>),
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> InFlight<K, V> {
+    pub(crate) fn join(&self, key: K) -> Flight<'_, K, V> {
+        let mut calls = self.calls.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(receiver) = calls.get(&key) {
+            return Flight::Follower(receiver.clone());
+        }
+        let (sender, receiver) = watch::channel(None);
+        calls.insert(key.clone(), receiver);
+        Flight::Leader(Leader { flights: self, key, sender })
+    }
+}
+
+/// Waits for the leader's result. Returns `None` if the leader was dropped before finishing,
+/// in which case the caller should make the request itself.
+pub(crate) async fn wait<V: Clone>(mut receiver: watch::Receiver<Option<V>>) -> Option<V> {
+    receiver.wait_for(Option::is_some).await.ok().and_then(|value| value.clone())
+}
+
+/// Removes the in-flight entry when dropped, also when the leader is cancelled
+pub(crate) struct Leader<'a, K: Hash + Eq + Clone, V: Clone> {
+    flights: &'a InFlight<K, V>,
+    key: K,
+    sender: watch::Sender<Option<V>>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Leader<'_, K, V> {
+    /// Shares the result with every waiting follower
+    pub(crate) fn finish(self, value: V) {
+        self.sender.send_replace(Some(value));
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Drop for Leader<'_, K, V> {
+    fn drop(&mut self) {
+        let mut calls = self.flights.calls.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        calls.remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use futures::future::join_all;
+    
+    type Flights = InFlight<u64, Result<u64, String>>;
+    
+    /// Requests `key` through `flights` like the finder's block time lookups, returning
+    /// `result` after a while if this caller has to make the request, and counting it
+    async fn request(flights: &Flights, key: u64, requests: &AtomicUsize, result: Result<u64, String>) -> Result<u64, String> {
+        let leader = match flights.join(key) {
+            Flight::Leader(leader) => Some(leader),
+            Flight::Follower(receiver) => match wait(receiver).await {
+                Some(result) => return result,
+                None => None,
+            },
+        };
+        requests.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        if let Some(leader) = leader {
+            leader.finish(result.clone());
+        }
+        result
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_callers_share_one_request() {
+        let flights = Flights::default();
+        let requests = AtomicUsize::new(0);
+        let results = join_all((0..16).map(|_| request(&flights, 7, &requests, Ok(42)))).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(results.iter().all(|result| *result == Ok(42)), "{:?}", results);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn errors_reach_every_waiter() {
+        let flights = Flights::default();
+        let requests = AtomicUsize::new(0);
+        let results = join_all((0..16).map(|_| request(&flights, 7, &requests, Err("slot skipped".to_string())))).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(results.iter().all(|result| *result == Err("slot skipped".to_string())), "{:?}", results);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn different_keys_are_requested_separately() {
+        let flights = Flights::default();
+        let requests = AtomicUsize::new(0);
+        let results = join_all((0..4).map(|key| request(&flights, key, &requests, Ok(key)))).await;
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+        assert_eq!(results, vec![Ok(0), Ok(1), Ok(2), Ok(3)]);
+    }
+    
+    #[tokio::test(start_paused = true)]
+    async fn finished_requests_are_not_reused() {
+        let flights = Flights::default();
+        let requests = AtomicUsize::new(0);
+        assert_eq!(request(&flights, 7, &requests, Ok(1)).await, Ok(1));
+        assert_eq!(request(&flights, 7, &requests, Ok(2)).await, Ok(2));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+    
+    #[tokio::test]
+    async fn followers_of_a_cancelled_leader_make_the_request() {
+        let flights = Flights::default();
+        let Flight::Leader(leader) = flights.join(7) else { panic!("the first caller leads") };
+        let Flight::Follower(receiver) = flights.join(7) else { panic!("the second caller follows") };
+        drop(leader);
+        assert_eq!(wait(receiver).await, None);
+        assert!(matches!(flights.join(7), Flight::Leader(_)));
+    }
+}
@@ -0,0 +1,303 @@
+//! [`RpcBackend`] on top of the official `solana-rpc-client` crate
+//!
+//! Enabled with the `solana-client` feature. Requests, retries and response
+//! types come from the Solana client libraries, so the finder behaves like
+//! the rest of the Solana tooling. Helius style API keys go in the URL
+//! (`https://mainnet.helius-rpc.com/?api-key=...`).
+
+use std::error::Error;
+use std::str::FromStr;
+use serde_json::{json, Value};
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::{Error as ClientError, ErrorKind as ClientErrorKind};
+use solana_rpc_client_api::config::{RpcBlockConfig, RpcSignaturesForAddressConfig, RpcTransactionConfig};
+use solana_rpc_client_api::request::{RpcError as ClientRpcError, RpcRequest};
+use solana_rpc_client_api::response::{RpcConfirmedTransactionStatusWithSignature, RpcVoteAccountInfo};
+use solana_signature::Signature;
+use solana_transaction_status_client_types::{TransactionDetails, UiConfirmedBlock, UiTransactionEncoding};
+
+use crate::backend::{BackendFuture, RpcBackend};
+use crate::epoch::EpochSchedule;
+use crate::finder::Commitment;
+use crate::rpc::{AccountInfo, BlockInfo, EpochInfo, PerformanceSample, Reward, RpcError, RpcErrorKind, SignatureInfo, VersionInfo, VoteAccount, VoteAccounts};
+use crate::types::{Slot, UnixTs};
+
+/// Backend using `solana_rpc_client::nonblocking::rpc_client::RpcClient`
+pub struct SolanaClientBackend {
+    client: RpcClient,
+    endpoints: Vec<String>,
+}
+
+impl SolanaClientBackend {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::from_client(RpcClient::new(url.into()))
+    }
+    
+    /// Wraps a preconfigured client, e.g. one with a custom sender or timeout.
+    pub fn from_client(client: RpcClient) -> Self {
+        let endpoints = vec![client.url()];
+        SolanaClientBackend { client, endpoints }
+    }
+    
+    fn block_config(details: TransactionDetails, include_rewards: bool, commitment: Commitment) -> RpcBlockConfig {
+        RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            transaction_details: Some(details),
+            rewards: Some(include_rewards),
+            commitment: Some(commitment_config(commitment)),
+            max_supported_transaction_version: Some(0),
+        }
+    }
+}
+
+fn commitment_config(commitment: Commitment) -> CommitmentConfig {
+    match commitment {
+        Commitment::Finalized => CommitmentConfig::finalized(),
+        Commitment::Confirmed => CommitmentConfig::confirmed(),
+    }
+}
+
+/// Converts JSON-RPC errors into [`RpcError`] so the search can classify them
+fn rpc_error(error: ClientError) -> Box<dyn Error> {
+    match error.kind() {
+        ClientErrorKind::RpcError(ClientRpcError::RpcResponseError { code, message, .. }) => Box::new(RpcError {
+            code: i32::try_from(*code).unwrap_or(i32::MIN),
+            message: message.clone(),
+        }),
+        _ => error.into(),
+    }
+}
+
+impl RpcBackend for SolanaClientBackend {
+    fn endpoints(&self) -> &[String] {
+        &self.endpoints
+    }
+    
+    fn get_slot(&self, commitment: Commitment) -> BackendFuture<'_, Slot> {
+        Box::pin(async move {
+            Ok(Slot(self.client.get_slot_with_commitment(commitment_config(commitment)).await?))
+        })
+    }
+    
+    fn get_block_time(&self, slot: Slot) -> BackendFuture<'_, Option<UnixTs>> {
+        Box::pin(async move {
+            // RpcClient::get_block_time treats a null result as an error, so send the request directly
+            match self.client.send::<Option<i64>>(RpcRequest::GetBlockTime, json!([slot])).await {
+                Ok(block_time) => Ok(block_time.map(UnixTs)),
+                Err(e) => match rpc_error(e) {
+                    e if RpcErrorKind::of(e.as_ref()) == RpcErrorKind::SlotSkipped => Ok(None),
+                    e => Err(e),
+                },
+            }
+        })
+    }
+    
+    fn get_block_info(&self, slot: Slot, include_rewards: bool, commitment: Commitment) -> BackendFuture<'_, BlockInfo> {
+        Box::pin(async move {
+            let config = Self::block_config(TransactionDetails::None, include_rewards, commitment);
+            let block: UiConfirmedBlock = self.client.get_block_with_config(slot.0, config).await.map_err(rpc_error)?;
+            
+            Ok(BlockInfo {
+                blockhash: block.blockhash,
+                parent_slot: Slot(block.parent_slot),
+                block_time: block.block_time.map(UnixTs),
+                block_height: block.block_height,
+                rewards: block.rewards
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|reward| Reward {
+                        pubkey: reward.pubkey,
+                        lamports: reward.lamports,
+                        post_balance: reward.post_balance,
+                        reward_type: reward.reward_type.map(|t| t.to_string()),
+                        commission: reward.commission,
+                    })
+                    .collect(),
+            })
+        })
+    }
+    
+    fn get_full_block(&self, slot: Slot, commitment: Commitment) -> BackendFuture<'_, Value> {
+        Box::pin(async move {
+            let config = Self::block_config(TransactionDetails::Full, true, commitment);
+            let block = self.client.get_block_with_config(slot.0, config).await?;
+            Ok(serde_json::to_value(block)?)
+        })
+    }
+    
+    fn get_epoch_schedule(&self) -> BackendFuture<'_, EpochSchedule> {
+        Box::pin(async move {
+            let schedule = self.client.get_epoch_schedule().await?;
+            Ok(EpochSchedule {
+                slots_per_epoch: schedule.slots_per_epoch,
+                leader_schedule_slot_offset: schedule.leader_schedule_slot_offset,
+                warmup: schedule.warmup,
+                first_normal_epoch: schedule.first_normal_epoch,
+                first_normal_slot: Slot(schedule.first_normal_slot),
+            })
+        })
+    }
+    
+    fn get_epoch_info(&self, commitment: Commitment) -> BackendFuture<'_, EpochInfo> {
+        Box::pin(async move {
+            let info = self.client.get_epoch_info_with_commitment(commitment_config(commitment)).await?;
+            Ok(EpochInfo {
+                epoch: info.epoch,
+                slot_index: info.slot_index,
+                slots_in_epoch: info.slots_in_epoch,
+                absolute_slot: Slot(info.absolute_slot),
+            })
+        })
+    }
+    
+    fn get_version(&self) -> BackendFuture<'_, VersionInfo> {
+        Box::pin(async move {
+            let version = self.client.get_version().await?;
+            Ok(VersionInfo {
+                solana_core: version.solana_core,
+                feature_set: version.feature_set,
+            })
+        })
+    }
+    
+    fn get_genesis_hash(&self) -> BackendFuture<'_, String> {
+        Box::pin(async move {
+            Ok(self.client.get_genesis_hash().await?.to_string())
+        })
+    }
+    
+    fn get_first_available_block(&self) -> BackendFuture<'_, Slot> {
+        Box::pin(async move {
+            Ok(Slot(self.client.get_first_available_block().await?))
+        })
+    }
+    
+    fn get_blocks_with_limit(&self, start_slot: Slot, limit: u64, commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        Box::pin(async move {
+            let limit = usize::try_from(limit)?;
+            let slots = self.client.get_blocks_with_limit_and_commitment(start_slot.0, limit, commitment_config(commitment)).await?;
+            Ok(slots.into_iter().map(Slot).collect())
+        })
+    }
+    
+    fn get_blocks(&self, start_slot: Slot, end_slot: Slot, commitment: Commitment) -> BackendFuture<'_, Vec<Slot>> {
+        Box::pin(async move {
+            let slots = self.client.get_blocks_with_commitment(start_slot.0, Some(end_slot.0), commitment_config(commitment)).await?;
+            Ok(slots.into_iter().map(Slot).collect())
+        })
+    }
+    
+    fn get_slot_leaders(&self, start_slot: Slot, limit: u64) -> BackendFuture<'_, Vec<String>> {
+        Box::pin(async move {
+            let leaders = self.client.get_slot_leaders(start_slot.0, limit).await?;
+            Ok(leaders.iter().map(|leader| leader.to_string()).collect())
+        })
+    }
+    
+    fn get_transaction_slot<'a>(&'a self, signature: &'a str, commitment: Commitment) -> BackendFuture<'a, (Slot, Option<UnixTs>)> {
+        Box::pin(async move {
+            let parsed = Signature::from_str(signature)?;
+            
+            // getSignatureStatuses is cheap, but only knows the slot
+            let statuses = self.client.get_signature_statuses_with_history(&[parsed]).await?;
+            let status_slot = statuses.value.first().and_then(|s| s.as_ref()).map(|s| Slot(s.slot));
+            
+            // getTransaction also gives us the block time, and works when the status cache misses
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(commitment_config(commitment)),
+                max_supported_transaction_version: Some(0),
+            };
+            let transaction = self.client.get_transaction_with_config(&parsed, config).await.ok();
+            let block_time = transaction.as_ref().and_then(|t| t.block_time).map(UnixTs);
+            let transaction_slot = transaction.as_ref().map(|t| Slot(t.slot));
+            
+            match status_slot.or(transaction_slot) {
+                Some(slot) => Ok((slot, block_time)),
+                None => Err(format!("Transaction {} not found", signature).into()),
+            }
+        })
+    }
+    
+    fn get_signatures_for_address<'a>(
+        &'a self,
+        address: &'a str,
+        before: Option<&'a str>,
+        min_context_slot: Option<Slot>,
+        limit: usize,
+        commitment: Commitment,
+    ) -> BackendFuture<'a, Vec<SignatureInfo>> {
+        Box::pin(async move {
+            let address = Pubkey::from_str(address)?;
+            // The typed helper has no minContextSlot, so build the request from the client's config type
+            let config = RpcSignaturesForAddressConfig {
+                before: before.map(String::from),
+                until: None,
+                limit: Some(limit),
+                commitment: Some(commitment_config(commitment)),
+                min_context_slot: min_context_slot.map(u64::from),
+            };
+            let signatures: Vec<RpcConfirmedTransactionStatusWithSignature> = self.client
+                .send(RpcRequest::GetSignaturesForAddress, json!([address.to_string(), config]))
+                .await?;
+            
+            signatures
+                .into_iter()
+                .map(|s| -> Result<SignatureInfo, Box<dyn Error>> {
+                    Ok(SignatureInfo {
+                        signature: s.signature,
+                        slot: Slot(s.slot),
+                        err: s.err.map(serde_json::to_value).transpose()?,
+                        memo: s.memo,
+                        block_time: s.block_time.map(UnixTs),
+                    })
+                })
+                .collect()
+        })
+    }
+    
+    fn get_account_info<'a>(&'a self, address: &'a str, commitment: Commitment) -> BackendFuture<'a, Option<AccountInfo>> {
+        Box::pin(async move {
+            // The typed helpers decode the data, which would lose the parsed form
+            let config = json!({"encoding": "jsonParsed", "commitment": commitment_config(commitment).commitment});
+            let response: Value = self.client.send(RpcRequest::GetAccountInfo, json!([address, config])).await?;
+            Ok(serde_json::from_value(response.get("value").cloned().unwrap_or(Value::Null))?)
+        })
+    }
+    
+    fn get_recent_performance_samples(&self, limit: usize) -> BackendFuture<'_, Vec<PerformanceSample>> {
+        Box::pin(async move {
+            let samples = self.client.get_recent_performance_samples(Some(limit)).await?;
+            Ok(samples
+                .into_iter()
+                .map(|s| PerformanceSample {
+                    slot: Slot(s.slot),
+                    num_slots: s.num_slots,
+                    num_transactions: s.num_transactions,
+                    sample_period_secs: s.sample_period_secs,
+                })
+                .collect())
+        })
+    }
+    
+    fn get_vote_accounts(&self, commitment: Commitment) -> BackendFuture<'_, VoteAccounts> {
+        Box::pin(async move {
+            let status = self.client.get_vote_accounts_with_commitment(commitment_config(commitment)).await?;
+            let convert = |accounts: Vec<RpcVoteAccountInfo>| {
+                accounts
+                    .into_iter()
+                    .map(|a| VoteAccount {
+                        vote_pubkey: a.vote_pubkey,
+                        node_pubkey: a.node_pubkey,
+                        activated_stake: a.activated_stake,
+                        commission: a.commission,
+                        last_vote: Slot(a.last_vote),
+                    })
+                    .collect()
+            };
+            Ok(VoteAccounts { current: convert(status.current), delinquent: convert(status.delinquent) })
+        })
+    }
+}
@@ -0,0 +1,49 @@
+//! Lazy streaming over the blocks of a slot range
+
+use std::error::Error;
+use std::ops::RangeInclusive;
+use futures::future;
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::finder::BlockFinder;
+use crate::types::{Slot, UnixTs};
+
+/// Number of slots requested per `getBlocks` page
+pub const STREAM_PAGE_SLOTS: u64 = 5_000;
+
+/// Number of `getBlockTime` requests a stream keeps in flight
+const STREAM_BLOCK_TIME_CONCURRENCY: usize = 20;
+
+impl BlockFinder {
+    /// Streams `(slot, blockTime)` pairs for every produced block in `range`, in slot order.
+    ///
+    /// Pages are fetched with `getBlocks` only as the consumer polls, so only one page
+    /// of slots is held in memory at a time. Blocks without a block time are skipped.
+    /// A failed block time lookup is yielded as an error and the stream continues;
+    /// a failed `getBlocks` page is yielded as an error and ends the stream.
+    pub fn stream_blocks(&self, range: RangeInclusive<Slot>) -> impl Stream<Item = Result<(Slot, UnixTs), Box<dyn Error>>> + '_ {
+        let (start, end) = range.into_inner();
+        
+        let pages = stream::unfold(Some(start), move |next| async move {
+            let page_start = next.filter(|&slot| slot <= end)?;
+            let page_end = page_start.saturating_add(STREAM_PAGE_SLOTS - 1).min(end);
+            match self.get_blocks(page_start, page_end).await {
+                Ok(slots) => Some((Ok(slots), page_end.checked_add(1))),
+                Err(e) => Some((Err(e), None)),
+            }
+        });
+        
+        pages
+            .map(move |page| match page {
+                Ok(slots) => stream::iter(slots)
+                    .map(move |slot| async move {
+                        self.get_block_time(slot).await.map(|block_time| block_time.map(|time| (slot, time)))
+                    })
+                    .buffered(STREAM_BLOCK_TIME_CONCURRENCY)
+                    .left_stream(),
+                Err(e) => stream::once(future::ready(Err(e))).right_stream(),
+            })
+            .flatten()
+            .filter_map(|item| future::ready(item.transpose()))
+    }
+}
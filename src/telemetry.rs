@@ -0,0 +1,69 @@
+//! OpenTelemetry trace export
+//!
+//! Searches and RPC calls are always instrumented with [`tracing`] spans:
+//! `search` (target timestamp, slot range and the resulting slot) and `rpc`
+//! (method, slot and latency). Services that already install their own
+//! `tracing-opentelemetry` layer get these spans for free.
+//!
+//! With the `otlp` feature, [`init`] installs a subscriber that exports the
+//! spans to an OTLP collector over gRPC, and [`layer`] returns the exporting
+//! layer for a subscriber of your own. Must be called inside a Tokio runtime.
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let _telemetry = solana_block_finder::telemetry::init("http://localhost:4317", "my-indexer")?;
+//! // ... spans are exported in batches until `_telemetry` is dropped
+//! # Ok(())
+//! # }
+//! ```
+
+use std::error::Error;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Layer, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Keeps the exporter running. Dropping it flushes the pending spans.
+pub struct Telemetry {
+    provider: TracerProvider,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            tracing::warn!("Failed to flush traces: {}", e);
+        }
+    }
+}
+
+/// Exports spans to the OTLP gRPC `endpoint` (e.g. `http://localhost:4317`) as `service_name`
+/// and installs the global tracing subscriber. Fails if a subscriber is already installed.
+pub fn init(endpoint: &str, service_name: &str) -> Result<Telemetry, Box<dyn Error>> {
+    let (telemetry, layer) = layer(endpoint, service_name)?;
+    tracing_subscriber::registry().with(layer).try_init()?;
+    Ok(telemetry)
+}
+
+/// Like [`init`], but returns the exporting layer to install next to other layers, e.g. one
+/// printing log events, instead of installing a subscriber
+pub fn layer<S>(endpoint: &str, service_name: &str) -> Result<(Telemetry, impl Layer<S>), Box<dyn Error>>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(endpoint)
+        .build()?;
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::Tokio)
+        .with_resource(Resource::new(vec![KeyValue::new("service.name", service_name.to_string())]))
+        .build();
+    
+    let tracer = provider.tracer("solana-block-finder");
+    Ok((Telemetry { provider }, tracing_opentelemetry::layer().with_tracer(tracer)))
+}
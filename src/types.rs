@@ -0,0 +1,383 @@
+//! Result types shared by the library and the CLI
+
+use std::fmt;
+use std::num::ParseIntError;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::estimate::SlotEstimate;
+use crate::rpc::BlockInfo;
+
+/// A slot number.
+///
+/// Arithmetic is limited to what makes sense for slots: adding or subtracting
+/// a number of slots gives a slot, and subtracting two slots gives the number
+/// of slots between them.
+///
+/// The operators panic on overflow, as they would for `u64` in debug builds but
+/// also in release builds. Use [`Slot::checked_add`] and [`Slot::checked_sub`]
+/// for slots from untrusted input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Slot(pub u64);
+
+impl Slot {
+    pub fn checked_add(self, slots: u64) -> Option<Slot> {
+        self.0.checked_add(slots).map(Slot)
+    }
+    
+    pub fn checked_sub(self, slots: u64) -> Option<Slot> {
+        self.0.checked_sub(slots).map(Slot)
+    }
+    
+    pub fn saturating_add(self, slots: u64) -> Slot {
+        Slot(self.0.saturating_add(slots))
+    }
+    
+    pub fn saturating_sub(self, slots: u64) -> Slot {
+        Slot(self.0.saturating_sub(slots))
+    }
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Slot {
+    type Err = ParseIntError;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Slot)
+    }
+}
+
+impl From<u64> for Slot {
+    fn from(slot: u64) -> Self {
+        Slot(slot)
+    }
+}
+
+impl From<Slot> for u64 {
+    fn from(slot: Slot) -> Self {
+        slot.0
+    }
+}
+
+impl Add<u64> for Slot {
+    type Output = Slot;
+    
+    fn add(self, slots: u64) -> Slot {
+        self.checked_add(slots).expect("slot overflow")
+    }
+}
+
+impl AddAssign<u64> for Slot {
+    fn add_assign(&mut self, slots: u64) {
+        *self = *self + slots;
+    }
+}
+
+impl Sub<u64> for Slot {
+    type Output = Slot;
+    
+    fn sub(self, slots: u64) -> Slot {
+        self.checked_sub(slots).expect("slot underflow")
+    }
+}
+
+impl SubAssign<u64> for Slot {
+    fn sub_assign(&mut self, slots: u64) {
+        *self = *self - slots;
+    }
+}
+
+/// Number of slots from `other` up to `self`
+impl Sub<Slot> for Slot {
+    type Output = u64;
+    
+    fn sub(self, other: Slot) -> u64 {
+        self.0.checked_sub(other.0).expect("slot subtracted from an earlier one")
+    }
+}
+
+/// A Unix timestamp in seconds, as used for block times.
+///
+/// Adding or subtracting seconds gives a timestamp, and subtracting two
+/// timestamps gives the difference in seconds.
+///
+/// The operators panic on overflow, in release builds too. Use
+/// [`UnixTs::checked_add`] and [`UnixTs::checked_sub`] for timestamps or
+/// durations from untrusted input.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UnixTs(pub i64);
+
+impl UnixTs {
+    pub fn checked_add(self, seconds: i64) -> Option<UnixTs> {
+        self.0.checked_add(seconds).map(UnixTs)
+    }
+    
+    pub fn checked_sub(self, seconds: i64) -> Option<UnixTs> {
+        self.0.checked_sub(seconds).map(UnixTs)
+    }
+    
+    pub fn saturating_add(self, seconds: i64) -> UnixTs {
+        UnixTs(self.0.saturating_add(seconds))
+    }
+    
+    pub fn saturating_sub(self, seconds: i64) -> UnixTs {
+        UnixTs(self.0.saturating_sub(seconds))
+    }
+}
+
+impl fmt::Display for UnixTs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for UnixTs {
+    type Err = ParseIntError;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(UnixTs)
+    }
+}
+
+impl From<i64> for UnixTs {
+    fn from(timestamp: i64) -> Self {
+        UnixTs(timestamp)
+    }
+}
+
+impl From<UnixTs> for i64 {
+    fn from(timestamp: UnixTs) -> Self {
+        timestamp.0
+    }
+}
+
+impl Add<i64> for UnixTs {
+    type Output = UnixTs;
+    
+    fn add(self, seconds: i64) -> UnixTs {
+        self.checked_add(seconds).expect("timestamp overflow")
+    }
+}
+
+impl AddAssign<i64> for UnixTs {
+    fn add_assign(&mut self, seconds: i64) {
+        *self = *self + seconds;
+    }
+}
+
+impl Sub<i64> for UnixTs {
+    type Output = UnixTs;
+    
+    fn sub(self, seconds: i64) -> UnixTs {
+        self.checked_sub(seconds).expect("timestamp overflow")
+    }
+}
+
+/// Seconds from `other` to `self`
+impl Sub<UnixTs> for UnixTs {
+    type Output = i64;
+    
+    fn sub(self, other: UnixTs) -> i64 {
+        self.0.checked_sub(other.0).expect("timestamp difference overflow")
+    }
+}
+
+/// A block resolved for a timestamp (or another lookup such as a transaction signature)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockMatch {
+    pub slot: Slot,
+    pub blockhash: String,
+    pub block_time: Option<UnixTs>,
+    pub block_height: Option<u64>,
+    /// The timestamp that was searched for, if the block was found by timestamp
+    pub target_timestamp: Option<UnixTs>,
+    /// `block_time - target_timestamp` in seconds
+    pub time_diff: Option<i64>,
+    /// Whether the block time equals the target timestamp
+    pub exact: bool,
+    /// Set when a limit stopped the search and this is only the closest block found before the target
+    #[serde(default)]
+    pub approximate: bool,
+    /// Whether the block is finalized, `None` if that was not checked. With confirmed
+    /// commitment a recent block may not be yet.
+    #[serde(default)]
+    pub finalized: Option<bool>,
+    /// Set when the target is still in the future and `slot` is only projected from the recent
+    /// slot rate (see [`BlockMatch::projected`]). Such a match has no blockhash, block time or height.
+    #[serde(default)]
+    pub estimated: bool,
+    /// For an estimated match, the lowest and highest slot expected at the target, if every
+    /// slot until then is as slow or as fast as in the slowest and fastest recent minute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate_range: Option<(Slot, Slot)>,
+    /// How the search went, when the block was found by [`crate::BlockFinder::find_block_by_timestamp`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<SearchDiagnostics>,
+}
+
+impl BlockMatch {
+    pub fn new(slot: Slot, block_info: &BlockInfo, target_timestamp: Option<UnixTs>) -> Self {
+        let time_diff = match (block_info.block_time, target_timestamp) {
+            (Some(block_time), Some(target)) => Some(block_time - target),
+            _ => None,
+        };
+        BlockMatch {
+            slot,
+            blockhash: block_info.blockhash.clone(),
+            block_time: block_info.block_time,
+            block_height: block_info.block_height,
+            target_timestamp,
+            time_diff,
+            exact: time_diff == Some(0),
+            approximate: false,
+            finalized: None,
+            estimated: false,
+            estimate_range: None,
+            diagnostics: None,
+        }
+    }
+    
+    /// A match for a slot the chain has not produced yet
+    pub fn projected(estimate: &SlotEstimate) -> Self {
+        BlockMatch {
+            slot: estimate.estimated_slot,
+            blockhash: String::new(),
+            block_time: None,
+            block_height: None,
+            target_timestamp: Some(estimate.target_timestamp),
+            time_diff: None,
+            exact: false,
+            approximate: false,
+            finalized: None,
+            estimated: true,
+            estimate_range: Some((estimate.lowest_slot, estimate.highest_slot)),
+            diagnostics: None,
+        }
+    }
+}
+
+/// Requests a backend sent to one endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointStats {
+    pub url: String,
+    /// Requests sent, including retries
+    pub requests: u64,
+    /// Requests that were retries of a failed attempt
+    pub retries: u64,
+}
+
+/// What a search cost and where it ended, for debugging slow or surprising results.
+///
+/// The counts are taken on the finder, so searches running at the same time on one
+/// finder (or its clones) include each other's calls.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchDiagnostics {
+    /// Block time lookups, including cache hits
+    pub probes: u64,
+    /// Binary search steps
+    pub iterations: u32,
+    /// Block time lookups answered by the cache
+    pub cache_hits: u64,
+    pub rpc_calls: u64,
+    pub retries: u64,
+    /// Endpoints that received requests during the search
+    pub endpoints: Vec<EndpointStats>,
+    /// Slot window left when the binary search ended, crossed (`low > high`) once it converged
+    pub final_low_slot: Slot,
+    pub final_high_slot: Slot,
+    pub duration_ms: u64,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn slot_arithmetic() {
+        assert_eq!(Slot(10) + 5, Slot(15));
+        assert_eq!(Slot(10) - 5, Slot(5));
+        assert_eq!(Slot(15) - Slot(10), 5);
+        let mut slot = Slot(10);
+        slot += 5;
+        slot -= 2;
+        assert_eq!(slot, Slot(13));
+    }
+    
+    #[test]
+    fn checked_slot_arithmetic() {
+        assert_eq!(Slot(u64::MAX - 1).checked_add(1), Some(Slot(u64::MAX)));
+        assert_eq!(Slot(u64::MAX).checked_add(1), None);
+        assert_eq!(Slot(1).checked_sub(1), Some(Slot(0)));
+        assert_eq!(Slot(0).checked_sub(1), None);
+        assert_eq!(Slot(u64::MAX).saturating_add(1), Slot(u64::MAX));
+        assert_eq!(Slot(0).saturating_sub(1), Slot(0));
+    }
+    
+    #[test]
+    #[should_panic(expected = "slot overflow")]
+    fn slot_addition_panics_on_overflow() {
+        let _ = Slot(u64::MAX) + 1;
+    }
+    
+    #[test]
+    #[should_panic(expected = "slot underflow")]
+    fn slot_subtraction_panics_on_underflow() {
+        let _ = Slot(0) - 1;
+    }
+    
+    #[test]
+    #[should_panic(expected = "slot subtracted from an earlier one")]
+    fn slot_distance_panics_when_negative() {
+        let _ = Slot(1) - Slot(2);
+    }
+    
+    #[test]
+    fn timestamp_arithmetic() {
+        assert_eq!(UnixTs(100) + 20, UnixTs(120));
+        assert_eq!(UnixTs(100) + -20, UnixTs(80));
+        assert_eq!(UnixTs(100) - 20, UnixTs(80));
+        assert_eq!(UnixTs(100) - UnixTs(120), -20);
+        let mut timestamp = UnixTs(100);
+        timestamp += 60;
+        assert_eq!(timestamp, UnixTs(160));
+    }
+    
+    #[test]
+    fn checked_timestamp_arithmetic() {
+        assert_eq!(UnixTs(i64::MAX).checked_add(1), None);
+        assert_eq!(UnixTs(i64::MIN).checked_sub(1), None);
+        assert_eq!(UnixTs(i64::MAX).checked_add(-1), Some(UnixTs(i64::MAX - 1)));
+        assert_eq!(UnixTs(i64::MAX).saturating_add(1), UnixTs(i64::MAX));
+        assert_eq!(UnixTs(i64::MIN).saturating_sub(1), UnixTs(i64::MIN));
+    }
+    
+    #[test]
+    #[should_panic(expected = "timestamp overflow")]
+    fn timestamp_addition_panics_on_overflow() {
+        let _ = UnixTs(i64::MAX) + 1;
+    }
+    
+    #[test]
+    #[should_panic(expected = "timestamp difference overflow")]
+    fn timestamp_difference_panics_on_overflow() {
+        let _ = UnixTs(i64::MAX) - UnixTs(-1);
+    }
+    
+    #[test]
+    fn parses_and_displays_plain_numbers() {
+        assert_eq!("432000".parse::<Slot>(), Ok(Slot(432000)));
+        assert_eq!("-5".parse::<UnixTs>(), Ok(UnixTs(-5)));
+        assert!("abc".parse::<Slot>().is_err());
+        assert_eq!(Slot(7).to_string(), "7");
+        assert_eq!(serde_json::to_string(&UnixTs(1600000000)).unwrap(), "1600000000");
+    }
+}
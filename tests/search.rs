@@ -0,0 +1,85 @@
+//! Searches replayed from the cassette in `tests/search.jsonl` on a `FakeClock`
+//!
+//! The cassette was recorded from a small simulated chain producing a block
+//! every 400 ms from Unix time 1600000000 on, with some skipped slots. Tests
+//! with paused time check the finder's waits without really sleeping.
+
+use std::sync::Arc;
+use std::time::Duration;
+use solana_block_finder::cassette::{Cassette, MissingRecording};
+use solana_block_finder::clock::{Clock, FakeClock};
+use solana_block_finder::search::{SearchStopped, StopReason};
+use solana_block_finder::{BlockFinder, BlockFinderBuilder, Slot, UnixTs};
+
+const CASSETTE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/search.jsonl");
+
+// Around the time the cassette was recorded, so the target is in the past
+const NOW: UnixTs = UnixTs(1600400000);
+
+fn replaying(clock: Arc<FakeClock>) -> BlockFinderBuilder {
+    BlockFinder::builder().cassette(Cassette::replay(CASSETTE).unwrap()).clock(clock)
+}
+
+fn replaying_finder() -> BlockFinder {
+    replaying(Arc::new(FakeClock::new(NOW))).build().unwrap()
+}
+
+#[tokio::test]
+async fn finds_the_recorded_block() {
+    let found = replaying_finder().find_block_by_timestamp(UnixTs(1600100000)).await.unwrap();
+    assert_eq!(found.slot, Slot(250002));
+    assert_eq!(found.block_time, Some(UnixTs(1600100000)));
+    assert_eq!(found.blockhash, "hash250002");
+}
+
+#[tokio::test]
+async fn replays_identically() {
+    let first = replaying_finder().find_block_by_timestamp(UnixTs(1600100000)).await.unwrap();
+    let second = replaying_finder().find_block_by_timestamp(UnixTs(1600100000)).await.unwrap();
+    assert_eq!(first.slot, second.slot);
+    assert_eq!(first.block_height, second.block_height);
+}
+
+#[tokio::test]
+async fn fails_on_requests_that_were_not_recorded() {
+    let error = replaying_finder().find_block_by_timestamp(UnixTs(1600300000)).await.unwrap_err();
+    assert!(error.downcast_ref::<MissingRecording>().is_some(), "{}", error);
+}
+
+#[tokio::test(start_paused = true)]
+async fn checks_for_future_timestamps_on_the_finder_clock() {
+    let clock = Arc::new(FakeClock::new(NOW));
+    let finder = replaying(clock.clone()).build().unwrap();
+    assert!(!finder.is_in_future(NOW).unwrap());
+    assert!(finder.is_in_future(NOW + 60).unwrap());
+    
+    clock.advance(Duration::from_secs(60));
+    assert!(!finder.is_in_future(NOW + 60).unwrap());
+}
+
+#[tokio::test(start_paused = true)]
+async fn waits_between_probes_in_paused_time() {
+    let clock = Arc::new(FakeClock::new(NOW));
+    let finder = replaying(clock.clone()).probe_delay(Duration::from_secs(60)).build().unwrap();
+    let started = std::time::Instant::now();
+    let found = finder.find_block_by_timestamp(UnixTs(1600100000)).await.unwrap();
+    assert_eq!(found.slot, Slot(250002));
+    // Every probe after the first waited a minute on the clock, but not in real time
+    assert!(clock.unix_now().unwrap() >= NOW + 10 * 60);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test(start_paused = true)]
+async fn stops_at_the_deadline_in_paused_time() {
+    let clock = Arc::new(FakeClock::new(NOW));
+    let finder = replaying(clock.clone())
+        .probe_delay(Duration::from_secs(60))
+        .deadline(Duration::from_secs(150))
+        .build()
+        .unwrap();
+    let error = finder.find_block_by_timestamp(UnixTs(1600100000)).await.unwrap_err();
+    let stopped = SearchStopped::of(error.as_ref()).unwrap_or_else(|| panic!("{}", error));
+    assert_eq!(stopped.reason, StopReason::Deadline(Duration::from_secs(150)));
+    // Stopped before the probe after the third wait
+    assert_eq!(clock.unix_now().unwrap(), NOW + 180);
+}